@@ -0,0 +1,825 @@
+// Track Your Shit - Offline Advisory Commands
+// Local vulnerability database (OSV / RustSec imports) matched against lockfile inventories
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use crate::commands::dependencies::{AuditResult, VulnerabilityInfo};
+use crate::commands::lockfiles::{build_inventory, InventoryPackage};
+use rusqlite::{params, Connection};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+type DbState = Arc<crate::db::DbPool>;
+
+/// Import errors reported back (the rest are only counted)
+const MAX_REPORTED_ERRORS: usize = 20;
+
+/// An OSV range event; the JSON form (`{"introduced": "0"}`) is OSV's own
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RangeEvent {
+    Introduced(String),
+    Fixed(String),
+    LastAffected(String),
+    Limit(String),
+}
+
+/// Which versions of a package an advisory covers
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Affected {
+    /// OSV: SEMVER/ECOSYSTEM ranges (one event list each) plus listed versions
+    Ranges { ranges: Vec<Vec<RangeEvent>>, versions: Vec<String> },
+    /// RustSec: every version not matching a patched or unaffected requirement
+    Requirements { patched: Vec<String>, unaffected: Vec<String> },
+}
+
+/// One advisory row: an advisory as it applies to one package
+struct AdvisoryRow {
+    id: String,
+    ecosystem: String,
+    package: String,
+    title: String,
+    severity: String,
+    url: Option<String>,
+    aliases: Vec<String>,
+    affected: Affected,
+    source: &'static str,
+    modified: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct AdvisoryImportResult {
+    pub imported: usize,
+    /// Withdrawn, informational or unsupported-ecosystem advisories
+    pub skipped: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct AdvisoryDbStatus {
+    pub ecosystem: String,
+    pub source: String,
+    pub advisories: i64,
+    pub last_imported: Option<String>,
+}
+
+// ----- version comparison -----
+
+/// PEP 440 sort key: (epoch, release, pre, post, dev)
+type Pep440Key = (u64, Vec<u64>, (i8, u64), i64, u64);
+
+fn pep440_key(version: &str) -> Option<Pep440Key> {
+    static RE: OnceLock<regex::Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        regex::Regex::new(
+            r"^v?(?:(\d+)!)?(\d+(?:\.\d+)*)(?:[-_.]?(a|b|c|rc|alpha|beta|pre|preview)[-_.]?(\d*))?(?:-(\d+)|[-_.]?(?:post|rev|r)[-_.]?(\d*))?(?:[-_.]?dev[-_.]?(\d*))?(?:\+.*)?$",
+        )
+        .unwrap()
+    });
+    let version = version.trim().to_lowercase();
+    let caps = re.captures(&version)?;
+    let num = |i: usize| caps.get(i).map(|m| m.as_str().parse::<u64>().unwrap_or(0));
+    let epoch = num(1).unwrap_or(0);
+    let mut release: Vec<u64> = caps[2].split('.').map(|p| p.parse().unwrap_or(0)).collect();
+    while release.len() > 1 && release.last() == Some(&0) {
+        release.pop();
+    }
+    let pre_rank = caps.get(3).map(|m| match m.as_str() {
+        "a" | "alpha" => 0,
+        "b" | "beta" => 1,
+        _ => 2,
+    });
+    let post = num(5).or(num(6));
+    let dev = num(7);
+    let pre = match (pre_rank, post, dev) {
+        (Some(rank), _, _) => (rank, num(4).unwrap_or(0)),
+        // 1.0.dev1 sorts before 1.0a1
+        (None, None, Some(_)) => (-1, 0),
+        _ => (3, 0),
+    };
+    Some((
+        epoch,
+        release,
+        pre,
+        post.map_or(-1, |p| p as i64),
+        dev.unwrap_or(u64::MAX),
+    ))
+}
+
+fn semver_of(version: &str) -> Option<semver::Version> {
+    semver::Version::parse(version.trim().trim_start_matches('v')).ok()
+}
+
+/// Dot-separated numeric comparison for anything else
+fn compare_loose(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|p| !p.is_empty())
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    };
+    parts(a).cmp(&parts(b))
+}
+
+/// Compare versions the way the ecosystem orders them
+fn compare_versions(ecosystem: &str, a: &str, b: &str) -> Ordering {
+    if ecosystem == "pypi" {
+        if let (Some(a), Some(b)) = (pep440_key(a), pep440_key(b)) {
+            return a.cmp(&b);
+        }
+    } else if let (Some(a), Some(b)) = (semver_of(a), semver_of(b)) {
+        return a.cmp_precedence(&b);
+    }
+    compare_loose(a, b)
+}
+
+/// Whether `version` is covered, and the lowest fixed version above it
+fn check_affected(ecosystem: &str, version: &str, affected: &Affected) -> (bool, Option<String>) {
+    match affected {
+        Affected::Ranges { ranges, versions } => {
+            let listed = versions.iter().any(|v| v == version);
+            let mut hit = false;
+            let mut fixes: Vec<&String> = Vec::new();
+            for events in ranges {
+                let at = |e: &RangeEvent| match e {
+                    RangeEvent::Introduced(v) | RangeEvent::Fixed(v) | RangeEvent::LastAffected(v) | RangeEvent::Limit(v) => {
+                        v.clone()
+                    }
+                };
+                let mut sorted: Vec<&RangeEvent> = events.iter().collect();
+                sorted.sort_by(|a, b| match (at(a).as_str(), at(b).as_str()) {
+                    ("0", "0") => Ordering::Equal,
+                    ("0", _) => Ordering::Less,
+                    (_, "0") => Ordering::Greater,
+                    (x, y) => compare_versions(ecosystem, x, y),
+                });
+                let cmp = |v: &str| if v == "0" { Ordering::Greater } else { compare_versions(ecosystem, version, v) };
+                let mut affected = false;
+                for event in sorted {
+                    match event {
+                        RangeEvent::Introduced(v) if cmp(v) != Ordering::Less => affected = true,
+                        RangeEvent::Fixed(v) if cmp(v) != Ordering::Less => affected = false,
+                        RangeEvent::LastAffected(v) if cmp(v) == Ordering::Greater => affected = false,
+                        RangeEvent::Limit(v) if cmp(v) != Ordering::Less => affected = false,
+                        _ => {}
+                    }
+                }
+                if affected {
+                    hit = true;
+                    fixes.extend(events.iter().filter_map(|e| match e {
+                        RangeEvent::Fixed(v) if cmp(v) == Ordering::Less => Some(v),
+                        _ => None,
+                    }));
+                }
+            }
+            fixes.sort_by(|a, b| compare_versions(ecosystem, a, b));
+            (listed || hit, fixes.first().map(|v| v.to_string()))
+        }
+        Affected::Requirements { patched, unaffected } => {
+            let Some(parsed) = semver_of(version) else { return (false, None) };
+            let matches = |req: &String| {
+                semver::VersionReq::parse(req)
+                    .map(|req| req.matches(&parsed))
+                    .unwrap_or(false)
+            };
+            let safe = patched.iter().chain(unaffected.iter()).any(matches);
+            let fixed_in = (!patched.is_empty()).then(|| patched.join(", "));
+            (!safe, fixed_in)
+        }
+    }
+}
+
+// ----- severity -----
+
+/// CVSS v3 base score from a vector (`CVSS:3.1/AV:N/AC:L/...`)
+fn cvss3_score(vector: &str) -> Option<f64> {
+    if !vector.starts_with("CVSS:3") {
+        return None;
+    }
+    let metric = |name: &str| {
+        vector
+            .split('/')
+            .find_map(|part| part.strip_prefix(name)?.strip_prefix(':'))
+    };
+    let changed = metric("S")? == "C";
+    let av: f64 = match metric("AV")? { "N" => 0.85, "A" => 0.62, "L" => 0.55, _ => 0.2 };
+    let ac: f64 = match metric("AC")? { "L" => 0.77, _ => 0.44 };
+    let pr: f64 = match (metric("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        (_, false) => 0.27,
+        (_, true) => 0.5,
+    };
+    let ui: f64 = match metric("UI")? { "N" => 0.85, _ => 0.62 };
+    let cia = |name: &str| -> f64 { match metric(name) { Some("H") => 0.56, Some("L") => 0.22, _ => 0.0 } };
+    let iss = 1.0 - (1.0 - cia("C")) * (1.0 - cia("I")) * (1.0 - cia("A"));
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02).powi(15)
+    } else {
+        6.42 * iss
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let exploitability = 8.22 * av * ac * pr * ui;
+    let base = if changed { 1.08 * (impact + exploitability) } else { impact + exploitability };
+    // CVSS "round up" to one decimal
+    Some((base.min(10.0) * 10.0 - 1e-9).ceil() / 10.0)
+}
+
+fn severity_for_score(score: f64) -> &'static str {
+    match score {
+        s if s >= 9.0 => "critical",
+        s if s >= 7.0 => "high",
+        s if s >= 4.0 => "moderate",
+        _ => "low",
+    }
+}
+
+/// Map a label (GHSA `MODERATE`, `medium`, ...) to our standard set
+fn severity_for_label(label: &str) -> Option<&'static str> {
+    match label.to_lowercase().as_str() {
+        "critical" => Some("critical"),
+        "high" => Some("high"),
+        "moderate" | "medium" => Some("moderate"),
+        "low" => Some("low"),
+        _ => None,
+    }
+}
+
+/// Order of our standard severities, least severe first
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "critical" => 3,
+        "high" => 2,
+        "moderate" => 1,
+        _ => 0,
+    }
+}
+
+// ----- importers -----
+
+/// OSV ecosystem name -> inventory ecosystem
+fn osv_ecosystem(name: &str) -> Option<&'static str> {
+    match name.split(':').next()? {
+        "npm" => Some("npm"),
+        "crates.io" => Some("cargo"),
+        "PyPI" => Some("pypi"),
+        "Go" => Some("go"),
+        _ => None,
+    }
+}
+
+fn normalize_package(ecosystem: &str, name: &str) -> String {
+    if ecosystem == "pypi" {
+        name.to_lowercase().replace(['_', '.'], "-")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Rows for one OSV record, and how many affected entries were skipped
+fn parse_osv(record: &serde_json::Value) -> Result<(Vec<AdvisoryRow>, usize), String> {
+    let id = record.get("id").and_then(|v| v.as_str()).ok_or("OSV record has no id")?;
+    if record.get("withdrawn").is_some_and(|w| !w.is_null()) {
+        return Ok((Vec::new(), 1));
+    }
+    let str_of = |v: &serde_json::Value, key: &str| v.get(key).and_then(|s| s.as_str()).map(|s| s.to_string());
+    let title = str_of(record, "summary")
+        .or_else(|| str_of(record, "details").map(|d| d.lines().next().unwrap_or_default().to_string()))
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| id.to_string());
+    let severity = record
+        .get("database_specific")
+        .and_then(|d| d.get("severity"))
+        .and_then(|s| s.as_str())
+        .and_then(severity_for_label)
+        .or_else(|| {
+            record
+                .get("severity")
+                .and_then(|s| s.as_array())?
+                .iter()
+                .filter_map(|s| str_of(s, "score"))
+                .find_map(|vector| cvss3_score(&vector))
+                .map(severity_for_score)
+        })
+        .unwrap_or("moderate");
+    let references = record.get("references").and_then(|r| r.as_array());
+    let url = references
+        .and_then(|refs| {
+            refs.iter()
+                .find(|r| str_of(r, "type").as_deref() == Some("ADVISORY"))
+                .or_else(|| refs.first())
+        })
+        .and_then(|r| str_of(r, "url"))
+        .or_else(|| Some(format!("https://osv.dev/vulnerability/{}", id)));
+    let aliases: Vec<String> = record
+        .get("aliases")
+        .and_then(|a| serde_json::from_value(a.clone()).ok())
+        .unwrap_or_default();
+
+    let mut rows = Vec::new();
+    let mut skipped = 0;
+    for entry in record.get("affected").and_then(|a| a.as_array()).into_iter().flatten() {
+        let package = entry.get("package");
+        let ecosystem = package.and_then(|p| str_of(p, "ecosystem")).and_then(|e| osv_ecosystem(&e));
+        let (Some(ecosystem), Some(name)) = (ecosystem, package.and_then(|p| str_of(p, "name"))) else {
+            skipped += 1;
+            continue;
+        };
+        let ranges = entry
+            .get("ranges")
+            .and_then(|r| r.as_array())
+            .into_iter()
+            .flatten()
+            .filter(|r| matches!(str_of(r, "type").as_deref(), Some("SEMVER" | "ECOSYSTEM")))
+            .filter_map(|r| serde_json::from_value::<Vec<RangeEvent>>(r.get("events")?.clone()).ok())
+            .collect();
+        let versions = entry
+            .get("versions")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        rows.push(AdvisoryRow {
+            id: id.to_string(),
+            ecosystem: ecosystem.to_string(),
+            package: normalize_package(ecosystem, &name),
+            title: title.clone(),
+            severity: severity.to_string(),
+            url: url.clone(),
+            aliases: aliases.clone(),
+            affected: Affected::Ranges { ranges, versions },
+            source: "osv",
+            modified: str_of(record, "modified"),
+        });
+    }
+    Ok((rows, skipped))
+}
+
+#[derive(serde::Deserialize)]
+struct RustsecFrontMatter {
+    advisory: RustsecAdvisory,
+    #[serde(default)]
+    versions: RustsecVersions,
+}
+
+#[derive(serde::Deserialize)]
+struct RustsecAdvisory {
+    id: String,
+    package: String,
+    url: Option<String>,
+    cvss: Option<String>,
+    informational: Option<String>,
+    withdrawn: Option<toml::Value>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    date: Option<toml::Value>,
+}
+
+#[derive(serde::Deserialize, Default)]
+struct RustsecVersions {
+    #[serde(default)]
+    patched: Vec<String>,
+    #[serde(default)]
+    unaffected: Vec<String>,
+}
+
+/// A RustSec advisory: TOML front matter in a ```toml fence, then `# Title`.
+/// None for informational (unmaintained, unsound, ...) and withdrawn advisories.
+fn parse_rustsec(markdown: &str) -> Result<Option<AdvisoryRow>, String> {
+    let body = markdown.trim_start().strip_prefix("```toml").ok_or("Missing ```toml front matter")?;
+    let (front, rest) = body.split_once("\n```").ok_or("Unterminated front matter")?;
+    let parsed: RustsecFrontMatter = toml::from_str(front).map_err(|e| e.to_string())?;
+    let advisory = parsed.advisory;
+    if advisory.informational.is_some() || advisory.withdrawn.is_some() {
+        return Ok(None);
+    }
+    let title = rest
+        .lines()
+        .find_map(|l| l.strip_prefix("# "))
+        .map(|t| t.trim().to_string())
+        .unwrap_or_else(|| advisory.id.clone());
+    let severity = advisory
+        .cvss
+        .as_deref()
+        .and_then(cvss3_score)
+        .map_or("moderate", severity_for_score);
+    Ok(Some(AdvisoryRow {
+        url: advisory
+            .url
+            .or_else(|| Some(format!("https://rustsec.org/advisories/{}", advisory.id))),
+        id: advisory.id,
+        ecosystem: "cargo".to_string(),
+        package: advisory.package,
+        title,
+        severity: severity.to_string(),
+        aliases: advisory.aliases,
+        affected: Affected::Requirements {
+            patched: parsed.versions.patched,
+            unaffected: parsed.versions.unaffected,
+        },
+        source: "rustsec",
+        modified: advisory.date.map(|d| d.to_string().trim_matches('"').to_string()),
+    }))
+}
+
+/// OSV `.json` records (single or arrays) and RustSec `.md` advisories under `path`
+fn collect_advisories(path: &Path, rows: &mut Vec<AdvisoryRow>, result: &mut AdvisoryImportResult) {
+    if path.is_dir() {
+        let Ok(entries) = std::fs::read_dir(path) else { return };
+        for entry in entries.flatten() {
+            if !entry.file_name().to_string_lossy().starts_with('.') {
+                collect_advisories(&entry.path(), rows, result);
+            }
+        }
+        return;
+    }
+    let fail = |result: &mut AdvisoryImportResult, e: String| {
+        result.failed += 1;
+        if result.errors.len() < MAX_REPORTED_ERRORS {
+            result.errors.push(format!("{}: {}", path.display(), e));
+        }
+    };
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => {
+            let parsed = std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).map_err(|e| e.to_string()));
+            let records = match parsed {
+                Ok(serde_json::Value::Array(records)) => records,
+                Ok(record) => vec![record],
+                Err(e) => return fail(result, e),
+            };
+            for record in &records {
+                match parse_osv(record) {
+                    Ok((parsed, skipped)) => {
+                        result.skipped += skipped;
+                        rows.extend(parsed);
+                    }
+                    Err(e) => fail(result, e),
+                }
+            }
+        }
+        // advisory-db keeps advisories under crates/<name>/RUSTSEC-*.md
+        Some("md") if path.file_name().is_some_and(|n| n.to_string_lossy().starts_with("RUSTSEC-")) => {
+            match std::fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|c| parse_rustsec(&c)) {
+                Ok(Some(row)) => rows.push(row),
+                Ok(None) => result.skipped += 1,
+                Err(e) => fail(result, e),
+            }
+        }
+        _ => {}
+    }
+}
+
+// ----- matching -----
+
+/// Match inventory packages against the advisories stored for them
+fn match_packages(conn: &Connection, ecosystem: &str, packages: &[&InventoryPackage]) -> Result<AuditResult, String> {
+    let label = if ecosystem == "pypi" { "pip" } else { ecosystem };
+    let known: i64 = conn
+        .query_row("SELECT COUNT(*) FROM advisories WHERE ecosystem = ?1", params![ecosystem], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if known == 0 {
+        return Ok(AuditResult::with_error(
+            label,
+            format!("No {} advisories imported. Import an OSV dump or advisory-db first.", label),
+        ));
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT id, title, severity, url, aliases, affected FROM advisories WHERE ecosystem = ?1 AND package = ?2")
+        .map_err(|e| e.to_string())?;
+    let mut vulnerabilities: Vec<VulnerabilityInfo> = Vec::new();
+    for pkg in packages {
+        let rows = stmt
+            .query_map(params![ecosystem, normalize_package(ecosystem, &pkg.name)], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, String>(5)?,
+                ))
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        let mut hits = Vec::new();
+        for (id, title, severity, url, aliases, affected) in rows {
+            let Ok(affected) = serde_json::from_str::<Affected>(&affected) else { continue };
+            let (hit, fixed_in) = check_affected(ecosystem, &pkg.version, &affected);
+            if hit {
+                let aliases: Vec<String> =
+                    aliases.and_then(|a| serde_json::from_str(&a).ok()).unwrap_or_default();
+                hits.push((
+                    aliases,
+                    VulnerabilityInfo {
+                        id,
+                        package: pkg.name.clone(),
+                        severity,
+                        title,
+                        url,
+                        fixable: fixed_in.is_some(),
+                        fixed_in,
+                    },
+                ));
+            }
+        }
+        vulnerabilities.extend(merge_aliased(hits));
+    }
+
+    Ok(AuditResult {
+        vulnerabilities,
+        audit_ran: true,
+        ..AuditResult::empty(label)
+    }
+    .tally())
+}
+
+/// Collapse one package's matches that are the same vulnerability under
+/// different IDs (a GHSA record and its PYSEC/RUSTSEC alias), keeping the
+/// most severe record
+fn merge_aliased(hits: Vec<(Vec<String>, VulnerabilityInfo)>) -> Vec<VulnerabilityInfo> {
+    let mut merged: Vec<(HashSet<String>, VulnerabilityInfo)> = Vec::new();
+    for (aliases, vuln) in hits {
+        let mut ids: HashSet<String> = aliases.into_iter().collect();
+        ids.insert(vuln.id.clone());
+        match merged.iter_mut().find(|(known, _)| !known.is_disjoint(&ids)) {
+            Some((known, kept)) => {
+                known.extend(ids);
+                if severity_rank(&vuln.severity) > severity_rank(&kept.severity) {
+                    *kept = vuln;
+                }
+            }
+            None => merged.push((ids, vuln)),
+        }
+    }
+    merged.into_iter().map(|(_, vuln)| vuln).collect()
+}
+
+/// Replace each imported advisory's rows, so packages a newer version of
+/// the record no longer lists don't keep matching
+fn write_advisories(conn: &Connection, rows: &[AdvisoryRow]) -> Result<(), String> {
+    let mut delete = conn
+        .prepare("DELETE FROM advisories WHERE id = ?1")
+        .map_err(|e| e.to_string())?;
+    let mut cleared = HashSet::new();
+    for row in rows {
+        if cleared.insert(row.id.as_str()) {
+            delete.execute(params![row.id]).map_err(|e| e.to_string())?;
+        }
+    }
+    let mut insert = conn
+        .prepare(
+            "INSERT OR REPLACE INTO advisories
+                (id, ecosystem, package, title, severity, url, aliases, affected, source, modified)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )
+        .map_err(|e| e.to_string())?;
+    for row in rows {
+        let affected = serde_json::to_string(&row.affected).map_err(|e| e.to_string())?;
+        let aliases = serde_json::to_string(&row.aliases).map_err(|e| e.to_string())?;
+        insert
+            .execute(params![
+                row.id,
+                row.ecosystem,
+                row.package,
+                row.title,
+                row.severity,
+                row.url,
+                aliases,
+                affected,
+                row.source,
+                row.modified,
+            ])
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// ----- commands -----
+
+/// Import advisories from a file or directory: an extracted OSV dump (one
+/// JSON record per file, e.g. the per-ecosystem `all.zip` from
+/// osv-vulnerabilities), individual OSV JSON files, or a clone of the RustSec
+/// advisory-db. Existing advisories with the same ID are replaced.
+#[tauri::command]
+pub async fn import_advisories(
+    db: tauri::State<'_, DbState>,
+    path: String,
+) -> Result<AdvisoryImportResult, String> {
+    if !Path::new(&path).exists() {
+        return Err(format!("{} does not exist", path));
+    }
+    let (rows, mut result) = tauri::async_runtime::spawn_blocking(move || {
+        let mut rows = Vec::new();
+        let mut result = AdvisoryImportResult {
+            imported: 0,
+            skipped: 0,
+            failed: 0,
+            errors: Vec::new(),
+        };
+        collect_advisories(Path::new(&path), &mut rows, &mut result);
+        (rows, result)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let db = db.write().await;
+    let conn = db.conn();
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    match write_advisories(conn, &rows) {
+        Ok(()) => conn.execute_batch("COMMIT").map_err(|e| e.to_string())?,
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+    }
+
+    result.imported = rows.len();
+    tracing::info!(
+        "Imported {} advisories ({} skipped, {} failed)",
+        result.imported,
+        result.skipped,
+        result.failed
+    );
+    Ok(result)
+}
+
+/// Advisory counts per ecosystem and source
+#[tauri::command]
+pub async fn get_advisory_db_status(
+    db: tauri::State<'_, DbState>,
+) -> Result<Vec<AdvisoryDbStatus>, String> {
+    let conn = db.read().await;
+    let mut stmt = conn
+        .prepare(
+            "SELECT ecosystem, source, COUNT(*), MAX(imported_at)
+             FROM advisories
+             GROUP BY ecosystem, source
+             ORDER BY ecosystem, source",
+        )
+        .map_err(|e| e.to_string())?;
+    let status = stmt
+        .query_map([], |row| {
+            Ok(AdvisoryDbStatus {
+                ecosystem: row.get(0)?,
+                source: row.get(1)?,
+                advisories: row.get(2)?,
+                last_imported: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(status)
+}
+
+/// Delete imported advisories (all, or one ecosystem's)
+#[tauri::command]
+pub async fn clear_advisories(
+    db: tauri::State<'_, DbState>,
+    ecosystem: Option<String>,
+) -> Result<usize, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+    let deleted = match ecosystem {
+        Some(ecosystem) => conn.execute("DELETE FROM advisories WHERE ecosystem = ?1", params![ecosystem]),
+        None => conn.execute("DELETE FROM advisories", []),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(deleted)
+}
+
+/// Audit the project's lockfiles against the local advisory database.
+/// Returns one AuditResult per ecosystem, like `run_dependency_audit`, without
+/// running any package manager or touching the network.
+#[tauri::command]
+pub async fn run_offline_audit(
+    db: tauri::State<'_, DbState>,
+    project_path: String,
+) -> Result<Vec<AuditResult>, String> {
+    let inventory = tauri::async_runtime::spawn_blocking(move || build_inventory(&project_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    if inventory.lockfiles.is_empty() {
+        return Err(match inventory.errors.first() {
+            Some(error) => format!("No lockfile could be read ({})", error),
+            None => "No supported lockfile found".to_string(),
+        });
+    }
+
+    let conn = db.read().await;
+    let mut results = Vec::new();
+    for ecosystem in ["npm", "cargo", "pypi", "go"] {
+        let packages: Vec<&InventoryPackage> =
+            inventory.packages.iter().filter(|p| p.ecosystem == ecosystem).collect();
+        if !packages.is_empty() {
+            results.push(match_packages(&conn, ecosystem, &packages)?);
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_versions_and_checks_ranges() {
+        assert_eq!(compare_versions("npm", "1.10.0", "1.9.0"), Ordering::Greater);
+        assert_eq!(compare_versions("npm", "2.0.0-beta.1", "2.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("go", "v0.14.0", "0.14.0"), Ordering::Equal);
+        for (lower, higher) in [("1.0.dev1", "1.0a1"), ("1.0a1", "1.0rc1"), ("1.0rc1", "1.0"), ("1.0", "1.0.post1"), ("2.9", "2.10")] {
+            assert_eq!(compare_versions("pypi", lower, higher), Ordering::Less, "{} < {}", lower, higher);
+        }
+        assert_eq!(compare_versions("pypi", "1.0", "1.0.0"), Ordering::Equal);
+
+        let osv: Affected = serde_json::from_value(serde_json::json!({
+            "kind": "ranges",
+            "ranges": [
+                [{ "introduced": "0" }, { "fixed": "4.17.21" }],
+                [{ "introduced": "5.0.0" }, { "last_affected": "5.0.2" }]
+            ],
+            "versions": ["6.0.0-rc.1"]
+        }))
+        .unwrap();
+        assert_eq!(check_affected("npm", "4.17.20", &osv), (true, Some("4.17.21".to_string())));
+        assert!(!check_affected("npm", "4.17.21", &osv).0);
+        assert_eq!(check_affected("npm", "5.0.2", &osv), (true, None));
+        assert!(!check_affected("npm", "5.0.3", &osv).0);
+        assert!(check_affected("npm", "6.0.0-rc.1", &osv).0);
+
+        let rustsec = Affected::Requirements {
+            patched: vec![">= 1.2.3, < 2.0.0".to_string(), ">= 2.0.1".to_string()],
+            unaffected: vec!["< 1.0.0".to_string()],
+        };
+        assert!(check_affected("cargo", "1.2.0", &rustsec).0);
+        assert!(check_affected("cargo", "2.0.0", &rustsec).0);
+        assert!(!check_affected("cargo", "1.2.4", &rustsec).0);
+        assert!(!check_affected("cargo", "0.9.0", &rustsec).0);
+    }
+
+    #[test]
+    fn parses_osv_and_rustsec_advisories() {
+        let record = serde_json::json!({
+            "id": "GHSA-jf85-cpcp-j695",
+            "modified": "2024-01-01T00:00:00Z",
+            "summary": "Prototype Pollution in lodash",
+            "aliases": ["CVE-2019-10744"],
+            "database_specific": { "severity": "CRITICAL" },
+            "references": [{ "type": "WEB", "url": "https://example.com" }, { "type": "ADVISORY", "url": "https://nvd.nist.gov/vuln/detail/CVE-2019-10744" }],
+            "affected": [
+                { "package": { "ecosystem": "npm", "name": "lodash" }, "ranges": [{ "type": "SEMVER", "events": [{ "introduced": "0" }, { "fixed": "4.17.12" }] }] },
+                { "package": { "ecosystem": "Maven", "name": "org.webjars:lodash" } }
+            ]
+        });
+        let (rows, skipped) = parse_osv(&record).unwrap();
+        assert_eq!((rows.len(), skipped), (1, 1));
+        assert_eq!(rows[0].severity, "critical");
+        assert_eq!(rows[0].url.as_deref(), Some("https://nvd.nist.gov/vuln/detail/CVE-2019-10744"));
+        assert!(check_affected("npm", "4.17.11", &rows[0].affected).0);
+
+        let markdown = "```toml\n[advisory]\nid = \"RUSTSEC-2021-0078\"\npackage = \"hyper\"\ndate = \"2021-07-07\"\n\
+                        cvss = \"CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:H/A:N\"\naliases = [\"CVE-2021-32715\"]\n\n\
+                        [versions]\npatched = [\">= 0.14.10\"]\n```\n\n# Lenient `hyper` header parsing of `Content-Length`\n\nDetails.\n";
+        let row = parse_rustsec(markdown).unwrap().unwrap();
+        assert_eq!(row.title, "Lenient `hyper` header parsing of `Content-Length`");
+        assert_eq!(row.severity, "high");
+        assert_eq!(row.modified.as_deref(), Some("2021-07-07"));
+        assert!(check_affected("cargo", "0.14.9", &row.affected).0);
+
+        let unmaintained = markdown.replace("[versions]", "informational = \"unmaintained\"\n[versions]");
+        assert!(parse_rustsec(&unmaintained).unwrap().is_none());
+
+        assert_eq!(cvss3_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"), Some(9.8));
+        assert_eq!(cvss3_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N"), Some(6.1));
+    }
+
+    #[test]
+    fn merges_aliased_advisories() {
+        let vuln = |id: &str, severity: &str| VulnerabilityInfo {
+            id: id.to_string(),
+            package: "jinja2".to_string(),
+            severity: severity.to_string(),
+            title: "Sandbox escape".to_string(),
+            url: None,
+            fixable: false,
+            fixed_in: None,
+        };
+        let aliases = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let merged = merge_aliased(vec![
+            (aliases(&["CVE-2024-1"]), vuln("PYSEC-2024-1", "moderate")),
+            (aliases(&["CVE-2024-1", "PYSEC-2024-1"]), vuln("GHSA-aaaa", "high")),
+            (aliases(&["CVE-2024-2"]), vuln("GHSA-bbbb", "low")),
+        ]);
+        let kept: Vec<_> = merged.iter().map(|v| (v.id.as_str(), v.severity.as_str())).collect();
+        assert_eq!(kept, [("GHSA-aaaa", "high"), ("GHSA-bbbb", "low")]);
+    }
+}
@@ -0,0 +1,502 @@
+// Track Your Shit - Dependency Audit History
+// Snapshots of every dependency audit, diffed into vulnerability and package changes
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use crate::commands::dependencies::{AuditResult, VulnerabilityInfo};
+use crate::commands::notifications::insert_notification;
+use crate::models::{CreateNotificationInput, Notification};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+type DbState = Arc<crate::db::DbPool>;
+
+/// Snapshots kept per project; older ones are pruned
+const MAX_SNAPSHOTS_PER_PROJECT: i64 = 200;
+
+/// New vulnerabilities named in the notification message
+const NOTIFICATION_VULNERABILITIES: usize = 3;
+
+/// A locked package as recorded in a snapshot
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SnapshotPackage {
+    pub ecosystem: String,
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct AuditSnapshot {
+    pub id: String,
+    pub project_id: String,
+    pub total: i64,
+    pub critical: i64,
+    pub high: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct VulnerabilityChange {
+    pub ecosystem: String,
+    pub vulnerability: VulnerabilityInfo,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct SeverityChange {
+    pub ecosystem: String,
+    pub id: String,
+    pub package: String,
+    pub title: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// A package added (no `from`), removed (no `to`) or bumped. Versions are
+/// comma-joined when a lockfile holds several copies of a package.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct PackageChange {
+    pub ecosystem: String,
+    pub name: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct AuditDiff {
+    pub base_id: String,
+    pub head_id: String,
+    pub new_vulnerabilities: Vec<VulnerabilityChange>,
+    pub fixed_vulnerabilities: Vec<VulnerabilityChange>,
+    pub severity_changes: Vec<SeverityChange>,
+    pub added_packages: Vec<PackageChange>,
+    pub removed_packages: Vec<PackageChange>,
+    pub bumped_packages: Vec<PackageChange>,
+    /// Ecosystems whose audit failed in either snapshot, so weren't compared
+    pub skipped_ecosystems: Vec<String>,
+}
+
+/// Vulnerabilities keyed by (ecosystem, advisory ID, package), for ecosystems whose audit ran
+fn vulnerability_map(results: &[AuditResult]) -> BTreeMap<(String, String, String), &VulnerabilityInfo> {
+    results
+        .iter()
+        .filter(|r| r.audit_ran)
+        .flat_map(|r| {
+            r.vulnerabilities
+                .iter()
+                .map(move |v| ((r.ecosystem.clone(), v.id.clone(), v.package.clone()), v))
+        })
+        .collect()
+}
+
+/// Versions of each (ecosystem, name), sorted and joined
+fn package_map(packages: &[SnapshotPackage]) -> BTreeMap<(String, String), String> {
+    let mut versions: BTreeMap<(String, String), Vec<&str>> = BTreeMap::new();
+    for pkg in packages {
+        versions
+            .entry((pkg.ecosystem.clone(), pkg.name.clone()))
+            .or_default()
+            .push(&pkg.version);
+    }
+    versions
+        .into_iter()
+        .map(|(key, mut v)| {
+            v.sort_unstable();
+            v.dedup();
+            (key, v.join(", "))
+        })
+        .collect()
+}
+
+fn diff_snapshots(
+    base: (&str, &[AuditResult], &[SnapshotPackage]),
+    head: (&str, &[AuditResult], &[SnapshotPackage]),
+) -> AuditDiff {
+    let (base_id, base_results, base_packages) = base;
+    let (head_id, head_results, head_packages) = head;
+
+    // Skip ecosystems whose audit failed on either side; a failed audit would
+    // otherwise read as every vulnerability being fixed. An ecosystem missing
+    // from one side (a lockfile added or removed) compares as empty.
+    let failed = |results: &[AuditResult], ecosystem: &str| {
+        results.iter().any(|r| r.ecosystem == ecosystem && !r.audit_ran)
+    };
+    let mut skipped_ecosystems: Vec<String> = base_results
+        .iter()
+        .chain(head_results)
+        .map(|r| r.ecosystem.clone())
+        .filter(|e| failed(base_results, e) || failed(head_results, e))
+        .collect();
+    skipped_ecosystems.sort();
+    skipped_ecosystems.dedup();
+
+    let compared = |key: &(String, String, String)| !skipped_ecosystems.contains(&key.0);
+    let before = vulnerability_map(base_results);
+    let after = vulnerability_map(head_results);
+    let change = |key: &(String, String, String), v: &VulnerabilityInfo| VulnerabilityChange {
+        ecosystem: key.0.clone(),
+        vulnerability: v.clone(),
+    };
+
+    let new_vulnerabilities = after
+        .iter()
+        .filter(|(key, _)| compared(key) && !before.contains_key(*key))
+        .map(|(key, v)| change(key, v))
+        .collect();
+    let fixed_vulnerabilities = before
+        .iter()
+        .filter(|(key, _)| compared(key) && !after.contains_key(*key))
+        .map(|(key, v)| change(key, v))
+        .collect();
+    let severity_changes = after
+        .iter()
+        .filter_map(|(key, v)| {
+            let old = before.get(key).filter(|old| old.severity != v.severity)?;
+            Some(SeverityChange {
+                ecosystem: key.0.clone(),
+                id: v.id.clone(),
+                package: v.package.clone(),
+                title: v.title.clone(),
+                from: old.severity.clone(),
+                to: v.severity.clone(),
+            })
+        })
+        .collect();
+
+    let before = package_map(base_packages);
+    let after = package_map(head_packages);
+    let package_change = |(ecosystem, name): &(String, String), from: Option<&String>, to: Option<&String>| PackageChange {
+        ecosystem: ecosystem.clone(),
+        name: name.clone(),
+        from: from.cloned(),
+        to: to.cloned(),
+    };
+    let added_packages = after
+        .iter()
+        .filter(|(key, _)| !before.contains_key(*key))
+        .map(|(key, to)| package_change(key, None, Some(to)))
+        .collect();
+    let removed_packages = before
+        .iter()
+        .filter(|(key, _)| !after.contains_key(*key))
+        .map(|(key, from)| package_change(key, Some(from), None))
+        .collect();
+    let bumped_packages = after
+        .iter()
+        .filter_map(|(key, to)| {
+            let from = before.get(key).filter(|from| *from != to)?;
+            Some(package_change(key, Some(from), Some(to)))
+        })
+        .collect();
+
+    AuditDiff {
+        base_id: base_id.to_string(),
+        head_id: head_id.to_string(),
+        new_vulnerabilities,
+        fixed_vulnerabilities,
+        severity_changes,
+        added_packages,
+        removed_packages,
+        bumped_packages,
+        skipped_ecosystems,
+    }
+}
+
+type SnapshotData = (String, Vec<AuditResult>, Vec<SnapshotPackage>);
+
+fn load_snapshot(conn: &Connection, snapshot_id: &str) -> Result<Option<SnapshotData>, String> {
+    let row: Option<(String, String, String)> = conn
+        .query_row(
+            "SELECT project_id, results, packages FROM dependency_audit_snapshots WHERE id = ?1",
+            params![snapshot_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let Some((project_id, results, packages)) = row else {
+        return Ok(None);
+    };
+    Ok(Some((
+        project_id,
+        serde_json::from_str(&results).map_err(|e| e.to_string())?,
+        serde_json::from_str(&packages).map_err(|e| e.to_string())?,
+    )))
+}
+
+/// Persist an audit run for a project and diff it against the previous run.
+/// Returns a notification when the locked packages changed and the run found
+/// new high or critical vulnerabilities; the caller emits it. The snapshot,
+/// the prune and the notification are written in one transaction.
+pub(crate) fn record_audit_snapshot(
+    conn: &Connection,
+    project_id: &str,
+    results: &[AuditResult],
+    packages: &[SnapshotPackage],
+) -> Result<Option<Notification>, String> {
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    match write_audit_snapshot(conn, project_id, results, packages) {
+        Ok(notification) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            Ok(notification)
+        }
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            Err(e)
+        }
+    }
+}
+
+fn write_audit_snapshot(
+    conn: &Connection,
+    project_id: &str,
+    results: &[AuditResult],
+    packages: &[SnapshotPackage],
+) -> Result<Option<Notification>, String> {
+    let previous: Option<String> = conn
+        .query_row(
+            "SELECT id FROM dependency_audit_snapshots WHERE project_id = ?1
+             ORDER BY created_at DESC, rowid DESC LIMIT 1",
+            params![project_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let snapshot_id = format!("{:032x}", rand::random::<u128>());
+    let count = |severity: &str| -> usize {
+        results
+            .iter()
+            .map(|r| r.vulnerabilities.iter().filter(|v| v.severity == severity).count())
+            .sum()
+    };
+    let total: usize = results.iter().map(|r| r.vulnerabilities.len()).sum();
+    conn.execute(
+        "INSERT INTO dependency_audit_snapshots (id, project_id, results, packages, total, critical, high)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            snapshot_id,
+            project_id,
+            serde_json::to_string(results).map_err(|e| e.to_string())?,
+            serde_json::to_string(packages).map_err(|e| e.to_string())?,
+            total as i64,
+            count("critical") as i64,
+            count("high") as i64,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM dependency_audit_snapshots
+         WHERE project_id = ?1 AND id NOT IN (
+             SELECT id FROM dependency_audit_snapshots WHERE project_id = ?1
+             ORDER BY created_at DESC, rowid DESC LIMIT ?2
+         )",
+        params![project_id, MAX_SNAPSHOTS_PER_PROJECT],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let Some((_, base_results, base_packages)) = previous
+        .as_deref()
+        .map(|id| load_snapshot(conn, id))
+        .transpose()?
+        .flatten()
+    else {
+        return Ok(None);
+    };
+    let diff = diff_snapshots(
+        (previous.as_deref().unwrap_or_default(), &base_results, &base_packages),
+        (&snapshot_id, results, packages),
+    );
+    // Only vulnerabilities in packages this change added or bumped; audit
+    // results label PyPI as `pip`, lockfile inventories as `pypi`
+    let package_key = |ecosystem: &str, name: &str| {
+        let ecosystem = if ecosystem == "pip" { "pypi" } else { ecosystem };
+        (ecosystem.to_string(), name.to_lowercase())
+    };
+    let changed: HashSet<(String, String)> = diff
+        .added_packages
+        .iter()
+        .chain(&diff.bumped_packages)
+        .map(|p| package_key(&p.ecosystem, &p.name))
+        .collect();
+    let serious: Vec<&VulnerabilityChange> = diff
+        .new_vulnerabilities
+        .iter()
+        .filter(|v| matches!(v.vulnerability.severity.as_str(), "critical" | "high"))
+        .filter(|v| changed.contains(&package_key(&v.ecosystem, &v.vulnerability.package)))
+        .collect();
+    if serious.is_empty() {
+        return Ok(None);
+    }
+
+    let enabled = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = 'notifications_enabled'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .is_none_or(|v| v == "true");
+    if !enabled {
+        return Ok(None);
+    }
+
+    let named: Vec<String> = serious
+        .iter()
+        .take(NOTIFICATION_VULNERABILITIES)
+        .map(|v| format!("{} in {} ({})", v.vulnerability.id, v.vulnerability.package, v.vulnerability.severity))
+        .collect();
+    let mut message = format!(
+        "The latest dependency changes introduced {} new high or critical {}: {}",
+        serious.len(),
+        if serious.len() == 1 { "vulnerability" } else { "vulnerabilities" },
+        named.join(", ")
+    );
+    if serious.len() > named.len() {
+        message.push_str(&format!(" and {} more", serious.len() - named.len()));
+    }
+    message.push('.');
+
+    insert_notification(
+        conn,
+        &CreateNotificationInput {
+            project_id: Some(project_id.to_string()),
+            notification_type: "error".to_string(),
+            title: "New vulnerable dependencies".to_string(),
+            message,
+            link: Some(format!("/projects/{}", project_id)),
+        },
+    )
+    .map(Some)
+}
+
+/// Audit snapshots for a project, newest first
+#[tauri::command]
+pub async fn list_audit_snapshots(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    limit: Option<i64>,
+) -> Result<Vec<AuditSnapshot>, String> {
+    let conn = db.read().await;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, project_id, total, critical, high, created_at
+             FROM dependency_audit_snapshots
+             WHERE project_id = ?1
+             ORDER BY created_at DESC, rowid DESC
+             LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let snapshots = stmt
+        .query_map(params![project_id, limit.unwrap_or(50)], |row| {
+            Ok(AuditSnapshot {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                total: row.get(2)?,
+                critical: row.get(3)?,
+                high: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(snapshots)
+}
+
+/// Diff two audit snapshots of the same project: vulnerabilities introduced,
+/// fixed or re-rated, and packages added, removed or bumped from base to head
+#[tauri::command]
+pub async fn diff_audit_snapshots(
+    db: tauri::State<'_, DbState>,
+    base_id: String,
+    head_id: String,
+) -> Result<AuditDiff, String> {
+    let conn = db.read().await;
+    let (base_project, base_results, base_packages) =
+        load_snapshot(&conn, &base_id)?.ok_or_else(|| format!("Audit snapshot {} not found", base_id))?;
+    let (head_project, head_results, head_packages) =
+        load_snapshot(&conn, &head_id)?.ok_or_else(|| format!("Audit snapshot {} not found", head_id))?;
+    if base_project != head_project {
+        return Err("Audit snapshots belong to different projects".to_string());
+    }
+    Ok(diff_snapshots(
+        (&base_id, &base_results, &base_packages),
+        (&head_id, &head_results, &head_packages),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vuln(id: &str, package: &str, severity: &str) -> VulnerabilityInfo {
+        VulnerabilityInfo {
+            id: id.to_string(),
+            package: package.to_string(),
+            severity: severity.to_string(),
+            title: id.to_string(),
+            url: None,
+            fixable: false,
+            fixed_in: None,
+        }
+    }
+
+    fn audit(ecosystem: &str, vulnerabilities: Vec<VulnerabilityInfo>) -> AuditResult {
+        AuditResult {
+            vulnerabilities,
+            audit_ran: true,
+            ..AuditResult::empty(ecosystem)
+        }
+        .tally()
+    }
+
+    fn pkg(ecosystem: &str, name: &str, version: &str) -> SnapshotPackage {
+        SnapshotPackage {
+            ecosystem: ecosystem.to_string(),
+            name: name.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn diffs_vulnerabilities_and_packages() {
+        let base_results = vec![
+            audit("npm", vec![vuln("GHSA-1", "lodash", "high"), vuln("GHSA-2", "minimist", "low")]),
+            audit("cargo", vec![vuln("RUSTSEC-1", "hyper", "high")]),
+        ];
+        let head_results = vec![
+            audit("npm", vec![vuln("GHSA-2", "minimist", "critical"), vuln("GHSA-3", "axios", "high")]),
+            AuditResult::with_error("cargo", "cargo-audit not installed"),
+            audit("go", vec![vuln("GO-1", "golang.org/x/net", "high")]),
+        ];
+        let base_packages = vec![
+            pkg("npm", "lodash", "4.17.20"),
+            pkg("npm", "minimist", "1.2.5"),
+            pkg("npm", "debug", "2.6.9"),
+            pkg("npm", "debug", "4.3.4"),
+        ];
+        let head_packages = vec![
+            pkg("npm", "minimist", "1.2.5"),
+            pkg("npm", "debug", "4.3.4"),
+            pkg("npm", "debug", "4.3.5"),
+            pkg("npm", "axios", "1.6.0"),
+        ];
+
+        let diff = diff_snapshots(("a", &base_results, &base_packages), ("b", &head_results, &head_packages));
+        let ids = |changes: &[VulnerabilityChange]| {
+            changes.iter().map(|c| c.vulnerability.id.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&diff.new_vulnerabilities), ["GO-1", "GHSA-3"]);
+        assert_eq!(ids(&diff.fixed_vulnerabilities), ["GHSA-1"]);
+        assert_eq!(diff.skipped_ecosystems, ["cargo"]);
+        assert_eq!(diff.severity_changes.len(), 1);
+        assert_eq!((diff.severity_changes[0].from.as_str(), diff.severity_changes[0].to.as_str()), ("low", "critical"));
+
+        assert_eq!(diff.added_packages.len(), 1);
+        assert_eq!(diff.added_packages[0].name, "axios");
+        assert_eq!(diff.removed_packages[0].from.as_deref(), Some("4.17.20"));
+        assert_eq!(diff.bumped_packages.len(), 1);
+        assert_eq!(diff.bumped_packages[0].from.as_deref(), Some("2.6.9, 4.3.4"));
+        assert_eq!(diff.bumped_packages[0].to.as_deref(), Some("4.3.4, 4.3.5"));
+    }
+}
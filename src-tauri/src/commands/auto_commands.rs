@@ -0,0 +1,261 @@
+// Track Your Shit - Auto-command Runner
+// Runs a project's enabled pre/post hooks around Claude PTY sessions
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use crate::commands::snippets::{row_to_auto_command, AUTO_COMMAND_COLUMNS};
+use crate::commands::test_runs::{
+    detect_framework, insert_test_run, quarantined_tests, run_test_command, worktree_state,
+    RunCoverage, TestCommandOutcome,
+};
+use crate::models::{
+    AutoCommand, AutoCommandChainResult, AutoCommandOutputLine, AutoCommandResult,
+};
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+type DbState = Arc<crate::db::DbPool>;
+
+/// Lines of hook output stored in the activity log entry
+const ACTIVITY_OUTPUT_LINES: usize = 50;
+
+/// Hooks still running past a chain's time budget get this long to finish
+const OVER_BUDGET_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The active project containing `path` (deepest match), as (id, path)
+pub(crate) fn project_for_path(conn: &Connection, path: &str) -> Option<(String, String)> {
+    let mut stmt = conn
+        .prepare("SELECT id, path FROM projects WHERE status = 'active'")
+        .ok()?;
+    let projects = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .ok()?
+        .filter_map(|r| r.ok())
+        .collect::<Vec<_>>();
+    projects
+        .into_iter()
+        .filter(|(_, project_path)| Path::new(path).starts_with(project_path))
+        .max_by_key(|(_, project_path)| project_path.len())
+}
+
+fn log_hook_result(conn: &Connection, result: &AutoCommandResult, session_id: Option<&str>) {
+    let outcome = if result.timed_out {
+        "timed out"
+    } else if result.success {
+        "passed"
+    } else {
+        "failed"
+    };
+    let message = format!(
+        "{} hook '{}' {} in {:.1}s",
+        if result.hook_type == "pre" { "Pre" } else { "Post" },
+        result.label,
+        outcome,
+        result.duration_ms as f64 / 1000.0
+    );
+    let tail = &result.output_tail[result.output_tail.len().saturating_sub(ACTIVITY_OUTPUT_LINES)..];
+    let metadata = serde_json::json!({
+        "auto_command_id": result.auto_command_id,
+        "hook_type": result.hook_type,
+        "command": result.command,
+        "exit_code": result.exit_code,
+        "timed_out": result.timed_out,
+        "duration_ms": result.duration_ms,
+        "session_id": session_id,
+        "test_run_id": result.test_run_id,
+        "quarantined_failures": result.quarantined_failures,
+        "output": tail,
+    });
+
+    if let Err(e) = conn.execute(
+        "INSERT INTO activity_log (id, project_id, event_type, message, metadata) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            format!("{:032x}", rand::random::<u128>()),
+            result.project_id,
+            if result.success { "auto_command_passed" } else { "auto_command_failed" },
+            message,
+            metadata.to_string(),
+        ],
+    ) {
+        tracing::warn!("[auto_commands] Failed to log hook result: {}", e);
+    }
+}
+
+/// Run one hook. Recognised test runners have their results recorded as a test
+/// run, and failures of quarantined tests don't fail the hook.
+async fn run_hook(
+    app: &AppHandle,
+    db: &DbState,
+    hook: &AutoCommand,
+    project_path: &str,
+    quarantine: &[(String, Option<String>)],
+    timeout: Duration,
+) -> AutoCommandResult {
+    let framework = detect_framework(&hook.command, None);
+    let emitter = app.clone();
+    let (project_id, hook_id) = (hook.project_id.clone(), hook.id.clone());
+
+    let outcome = run_test_command(
+        project_path,
+        &hook.command,
+        framework,
+        timeout,
+        move |stream, line| {
+            let _ = emitter.emit(
+                "auto-commands:output",
+                AutoCommandOutputLine {
+                    project_id: project_id.clone(),
+                    auto_command_id: hook_id.clone(),
+                    stream: stream.to_string(),
+                    line: line.to_string(),
+                },
+            );
+        },
+    )
+    .await
+    .unwrap_or_else(|e| TestCommandOutcome {
+        output_tail: vec![e],
+        ..Default::default()
+    });
+
+    let (_, quarantined_failures, success) = outcome.judge(quarantine);
+
+    let test_run_id = match (&outcome.report, framework) {
+        (Some(report), Some(_)) => {
+            let phase = crate::commands::gsd::read_current_phase_plan(project_path).0;
+            let commit_sha = worktree_state(project_path);
+            let db = db.write().await;
+            insert_test_run(
+                db.conn(),
+                &hook.project_id,
+                None,
+                phase.as_deref(),
+                commit_sha.as_deref(),
+                report,
+                RunCoverage::default(),
+            )
+            .map_err(|e| tracing::warn!("[auto_commands] Failed to record test run: {}", e))
+            .ok()
+            .map(|run| run.id)
+        }
+        _ => None,
+    };
+
+    AutoCommandResult {
+        auto_command_id: hook.id.clone(),
+        project_id: hook.project_id.clone(),
+        label: hook.label.clone(),
+        command: hook.command.clone(),
+        hook_type: hook.hook_type.clone(),
+        exit_code: outcome.exit_code,
+        timed_out: outcome.timed_out,
+        success,
+        duration_ms: outcome.duration_ms,
+        output_tail: outcome.output_tail,
+        test_run_id,
+        quarantined_failures,
+    }
+}
+
+/// Run a project's enabled hooks of `hook_type` in order_index order. Each
+/// result goes to activity_log and an `auto-commands:result` event; the chain
+/// ends with `auto-commands:complete`. A failing hook with stop_on_failure
+/// skips the rest. With a `budget`, each hook's timeout is cut to the time the
+/// chain has left.
+pub(crate) async fn run_hook_chain(
+    app: &AppHandle,
+    db: &DbState,
+    project_id: &str,
+    project_path: &str,
+    hook_type: &str,
+    session_id: Option<&str>,
+    budget: Option<Duration>,
+) -> Result<AutoCommandChainResult, String> {
+    let (hooks, quarantine) = {
+        let conn = db.read().await;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM auto_commands
+                 WHERE project_id = ?1 AND hook_type = ?2 AND enabled = 1
+                 ORDER BY order_index ASC",
+                AUTO_COMMAND_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+        let hooks = stmt
+            .query_map(params![project_id, hook_type], row_to_auto_command)
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        (hooks, quarantined_tests(&conn, project_id)?)
+    };
+
+    let mut chain = AutoCommandChainResult {
+        project_id: project_id.to_string(),
+        hook_type: hook_type.to_string(),
+        session_id: session_id.map(String::from),
+        success: true,
+        stopped: false,
+        results: Vec::new(),
+    };
+    if hooks.is_empty() {
+        return Ok(chain);
+    }
+
+    tracing::info!(
+        "[auto_commands] Running {} {} hook(s) for project {}",
+        hooks.len(),
+        hook_type,
+        project_id
+    );
+
+    let deadline = budget.map(|budget| Instant::now() + budget);
+    for hook in &hooks {
+        let mut timeout = Duration::from_secs(hook.timeout_secs.max(1) as u64);
+        if let Some(deadline) = deadline {
+            timeout = timeout.min(deadline.saturating_duration_since(Instant::now()).max(OVER_BUDGET_TIMEOUT));
+        }
+        let result = run_hook(app, db, hook, project_path, &quarantine, timeout).await;
+        {
+            let db = db.write().await;
+            log_hook_result(db.conn(), &result, session_id);
+        }
+        let _ = app.emit("auto-commands:result", &result);
+
+        let failed = !result.success;
+        chain.success &= result.success;
+        chain.results.push(result);
+        if failed && hook.stop_on_failure {
+            chain.stopped = true;
+            break;
+        }
+    }
+
+    let _ = app.emit("auto-commands:complete", &chain);
+    Ok(chain)
+}
+
+/// Run a project's pre or post hooks now, outside of a Claude session
+#[tauri::command]
+pub async fn run_auto_commands(
+    app: AppHandle,
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    hook_type: String,
+) -> Result<AutoCommandChainResult, String> {
+    if hook_type != "pre" && hook_type != "post" {
+        return Err(format!("Invalid hook type '{}'. Use pre or post.", hook_type));
+    }
+    let project_path: String = {
+        let conn = db.read().await;
+        conn.query_row(
+            "SELECT path FROM projects WHERE id = ?1",
+            params![project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Project not found: {}", e))?
+    };
+
+    run_hook_chain(&app, db.inner(), &project_id, &project_path, &hook_type, None, None).await
+}
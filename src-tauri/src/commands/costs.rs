@@ -0,0 +1,1365 @@
+// Track Your Shit - Cost Tracking Commands
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+//
+// Ingests per-message token usage from Claude Code session transcripts
+// (~/.claude/projects/<encoded-path>/*.jsonl) into the costs table, and
+// enforces per-project warn/alert/stop cost thresholds as rows arrive.
+
+use crate::commands::notifications::insert_notification;
+use crate::db::DbPool;
+use crate::models::{
+    CostBreakdownRow, CostIngestResult, CostReport, CostThreshold, CostThresholdEvent,
+    CreateNotificationInput, ModelPrice, Notification,
+};
+use crate::pty::TerminalManagerState;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+type DbState = Arc<crate::db::DbPool>;
+
+/// How often the background ingester tails transcripts
+const COST_INGEST_INTERVAL_SECS: u64 = 60;
+
+/// Built-in prices, USD per million tokens:
+/// (model pattern, input, output, cache write, cache read).
+/// Rows in `model_prices` override these; the longest matching pattern wins.
+/// Patterns name specific model versions: a model none of them match is
+/// recorded at zero cost and reported as unpriced rather than guessed at, and is
+/// repriced once a price that covers it exists (see `reprice_unpriced_costs`).
+const DEFAULT_MODEL_PRICES: &[(&str, f64, f64, f64, f64)] = &[
+    ("opus-4-5", 5.0, 25.0, 6.25, 0.50),
+    ("opus-4-1", 15.0, 75.0, 18.75, 1.50),
+    // claude-opus-4-20250514 / the claude-opus-4-0 alias
+    ("opus-4-2025", 15.0, 75.0, 18.75, 1.50),
+    ("opus-4-0", 15.0, 75.0, 18.75, 1.50),
+    ("3-opus", 15.0, 75.0, 18.75, 1.50),
+    ("sonnet-4-5", 3.0, 15.0, 3.75, 0.30),
+    ("sonnet-4-2025", 3.0, 15.0, 3.75, 0.30),
+    ("sonnet-4-0", 3.0, 15.0, 3.75, 0.30),
+    ("3-7-sonnet", 3.0, 15.0, 3.75, 0.30),
+    ("3-5-sonnet", 3.0, 15.0, 3.75, 0.30),
+    ("haiku-4-5", 1.0, 5.0, 1.25, 0.10),
+    ("3-5-haiku", 0.80, 4.0, 1.0, 0.08),
+    ("3-haiku", 0.25, 1.25, 0.30, 0.03),
+];
+
+/// Token usage for one assistant message in a transcript
+#[derive(Debug, Clone, PartialEq)]
+struct UsageRecord {
+    message_id: String,
+    session_id: Option<String>,
+    model: String,
+    agent: String,
+    input_tokens: i64,
+    output_tokens: i64,
+    cache_creation_tokens: i64,
+    cache_read_tokens: i64,
+    created_at: Option<String>,
+}
+
+fn claude_projects_dir() -> Option<PathBuf> {
+    std::env::var_os("CLAUDE_CONFIG_DIR")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".claude")))
+        .map(|d| d.join("projects"))
+}
+
+/// Claude Code names each project's transcript directory after the project
+/// path with every non-alphanumeric character replaced by '-'
+fn transcript_dir_name(project_path: &str) -> String {
+    project_path
+        .trim_end_matches(['/', '\\'])
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Collect .jsonl transcripts, including subagent transcripts in nested directories
+fn collect_transcripts(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_transcripts(&path, out);
+        } else if path.extension().is_some_and(|e| e == "jsonl") {
+            out.push(path);
+        }
+    }
+}
+
+/// Parse one transcript line, returning usage for assistant messages only
+fn parse_usage_line(line: &str) -> Option<UsageRecord> {
+    let v: serde_json::Value = serde_json::from_str(line).ok()?;
+    if v.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+        return None;
+    }
+    let message = v.get("message")?;
+    let usage = message.get("usage")?;
+    let model = message.get("model").and_then(|m| m.as_str())?;
+    if model.starts_with('<') {
+        // "<synthetic>" messages are generated locally and not billed
+        return None;
+    }
+
+    let message_id = message
+        .get("id")
+        .or_else(|| v.get("requestId"))
+        .or_else(|| v.get("uuid"))
+        .and_then(|id| id.as_str())?
+        .to_string();
+
+    let agent = if v.get("isSidechain").and_then(|s| s.as_bool()) == Some(true) {
+        v.get("agentId")
+            .and_then(|a| a.as_str())
+            .map(|a| format!("subagent:{}", a))
+            .unwrap_or_else(|| "subagent".to_string())
+    } else {
+        "main".to_string()
+    };
+
+    let tokens = |key: &str| usage.get(key).and_then(|t| t.as_i64()).unwrap_or(0);
+
+    let created_at = v
+        .get("timestamp")
+        .and_then(|t| t.as_str())
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| {
+            t.with_timezone(&chrono::Utc)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        });
+
+    Some(UsageRecord {
+        message_id,
+        session_id: v.get("sessionId").and_then(|s| s.as_str()).map(String::from),
+        model: model.to_string(),
+        agent,
+        input_tokens: tokens("input_tokens"),
+        output_tokens: tokens("output_tokens"),
+        cache_creation_tokens: tokens("cache_creation_input_tokens"),
+        cache_read_tokens: tokens("cache_read_input_tokens"),
+        created_at,
+    })
+}
+
+/// Built-in prices overlaid with user-configured rows
+fn load_prices(conn: &Connection) -> Result<Vec<ModelPrice>, String> {
+    let mut prices: Vec<ModelPrice> = DEFAULT_MODEL_PRICES
+        .iter()
+        .map(|(model, input, output, cache_write, cache_read)| ModelPrice {
+            model: model.to_string(),
+            input_per_mtok: *input,
+            output_per_mtok: *output,
+            cache_write_per_mtok: *cache_write,
+            cache_read_per_mtok: *cache_read,
+            is_default: true,
+        })
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT model, input_per_mtok, output_per_mtok, cache_write_per_mtok, cache_read_per_mtok
+             FROM model_prices",
+        )
+        .map_err(|e| e.to_string())?;
+    let custom = stmt
+        .query_map([], |row| {
+            Ok(ModelPrice {
+                model: row.get(0)?,
+                input_per_mtok: row.get(1)?,
+                output_per_mtok: row.get(2)?,
+                cache_write_per_mtok: row.get(3)?,
+                cache_read_per_mtok: row.get(4)?,
+                is_default: false,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    for price in custom {
+        prices.retain(|p| p.model != price.model);
+        prices.push(price);
+    }
+    prices.sort_by(|a, b| a.model.cmp(&b.model));
+    Ok(prices)
+}
+
+/// Longest pattern contained in the model name (case-insensitive)
+fn price_for<'a>(prices: &'a [ModelPrice], model: &str) -> Option<&'a ModelPrice> {
+    let model = model.to_lowercase();
+    prices
+        .iter()
+        .filter(|p| model.contains(&p.model.to_lowercase()))
+        .max_by_key(|p| p.model.len())
+}
+
+fn usage_cost(price: &ModelPrice, usage: &UsageRecord) -> f64 {
+    (usage.input_tokens as f64 * price.input_per_mtok
+        + usage.output_tokens as f64 * price.output_per_mtok
+        + usage.cache_creation_tokens as f64 * price.cache_write_per_mtok
+        + usage.cache_read_tokens as f64 * price.cache_read_per_mtok)
+        / 1_000_000.0
+}
+
+/// Price ingested rows that were recorded at zero cost because their model had
+/// no price, now that one matches. Returns the number of rows repriced.
+fn reprice_unpriced_costs(conn: &Connection) -> Result<usize, String> {
+    let prices = load_prices(conn)?;
+    let rows: Vec<(String, UsageRecord)> = conn
+        .prepare(
+            "SELECT id, model, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens
+             FROM costs
+             WHERE total_cost = 0 AND message_id IS NOT NULL
+               AND input_tokens + output_tokens + cache_creation_tokens + cache_read_tokens > 0",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    UsageRecord {
+                        message_id: String::new(),
+                        session_id: None,
+                        model: row.get(1)?,
+                        agent: String::new(),
+                        input_tokens: row.get(2)?,
+                        output_tokens: row.get(3)?,
+                        cache_creation_tokens: row.get(4)?,
+                        cache_read_tokens: row.get(5)?,
+                        created_at: None,
+                    },
+                ))
+            })?
+            .collect()
+        })
+        .map_err(|e| e.to_string())?;
+
+    let repriced: Vec<(String, f64)> = rows
+        .iter()
+        .filter_map(|(id, usage)| {
+            let cost = usage_cost(price_for(&prices, &usage.model)?, usage);
+            (cost > 0.0).then(|| (id.clone(), cost))
+        })
+        .collect();
+    if repriced.is_empty() {
+        return Ok(0);
+    }
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let result = repriced.iter().try_for_each(|(id, cost)| {
+        conn.execute("UPDATE costs SET total_cost = ?1 WHERE id = ?2", params![cost, id])
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    });
+    match result {
+        Ok(()) => conn.execute_batch("COMMIT").map_err(|e| e.to_string())?,
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+    }
+    Ok(repriced.len())
+}
+
+/// Read the complete lines appended to a transcript since `offset`.
+/// Returns the text and the number of bytes consumed.
+fn read_new_lines(path: &Path, offset: u64) -> Result<(String, u64), String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| e.to_string())?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).map_err(|e| e.to_string())?;
+
+    // Leave a trailing partial line for the next pass
+    let consumed = buf.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    Ok((
+        String::from_utf8_lossy(&buf[..consumed]).to_string(),
+        consumed as u64,
+    ))
+}
+
+/// New lines of one transcript, read and parsed without touching the database
+struct TranscriptChunk {
+    file_path: String,
+    /// Byte offset the parsed lines end at
+    end_offset: u64,
+    records: Vec<UsageRecord>,
+}
+
+/// A project's unread transcript usage
+struct ProjectScan {
+    project_id: String,
+    project_path: String,
+    phase: Option<String>,
+    plan: Option<String>,
+    files_scanned: i32,
+    chunks: Vec<TranscriptChunk>,
+}
+
+/// Stored read offsets for every transcript, keyed by file path
+fn load_ingest_offsets(conn: &Connection) -> Result<HashMap<String, u64>, String> {
+    let mut stmt = conn
+        .prepare("SELECT file_path, byte_offset FROM cost_ingest_state")
+        .map_err(|e| e.to_string())?;
+    let offsets = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?.max(0) as u64)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<HashMap<_, _>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(offsets)
+}
+
+/// Read and parse the lines appended to a project's transcripts since the
+/// stored offsets. Blocking file IO; holds no database lock.
+fn scan_project_transcripts(
+    project_id: &str,
+    project_path: &str,
+    offsets: &HashMap<String, u64>,
+) -> ProjectScan {
+    let mut scan = ProjectScan {
+        project_id: project_id.to_string(),
+        project_path: project_path.to_string(),
+        phase: None,
+        plan: None,
+        files_scanned: 0,
+        chunks: Vec::new(),
+    };
+
+    let Some(projects_dir) = claude_projects_dir() else {
+        return scan;
+    };
+    let transcript_dir = projects_dir.join(transcript_dir_name(project_path));
+    if !transcript_dir.is_dir() {
+        return scan;
+    }
+    let mut transcripts = Vec::new();
+    collect_transcripts(&transcript_dir, &mut transcripts);
+
+    for path in transcripts {
+        let file_path = path.to_string_lossy().to_string();
+        let len = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let stored_offset = offsets.get(&file_path).copied().unwrap_or(0);
+        // A shorter file means it was rewritten; start over (message ids dedupe)
+        let offset = if len < stored_offset { 0 } else { stored_offset };
+        if len == offset {
+            continue;
+        }
+        scan.files_scanned += 1;
+
+        let (text, consumed) = match read_new_lines(&path, offset) {
+            Ok(read) => read,
+            Err(e) => {
+                tracing::warn!("[costs] Failed to read transcript {}: {}", file_path, e);
+                continue;
+            }
+        };
+        if consumed == 0 {
+            continue;
+        }
+        scan.chunks.push(TranscriptChunk {
+            file_path,
+            end_offset: offset + consumed,
+            records: text.lines().filter_map(parse_usage_line).collect(),
+        });
+    }
+
+    if !scan.chunks.is_empty() {
+        // Rows are attributed to the current GSD phase and plan from STATE.md
+        let (phase, plan) = crate::commands::gsd::read_current_phase_plan(project_path);
+        scan.phase = phase;
+        scan.plan = plan;
+    }
+    scan
+}
+
+/// Write a project's scanned usage rows and new offsets (one transaction per
+/// transcript), then check its cost thresholds
+fn apply_project_scan(conn: &Connection, scan: &ProjectScan) -> Result<CostIngestResult, String> {
+    let mut result = CostIngestResult {
+        projects_scanned: 1,
+        files_scanned: scan.files_scanned,
+        ..Default::default()
+    };
+    if scan.chunks.is_empty() {
+        return Ok(result);
+    }
+    let prices = load_prices(conn)?;
+
+    for chunk in &scan.chunks {
+        conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+        let ingest = (|| -> Result<(), String> {
+            for usage in &chunk.records {
+                let cost = match price_for(&prices, &usage.model) {
+                    Some(price) => usage_cost(price, usage),
+                    None => {
+                        if !result.unpriced_models.contains(&usage.model) {
+                            tracing::warn!("[costs] No price configured for model {}", usage.model);
+                            result.unpriced_models.push(usage.model.clone());
+                        }
+                        0.0
+                    }
+                };
+                let inserted = conn
+                    .execute(
+                        "INSERT OR IGNORE INTO costs (id, project_id, phase, plan, agent, model, input_tokens, output_tokens,
+                             cache_creation_tokens, cache_read_tokens, total_cost, session_id, message_id, created_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, COALESCE(?14, datetime('now')))",
+                        params![
+                            format!("{:032x}", rand::random::<u128>()),
+                            scan.project_id,
+                            scan.phase,
+                            scan.plan,
+                            usage.agent,
+                            usage.model,
+                            usage.input_tokens,
+                            usage.output_tokens,
+                            usage.cache_creation_tokens,
+                            usage.cache_read_tokens,
+                            cost,
+                            usage.session_id,
+                            usage.message_id,
+                            usage.created_at,
+                        ],
+                    )
+                    .map_err(|e| e.to_string())?;
+                if inserted > 0 {
+                    result.rows_inserted += 1;
+                    result.total_cost += cost;
+                }
+            }
+
+            conn.execute(
+                "INSERT INTO cost_ingest_state (file_path, project_id, byte_offset, updated_at)
+                 VALUES (?1, ?2, ?3, datetime('now'))
+                 ON CONFLICT(file_path) DO UPDATE SET byte_offset = ?3, updated_at = datetime('now')",
+                params![chunk.file_path, scan.project_id, chunk.end_offset as i64],
+            )
+            .map_err(|e| e.to_string())?;
+            Ok(())
+        })();
+
+        match ingest {
+            Ok(()) => conn.execute_batch("COMMIT").map_err(|e| e.to_string())?,
+            Err(e) => {
+                let _ = conn.execute_batch("ROLLBACK");
+                return Err(e);
+            }
+        }
+    }
+
+    if result.rows_inserted > 0 {
+        if let Some(event) = evaluate_cost_thresholds(conn, &scan.project_id, &scan.project_path)? {
+            result.threshold_events.push(event);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Ingest new transcript usage for one project, or every active project.
+/// Transcripts are read and parsed on a blocking thread with no lock held;
+/// the write lock is only taken for the inserts.
+pub async fn ingest_costs(pool: &DbPool, project_id: Option<&str>) -> Result<CostIngestResult, String> {
+    let (projects, offsets) = {
+        let conn = pool.read().await;
+        let projects: Vec<(String, String)> = match project_id {
+            Some(id) => vec![(
+                id.to_string(),
+                conn.query_row("SELECT path FROM projects WHERE id = ?1", params![id], |row| row.get(0))
+                    .map_err(|e| format!("Project not found: {}", e))?,
+            )],
+            None => {
+                let mut stmt = conn
+                    .prepare("SELECT id, path FROM projects WHERE status = 'active'")
+                    .map_err(|e| e.to_string())?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                    .map_err(|e| e.to_string())?
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| e.to_string())?;
+                rows
+            }
+        };
+        (projects, load_ingest_offsets(&conn)?)
+    };
+
+    let scans = tauri::async_runtime::spawn_blocking(move || {
+        projects
+            .iter()
+            .map(|(id, path)| scan_project_transcripts(id, path, &offsets))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut total = CostIngestResult::default();
+    if scans.iter().all(|scan| scan.chunks.is_empty()) {
+        total.projects_scanned = scans.len() as i32;
+        total.files_scanned = scans.iter().map(|scan| scan.files_scanned).sum();
+        return Ok(total);
+    }
+
+    let db = pool.write().await;
+    for scan in &scans {
+        match apply_project_scan(db.conn(), scan) {
+            Ok(r) => {
+                total.projects_scanned += r.projects_scanned;
+                total.files_scanned += r.files_scanned;
+                total.rows_inserted += r.rows_inserted;
+                total.total_cost += r.total_cost;
+                total.threshold_events.extend(r.threshold_events);
+                for model in r.unpriced_models {
+                    if !total.unpriced_models.contains(&model) {
+                        total.unpriced_models.push(model);
+                    }
+                }
+            }
+            Err(e) if project_id.is_some() => return Err(e),
+            Err(e) => {
+                tracing::warn!("[costs] Ingest failed for project {}: {}", scan.project_id, e);
+            }
+        }
+    }
+    Ok(total)
+}
+
+/// Periodically tail transcripts for all projects in the background.
+/// Emits "costs:updated" when new rows are written.
+pub fn spawn_cost_ingester(app: AppHandle, pool: Arc<DbPool>) {
+    tauri::async_runtime::spawn(async move {
+        // Built-in prices may now cover models earlier versions recorded at $0
+        match reprice_unpriced_costs(pool.write().await.conn()) {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("[costs] Repriced {} previously unpriced usage rows", n),
+            Err(e) => tracing::warn!("[costs] Repricing failed: {}", e),
+        }
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(COST_INGEST_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match ingest_costs(&pool, None).await {
+                Ok(mut r) if r.rows_inserted > 0 => {
+                    apply_threshold_events(&app, &pool, &mut r.threshold_events).await;
+                    tracing::info!(
+                        "[costs] Ingested {} usage rows (${:.4}) from {} transcripts",
+                        r.rows_inserted,
+                        r.total_cost,
+                        r.files_scanned
+                    );
+                    let _ = app.emit("costs:updated", &r);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("[costs] Background ingest failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Ingest Claude session transcripts now, for one project or all active projects
+#[tauri::command]
+pub async fn ingest_claude_costs(
+    db: tauri::State<'_, DbState>,
+    app: AppHandle,
+    project_id: Option<String>,
+) -> Result<CostIngestResult, String> {
+    let mut result = ingest_costs(&db, project_id.as_deref()).await?;
+
+    apply_threshold_events(&app, &db, &mut result.threshold_events).await;
+    if result.rows_inserted > 0 {
+        let _ = app.emit("costs:updated", &result);
+    }
+
+    Ok(result)
+}
+
+/// List effective model prices (built-in defaults merged with overrides)
+#[tauri::command]
+pub async fn list_model_prices(db: tauri::State<'_, DbState>) -> Result<Vec<ModelPrice>, String> {
+    let conn = db.read().await;
+    load_prices(&conn)
+}
+
+/// Set the price for a model pattern. Cache prices default to 1.25x (write)
+/// and 0.1x (read) the input price.
+#[tauri::command]
+pub async fn set_model_price(
+    db: tauri::State<'_, DbState>,
+    model: String,
+    input_per_mtok: f64,
+    output_per_mtok: f64,
+    cache_write_per_mtok: Option<f64>,
+    cache_read_per_mtok: Option<f64>,
+) -> Result<ModelPrice, String> {
+    let model = model.trim().to_lowercase();
+    if model.is_empty() {
+        return Err("Model pattern cannot be empty".to_string());
+    }
+    if input_per_mtok < 0.0 || output_per_mtok < 0.0 {
+        return Err("Prices cannot be negative".to_string());
+    }
+    let price = ModelPrice {
+        cache_write_per_mtok: cache_write_per_mtok.unwrap_or(input_per_mtok * 1.25),
+        cache_read_per_mtok: cache_read_per_mtok.unwrap_or(input_per_mtok * 0.1),
+        model,
+        input_per_mtok,
+        output_per_mtok,
+        is_default: false,
+    };
+
+    let db = db.write().await;
+    db.conn()
+        .execute(
+            "INSERT INTO model_prices (model, input_per_mtok, output_per_mtok, cache_write_per_mtok, cache_read_per_mtok, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))
+             ON CONFLICT(model) DO UPDATE SET
+                 input_per_mtok = ?2, output_per_mtok = ?3,
+                 cache_write_per_mtok = ?4, cache_read_per_mtok = ?5,
+                 updated_at = datetime('now')",
+            params![
+                price.model,
+                price.input_per_mtok,
+                price.output_per_mtok,
+                price.cache_write_per_mtok,
+                price.cache_read_per_mtok,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    reprice_unpriced_costs(db.conn())?;
+
+    Ok(price)
+}
+
+/// Remove a price override (built-in patterns revert to their default)
+#[tauri::command]
+pub async fn delete_model_price(db: tauri::State<'_, DbState>, model: String) -> Result<(), String> {
+    let db = db.write().await;
+    db.conn()
+        .execute(
+            "DELETE FROM model_prices WHERE model = ?1",
+            params![model.trim().to_lowercase()],
+        )
+        .map_err(|e| e.to_string())?;
+    reprice_unpriced_costs(db.conn())?;
+    Ok(())
+}
+
+// ============================================================
+// Cost Thresholds (warn / alert / stop)
+// ============================================================
+
+fn read_setting(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// 0 = below warn, 1 = warn, 2 = alert, 3 = stop
+fn level_rank(level: Option<&str>) -> u8 {
+    match level {
+        Some("warn") => 1,
+        Some("alert") => 2,
+        Some("stop") => 3,
+        _ => 0,
+    }
+}
+
+fn level_for_cost(threshold: &CostThreshold, cost: f64) -> Option<&'static str> {
+    if cost >= threshold.stop_cost {
+        Some("stop")
+    } else if cost >= threshold.alert_cost {
+        Some("alert")
+    } else if cost >= threshold.warn_cost {
+        Some("warn")
+    } else {
+        None
+    }
+}
+
+/// Global warn/alert/stop amounts from Settings
+fn global_thresholds(conn: &Connection) -> (f64, f64, f64) {
+    let global = |key: &str, default: f64| {
+        read_setting(conn, key)
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(default)
+    };
+    (
+        global("warn_cost", 10.0),
+        global("alert_cost", 25.0),
+        global("stop_cost", 50.0),
+    )
+}
+
+/// Read a project's thresholds without writing anything. A project with no
+/// row yet follows the global settings.
+fn read_threshold(conn: &Connection, project_id: &str) -> Result<CostThreshold, String> {
+    let (global_warn, global_alert, global_stop) = global_thresholds(conn);
+
+    let stored = conn
+        .query_row(
+            "SELECT ct.project_id, ct.warn_cost, ct.alert_cost, ct.stop_cost, COALESCE(ct.enabled, 1),
+                    COALESCE(ct.use_global, 0), COALESCE(ct.interrupt_on_stop, 0),
+                    COALESCE(ct.alert_acknowledged, 0), ct.last_level, ct.stop_triggered_at,
+                    ct.acknowledged_at,
+                    (SELECT COALESCE(SUM(total_cost), 0) FROM costs WHERE project_id = ct.project_id)
+             FROM cost_thresholds ct WHERE ct.project_id = ?1",
+            params![project_id],
+            |row| {
+                Ok(CostThreshold {
+                    project_id: row.get(0)?,
+                    warn_cost: row.get(1)?,
+                    alert_cost: row.get(2)?,
+                    stop_cost: row.get(3)?,
+                    enabled: row.get::<_, i32>(4)? != 0,
+                    use_global: row.get::<_, i32>(5)? != 0,
+                    interrupt_on_stop: row.get::<_, i32>(6)? != 0,
+                    alert_acknowledged: row.get::<_, i32>(7)? != 0,
+                    last_level: row.get(8)?,
+                    stop_triggered_at: row.get(9)?,
+                    acknowledged_at: row.get(10)?,
+                    current_cost: row.get(11)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let mut threshold = match stored {
+        Some(threshold) => threshold,
+        None => CostThreshold {
+            project_id: project_id.to_string(),
+            warn_cost: global_warn,
+            alert_cost: global_alert,
+            stop_cost: global_stop,
+            enabled: true,
+            use_global: true,
+            interrupt_on_stop: false,
+            alert_acknowledged: false,
+            last_level: None,
+            stop_triggered_at: None,
+            acknowledged_at: None,
+            current_cost: conn
+                .query_row(
+                    "SELECT COALESCE(SUM(total_cost), 0) FROM costs WHERE project_id = ?1",
+                    params![project_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?,
+        },
+    };
+
+    if threshold.use_global {
+        threshold.warn_cost = global_warn;
+        threshold.alert_cost = global_alert;
+        threshold.stop_cost = global_stop;
+    }
+    Ok(threshold)
+}
+
+/// Load a project's thresholds, creating a row that follows the global
+/// settings if the project has none yet
+fn load_threshold(conn: &Connection, project_id: &str) -> Result<CostThreshold, String> {
+    let (global_warn, global_alert, global_stop) = global_thresholds(conn);
+    conn.execute(
+        "INSERT OR IGNORE INTO cost_thresholds (id, project_id, warn_cost, alert_cost, stop_cost, use_global)
+         VALUES (?1, ?2, ?3, ?4, ?5, 1)",
+        params![
+            format!("{:032x}", rand::random::<u128>()),
+            project_id,
+            global_warn,
+            global_alert,
+            global_stop
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    read_threshold(conn, project_id)
+}
+
+/// Compare a project's total spend with its thresholds and record any newly
+/// reached level. Warn and alert create notifications; stop additionally
+/// starts the acknowledgment workflow and, when `interrupt_on_stop` is set,
+/// requests an interrupt of the project's Claude sessions. While a stop is
+/// unacknowledged every evaluation (i.e. every batch of new spend) requests
+/// another interrupt.
+pub(crate) fn evaluate_cost_thresholds(
+    conn: &Connection,
+    project_id: &str,
+    project_path: &str,
+) -> Result<Option<CostThresholdEvent>, String> {
+    let thresholds_enabled = read_setting(conn, "cost_thresholds_enabled")
+        .map(|v| v == "true")
+        .unwrap_or(true);
+    if !thresholds_enabled {
+        return Ok(None);
+    }
+
+    let threshold = load_threshold(conn, project_id)?;
+    if !threshold.enabled {
+        return Ok(None);
+    }
+
+    let level = level_for_cost(&threshold, threshold.current_cost);
+    let previous = threshold.last_level.as_deref();
+
+    if level_rank(level) < level_rank(previous) {
+        // Spend dropped (costs cleared or thresholds raised): reset the workflow
+        conn.execute(
+            "UPDATE cost_thresholds
+             SET last_level = ?1,
+                 alert_acknowledged = CASE WHEN ?1 = 'stop' THEN alert_acknowledged ELSE 0 END,
+                 stop_triggered_at = CASE WHEN ?1 = 'stop' THEN stop_triggered_at ELSE NULL END,
+                 updated_at = datetime('now')
+             WHERE project_id = ?2",
+            params![level, project_id],
+        )
+        .map_err(|e| e.to_string())?;
+        return Ok(None);
+    }
+
+    let Some(level) = level else {
+        return Ok(None);
+    };
+    let threshold_amount = match level {
+        "stop" => threshold.stop_cost,
+        "alert" => threshold.alert_cost,
+        _ => threshold.warn_cost,
+    };
+
+    if level_rank(Some(level)) == level_rank(previous) {
+        if level == "stop" && threshold.interrupt_on_stop && !threshold.alert_acknowledged {
+            return Ok(Some(CostThresholdEvent {
+                project_id: project_id.to_string(),
+                project_path: project_path.to_string(),
+                level: level.to_string(),
+                threshold: threshold_amount,
+                current_cost: threshold.current_cost,
+                notification: None,
+                interrupt_requested: true,
+                interrupted_sessions: Vec::new(),
+                notify_after_interrupt: false,
+            }));
+        }
+        return Ok(None);
+    }
+
+    if level == "stop" {
+        conn.execute(
+            "UPDATE cost_thresholds
+             SET last_level = 'stop', alert_acknowledged = 0, acknowledged_at = NULL,
+                 stop_triggered_at = datetime('now'), updated_at = datetime('now')
+             WHERE project_id = ?1",
+            params![project_id],
+        )
+        .map_err(|e| e.to_string())?;
+    } else {
+        conn.execute(
+            "UPDATE cost_thresholds SET last_level = ?1, updated_at = datetime('now') WHERE project_id = ?2",
+            params![level, project_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // A stop that interrupts sessions is announced once the interrupt has
+    // happened, so the message can say how many sessions were stopped
+    let interrupt_requested = level == "stop" && threshold.interrupt_on_stop;
+    let notification = if interrupt_requested {
+        None
+    } else {
+        threshold_notification(conn, project_id, level, threshold.current_cost, threshold_amount, 0)?
+    };
+
+    Ok(Some(CostThresholdEvent {
+        project_id: project_id.to_string(),
+        project_path: project_path.to_string(),
+        level: level.to_string(),
+        threshold: threshold_amount,
+        current_cost: threshold.current_cost,
+        notification,
+        interrupt_requested,
+        interrupted_sessions: Vec::new(),
+        notify_after_interrupt: interrupt_requested,
+    }))
+}
+
+/// Insert the notification for a newly reached threshold level, if cost
+/// notifications are on. `interrupted` is the number of Claude sessions the
+/// stop actually interrupted.
+fn threshold_notification(
+    conn: &Connection,
+    project_id: &str,
+    level: &str,
+    current_cost: f64,
+    threshold_amount: f64,
+    interrupted: usize,
+) -> Result<Option<Notification>, String> {
+    let notify = read_setting(conn, "notifications_enabled").is_none_or(|v| v == "true")
+        && read_setting(conn, "notify_on_cost_warning").is_none_or(|v| v == "true");
+    if !notify {
+        return Ok(None);
+    }
+
+    let project_name: String = conn
+        .query_row(
+            "SELECT name FROM projects WHERE id = ?1",
+            params![project_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_else(|| project_id.to_string());
+    let (title, message) = match level {
+        "stop" => (
+            format!("Cost limit reached: {}", project_name),
+            format!(
+                "Spend is ${:.2}, over the ${:.2} stop threshold.{} Acknowledge to continue.",
+                current_cost,
+                threshold_amount,
+                match interrupted {
+                    0 => String::new(),
+                    1 => " 1 running Claude session was interrupted.".to_string(),
+                    n => format!(" {} running Claude sessions were interrupted.", n),
+                }
+            ),
+        ),
+        "alert" => (
+            format!("Cost alert: {}", project_name),
+            format!(
+                "Spend is ${:.2}, over the ${:.2} alert threshold.",
+                current_cost, threshold_amount
+            ),
+        ),
+        _ => (
+            format!("Cost warning: {}", project_name),
+            format!(
+                "Spend is ${:.2}, over the ${:.2} warning threshold.",
+                current_cost, threshold_amount
+            ),
+        ),
+    };
+    insert_notification(
+        conn,
+        &CreateNotificationInput {
+            project_id: Some(project_id.to_string()),
+            notification_type: if level == "warn" { "warning" } else { "error" }.to_string(),
+            title,
+            message,
+            link: Some(format!("/projects/{}", project_id)),
+        },
+    )
+    .map(Some)
+}
+
+/// Emit notifications and threshold events, and interrupt Claude sessions
+/// for stop events that request it
+pub async fn apply_threshold_events(app: &AppHandle, pool: &DbPool, events: &mut [CostThresholdEvent]) {
+    for event in events.iter_mut() {
+        if event.interrupt_requested {
+            if let Some(terminal_manager) = app.try_state::<TerminalManagerState>() {
+                let mut tm = terminal_manager.lock().await;
+                event.interrupted_sessions = tm.interrupt_claude_sessions(&event.project_path);
+            }
+            tracing::warn!(
+                "[costs] Stop threshold for project {} (${:.2}): interrupted {} Claude session(s)",
+                event.project_id,
+                event.current_cost,
+                event.interrupted_sessions.len()
+            );
+        }
+        if event.notify_after_interrupt {
+            let db = pool.write().await;
+            match threshold_notification(
+                db.conn(),
+                &event.project_id,
+                &event.level,
+                event.current_cost,
+                event.threshold,
+                event.interrupted_sessions.len(),
+            ) {
+                Ok(notification) => event.notification = notification,
+                Err(e) => tracing::warn!("[costs] Failed to record stop notification: {}", e),
+            }
+        }
+        if let Some(notification) = &event.notification {
+            let _ = app.emit("notification:new", notification);
+        }
+        let _ = app.emit("costs:threshold", &*event);
+    }
+}
+
+/// Get a project's effective cost thresholds and current spend
+#[tauri::command]
+pub async fn get_cost_thresholds(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+) -> Result<CostThreshold, String> {
+    let conn = db.read().await;
+    read_threshold(&conn, &project_id)
+}
+
+/// Set per-project thresholds (omitted amounts keep following global settings).
+/// Changing thresholds restarts the warn/alert/stop workflow.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn set_cost_thresholds(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    warn_cost: Option<f64>,
+    alert_cost: Option<f64>,
+    stop_cost: Option<f64>,
+    enabled: Option<bool>,
+    interrupt_on_stop: Option<bool>,
+) -> Result<CostThreshold, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+    let current = load_threshold(conn, &project_id)?;
+
+    let overrides_amounts = warn_cost.is_some() || alert_cost.is_some() || stop_cost.is_some();
+    let warn = warn_cost.unwrap_or(current.warn_cost);
+    let alert = alert_cost.unwrap_or(current.alert_cost);
+    let stop = stop_cost.unwrap_or(current.stop_cost);
+    if warn < 0.0 || !(warn <= alert && alert <= stop) {
+        return Err("Thresholds must satisfy 0 <= warn <= alert <= stop".to_string());
+    }
+
+    conn.execute(
+        "UPDATE cost_thresholds
+         SET warn_cost = ?1, alert_cost = ?2, stop_cost = ?3, enabled = ?4,
+             interrupt_on_stop = ?5, use_global = ?6,
+             last_level = NULL, alert_acknowledged = 0, stop_triggered_at = NULL,
+             acknowledged_at = NULL, updated_at = datetime('now')
+         WHERE project_id = ?7",
+        params![
+            warn,
+            alert,
+            stop,
+            enabled.unwrap_or(current.enabled) as i32,
+            interrupt_on_stop.unwrap_or(current.interrupt_on_stop) as i32,
+            (current.use_global && !overrides_amounts) as i32,
+            project_id,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    load_threshold(conn, &project_id)
+}
+
+/// Acknowledge a reached stop threshold so sessions are no longer interrupted
+#[tauri::command]
+pub async fn acknowledge_cost_alert(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+) -> Result<CostThreshold, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+    load_threshold(conn, &project_id)?;
+
+    conn.execute(
+        "UPDATE cost_thresholds
+         SET alert_acknowledged = 1, acknowledged_at = datetime('now'), updated_at = datetime('now')
+         WHERE project_id = ?1",
+        params![project_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    load_threshold(conn, &project_id)
+}
+
+// ============================================================
+// Cost Analytics
+// ============================================================
+
+/// Aggregate matching costs rows (alias `c`) grouped by `key_expr`.
+/// An empty `key_expr` aggregates everything into a single row.
+fn cost_breakdown(
+    conn: &Connection,
+    key_expr: &str,
+    label_expr: &str,
+    joins: &str,
+    where_clause: &str,
+    params_vec: &[Box<dyn rusqlite::ToSql>],
+    order_by: &str,
+) -> Result<Vec<CostBreakdownRow>, String> {
+    let group_by = if key_expr.is_empty() {
+        String::new()
+    } else {
+        format!("GROUP BY {}", key_expr)
+    };
+    let sql = format!(
+        "SELECT {}, {},
+                COALESCE(SUM(c.total_cost), 0), COALESCE(SUM(c.input_tokens), 0),
+                COALESCE(SUM(c.output_tokens), 0), COALESCE(SUM(c.cache_creation_tokens), 0),
+                COALESCE(SUM(c.cache_read_tokens), 0), COUNT(c.id)
+         FROM costs c {}
+         WHERE {}
+         {}
+         ORDER BY {}",
+        if key_expr.is_empty() { "'all'" } else { key_expr },
+        if label_expr.is_empty() { "'All'" } else { label_expr },
+        joins,
+        where_clause,
+        group_by,
+        order_by
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let rows = stmt
+        .query_map(params_refs.as_slice(), |row| {
+            Ok(CostBreakdownRow {
+                key: row.get(0)?,
+                label: row.get(1)?,
+                total_cost: row.get(2)?,
+                input_tokens: row.get(3)?,
+                output_tokens: row.get(4)?,
+                cache_creation_tokens: row.get(5)?,
+                cache_read_tokens: row.get(6)?,
+                message_count: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+/// Cost report over an optional date range (YYYY-MM-DD, inclusive), broken
+/// down by project, model, agent, GSD phase and plan, and by day or week
+#[tauri::command]
+pub async fn get_cost_report(
+    db: tauri::State<'_, DbState>,
+    project_id: Option<String>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+    granularity: Option<String>,
+) -> Result<CostReport, String> {
+    let granularity = granularity.unwrap_or_else(|| "day".to_string());
+    let period_expr = match granularity.as_str() {
+        "day" => "date(c.created_at)",
+        // Monday of the ISO week
+        "week" => "date(c.created_at, 'weekday 0', '-6 days')",
+        other => return Err(format!("Unknown granularity '{}'. Use 'day' or 'week'.", other)),
+    };
+
+    let conn = db.read().await;
+
+    let mut conditions = vec!["1 = 1".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    if let Some(id) = &project_id {
+        conditions.push("c.project_id = ?".to_string());
+        params_vec.push(Box::new(id.clone()));
+    }
+    if let Some(from) = &date_from {
+        conditions.push("date(c.created_at) >= date(?)".to_string());
+        params_vec.push(Box::new(from.clone()));
+    }
+    if let Some(to) = &date_to {
+        conditions.push("date(c.created_at) <= date(?)".to_string());
+        params_vec.push(Box::new(to.clone()));
+    }
+    let where_clause = conditions.join(" AND ");
+
+    let breakdown = |key: &str, label: &str, joins: &str, order_by: &str| {
+        cost_breakdown(&conn, key, label, joins, &where_clause, &params_vec, order_by)
+    };
+
+    let totals = breakdown("", "", "", "1")?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Failed to aggregate costs".to_string())?;
+    let by_project = breakdown(
+        "c.project_id",
+        "COALESCE(p.name, c.project_id)",
+        "LEFT JOIN projects p ON p.id = c.project_id",
+        "3 DESC",
+    )?;
+    let by_model = breakdown("c.model", "c.model", "", "3 DESC")?;
+    let by_agent = breakdown(
+        "COALESCE(c.agent, 'unknown')",
+        "COALESCE(c.agent, 'unknown')",
+        "",
+        "3 DESC",
+    )?;
+    let by_phase = breakdown(
+        "c.project_id || ':' || COALESCE(c.phase, '')",
+        "COALESCE(p.name, c.project_id) || ' / ' || COALESCE('Phase ' || c.phase, 'Unattributed')",
+        "LEFT JOIN projects p ON p.id = c.project_id",
+        "3 DESC",
+    )?;
+    let by_plan = breakdown(
+        "c.project_id || ':' || COALESCE(c.phase, '') || ':' || COALESCE(c.plan, '')",
+        "COALESCE(p.name, c.project_id) || ' / ' || COALESCE('Phase ' || c.phase || COALESCE(' Plan ' || c.plan, ''), 'Unattributed')",
+        "LEFT JOIN projects p ON p.id = c.project_id",
+        "3 DESC",
+    )?;
+    let by_period = breakdown(period_expr, period_expr, "", "1 ASC")?;
+
+    // Spend attributed to plans that have a synced SUMMARY.md (i.e. completed)
+    let (completed_plans, completed_cost): (i32, f64) = {
+        let sql = format!(
+            "SELECT COUNT(*), COALESCE(SUM(plan_cost), 0) FROM (
+                 SELECT SUM(c.total_cost) AS plan_cost
+                 FROM costs c
+                 JOIN gsd_summaries s
+                   ON s.project_id = c.project_id
+                  AND CAST(s.phase_number AS REAL) = CAST(c.phase AS REAL)
+                  AND s.plan_number = CAST(c.plan AS INTEGER)
+                 WHERE {} AND c.phase IS NOT NULL AND c.plan IS NOT NULL
+                 GROUP BY c.project_id, s.phase_number, s.plan_number
+             )",
+            where_clause
+        );
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        conn.query_row(&sql, params_refs.as_slice(), |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+    };
+
+    let prompt_tokens = totals.input_tokens + totals.cache_creation_tokens + totals.cache_read_tokens;
+    let (cache_hit_ratio, fresh_token_ratio) = if prompt_tokens > 0 {
+        let hit = totals.cache_read_tokens as f64 / prompt_tokens as f64;
+        (hit, 1.0 - hit)
+    } else {
+        (0.0, 0.0)
+    };
+
+    Ok(CostReport {
+        date_from,
+        date_to,
+        granularity,
+        totals,
+        by_project,
+        by_model,
+        by_agent,
+        by_phase,
+        by_plan,
+        by_period,
+        cache_hit_ratio,
+        fresh_token_ratio,
+        completed_plans,
+        cost_per_completed_plan: (completed_plans > 0)
+            .then(|| completed_cost / completed_plans as f64),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    fn default_prices() -> Vec<ModelPrice> {
+        DEFAULT_MODEL_PRICES
+            .iter()
+            .map(|(model, input, output, cache_write, cache_read)| ModelPrice {
+                model: model.to_string(),
+                input_per_mtok: *input,
+                output_per_mtok: *output,
+                cache_write_per_mtok: *cache_write,
+                cache_read_per_mtok: *cache_read,
+                is_default: true,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_transcript_dir_name() {
+        assert_eq!(transcript_dir_name("/Users/me/my_app.v2/"), "-Users-me-my-app-v2");
+    }
+
+    #[test]
+    fn test_parse_usage_line() {
+        let line = r#"{"type":"assistant","sessionId":"s1","isSidechain":false,"timestamp":"2026-03-01T10:15:30.123Z","message":{"id":"msg_1","model":"claude-sonnet-4-5","usage":{"input_tokens":10,"output_tokens":200,"cache_creation_input_tokens":300,"cache_read_input_tokens":4000}}}"#;
+        let usage = parse_usage_line(line).expect("assistant line should parse");
+        assert_eq!(usage.message_id, "msg_1");
+        assert_eq!(usage.agent, "main");
+        assert_eq!(usage.cache_read_tokens, 4000);
+        assert_eq!(usage.created_at.as_deref(), Some("2026-03-01 10:15:30"));
+
+        assert!(parse_usage_line(r#"{"type":"user","message":{"content":"hi"}}"#).is_none());
+        assert!(parse_usage_line(
+            r#"{"type":"assistant","message":{"id":"x","model":"<synthetic>","usage":{}}}"#
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_level_for_cost() {
+        let threshold = CostThreshold {
+            project_id: "p".to_string(),
+            warn_cost: 10.0,
+            alert_cost: 25.0,
+            stop_cost: 50.0,
+            enabled: true,
+            use_global: true,
+            interrupt_on_stop: false,
+            alert_acknowledged: false,
+            last_level: None,
+            stop_triggered_at: None,
+            acknowledged_at: None,
+            current_cost: 0.0,
+        };
+        assert_eq!(level_for_cost(&threshold, 9.99), None);
+        assert_eq!(level_for_cost(&threshold, 10.0), Some("warn"));
+        assert_eq!(level_for_cost(&threshold, 30.0), Some("alert"));
+        assert_eq!(level_for_cost(&threshold, 75.0), Some("stop"));
+        assert!(level_rank(Some("stop")) > level_rank(Some("alert")));
+        assert_eq!(level_rank(None), 0);
+    }
+
+    #[test]
+    fn test_price_for_prefers_longest_pattern() {
+        let prices = default_prices();
+        assert_eq!(price_for(&prices, "claude-opus-4-5-20251101").unwrap().model, "opus-4-5");
+        assert_eq!(price_for(&prices, "claude-opus-4-1-20250805").unwrap().model, "opus-4-1");
+        assert_eq!(price_for(&prices, "claude-opus-4-20250514").unwrap().input_per_mtok, 15.0);
+        assert_eq!(price_for(&prices, "claude-3-5-haiku-20241022").unwrap().model, "3-5-haiku");
+        // Unlisted versions are unpriced, not billed at an older model's rate
+        assert!(price_for(&prices, "claude-opus-5").is_none());
+        assert!(price_for(&prices, "gpt-4o").is_none());
+
+        let usage = parse_usage_line(
+            r#"{"type":"assistant","message":{"id":"m","model":"claude-sonnet-4-20250514","usage":{"input_tokens":1000000,"output_tokens":1000000}}}"#,
+        )
+        .unwrap();
+        let cost = usage_cost(price_for(&prices, &usage.model).unwrap(), &usage);
+        assert!((cost - 18.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reprice_unpriced_costs() {
+        let db = Database::open_in_memory();
+        let conn = db.conn();
+        conn.execute("INSERT INTO projects (id, name, path) VALUES ('p', 'p', '/tmp/p')", [])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO costs (id, project_id, model, input_tokens, output_tokens, total_cost, message_id)
+             VALUES ('c1', 'p', 'claude-nova-1', 1000000, 0, 0, 'm1'),
+                    ('c2', 'p', 'claude-nova-1', 0, 0, 0, 'm2')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(reprice_unpriced_costs(conn).unwrap(), 0);
+
+        conn.execute(
+            "INSERT INTO model_prices (model, input_per_mtok, output_per_mtok, cache_write_per_mtok, cache_read_per_mtok)
+             VALUES ('nova-1', 2.0, 10.0, 2.5, 0.2)",
+            [],
+        )
+        .unwrap();
+        assert_eq!(reprice_unpriced_costs(conn).unwrap(), 1);
+        let cost: f64 = conn
+            .query_row("SELECT total_cost FROM costs WHERE id = 'c1'", [], |row| row.get(0))
+            .unwrap();
+        assert!((cost - 2.0).abs() < 1e-9);
+        // Nothing left to reprice
+        assert_eq!(reprice_unpriced_costs(conn).unwrap(), 0);
+    }
+}
@@ -0,0 +1,723 @@
+// Track Your Shit - Coverage Commands
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+//
+// Imports lcov and cobertura reports into per-file coverage attached to
+// test runs, with trends over time and per-directory deltas between runs.
+
+use crate::commands::notifications::insert_notification;
+use crate::commands::test_runs::{write_test_run, xml_attrs, ParsedTestReport, RunCoverage};
+use crate::models::{
+    CoverageDirectoryDelta, CoverageImportResult, CoverageTrendPoint, CreateNotificationInput,
+    FileCoverage, ImportCoverageInput, Notification,
+};
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+type DbState = Arc<crate::db::DbPool>;
+
+/// Line coverage may dip by this many percentage points before a phase is flagged
+const COVERAGE_DROP_TOLERANCE: f64 = 0.5;
+
+/// Directories listed in a coverage drop notification
+const DROP_NOTIFICATION_DIRS: usize = 3;
+
+fn pct(covered: i64, total: i64) -> Option<f64> {
+    (total > 0).then(|| covered as f64 * 100.0 / total as f64)
+}
+
+/// Guess the coverage report format from its content
+fn detect_coverage_format(content: &str) -> Option<&'static str> {
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('<') && content.contains("<coverage") {
+        Some("cobertura")
+    } else if content.lines().any(|l| l.starts_with("SF:")) {
+        Some("lcov")
+    } else {
+        None
+    }
+}
+
+/// Make a report path relative to the project root, with forward slashes
+fn relative_path(file: &str, source: Option<&str>, project_path: &str) -> String {
+    let path = Path::new(file);
+    let full = match source {
+        Some(source) if !path.is_absolute() => Path::new(source).join(path),
+        _ => path.to_path_buf(),
+    };
+    full.strip_prefix(project_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .to_string()
+}
+
+/// Reports sometimes list a file more than once (merged runs); keep the best numbers
+fn merge_file(files: &mut BTreeMap<String, FileCoverage>, file: FileCoverage) {
+    match files.get_mut(&file.file_path) {
+        Some(existing) => {
+            existing.lines_total = existing.lines_total.max(file.lines_total);
+            existing.lines_covered = existing.lines_covered.max(file.lines_covered);
+            existing.branches_total = existing.branches_total.max(file.branches_total);
+            existing.branches_covered = existing.branches_covered.max(file.branches_covered);
+            existing.functions_total = existing.functions_total.max(file.functions_total);
+            existing.functions_covered = existing.functions_covered.max(file.functions_covered);
+        }
+        None => {
+            files.insert(file.file_path.clone(), file);
+        }
+    }
+}
+
+/// lcov tracefile (`SF:` ... `end_of_record`). Summary counters (LF/LH, BRF/BRH,
+/// FNF/FNH) are used when present, otherwise counted from DA/BRDA/FNDA records.
+fn parse_lcov(content: &str, project_path: &str) -> Vec<FileCoverage> {
+    let mut files: BTreeMap<String, FileCoverage> = BTreeMap::new();
+    let mut current: Option<FileCoverage> = None;
+    let mut counted = FileCoverage::default();
+    let mut summary = (false, false, false);
+
+    for line in content.lines().map(str::trim) {
+        let (key, value) = line.split_once(':').unwrap_or((line, ""));
+        let num = |v: &str| v.trim().parse::<i64>().unwrap_or(0);
+        let Some(file) = current.as_mut() else {
+            if key == "SF" {
+                current = Some(FileCoverage {
+                    file_path: relative_path(value, None, project_path),
+                    ..Default::default()
+                });
+                counted = FileCoverage::default();
+                summary = (false, false, false);
+            }
+            continue;
+        };
+        match key {
+            "DA" => {
+                counted.lines_total += 1;
+                if value.split(',').nth(1).is_some_and(|hits| num(hits) > 0) {
+                    counted.lines_covered += 1;
+                }
+            }
+            "BRDA" => {
+                counted.branches_total += 1;
+                if value.split(',').nth(3).is_some_and(|taken| num(taken) > 0) {
+                    counted.branches_covered += 1;
+                }
+            }
+            "FN" => counted.functions_total += 1,
+            "FNDA" if value.split(',').next().is_some_and(|hits| num(hits) > 0) => {
+                counted.functions_covered += 1
+            }
+            "LF" => {
+                file.lines_total = num(value);
+                summary.0 = true;
+            }
+            "LH" => file.lines_covered = num(value),
+            "BRF" => {
+                file.branches_total = num(value);
+                summary.1 = true;
+            }
+            "BRH" => file.branches_covered = num(value),
+            "FNF" => {
+                file.functions_total = num(value);
+                summary.2 = true;
+            }
+            "FNH" => file.functions_covered = num(value),
+            "end_of_record" => {
+                let mut file = current.take().unwrap_or_default();
+                if !summary.0 {
+                    file.lines_total = counted.lines_total;
+                    file.lines_covered = counted.lines_covered;
+                }
+                if !summary.1 {
+                    file.branches_total = counted.branches_total;
+                    file.branches_covered = counted.branches_covered;
+                }
+                if !summary.2 {
+                    file.functions_total = counted.functions_total;
+                    file.functions_covered = counted.functions_covered;
+                }
+                merge_file(&mut files, file);
+            }
+            _ => {}
+        }
+    }
+
+    files.into_values().collect()
+}
+
+/// Cobertura XML (coverage.py, istanbul, cargo-llvm-cov, JaCoCo converters).
+/// Classes sharing a filename are summed; method lines are not double counted.
+fn parse_cobertura(content: &str, project_path: &str) -> Vec<FileCoverage> {
+    static SOURCE_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"<source>([^<]*)</source>").unwrap());
+    static CLASS_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?s)<class\b([^>]*?)(?:/>|>(.*?)</class>)").unwrap());
+    static METHODS_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?s)<methods>.*?</methods>").unwrap());
+    static METHOD_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?s)<method\b([^>]*?)(?:/>|>(.*?)</method>)").unwrap());
+    static LINE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<line\b([^>]*?)/?>").unwrap());
+    static CONDITION_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\((\d+)/(\d+)\)").unwrap());
+
+    let source = SOURCE_RE
+        .captures(content)
+        .map(|c| c[1].trim().to_string())
+        .filter(|s| !s.is_empty() && s != ".");
+
+    let line_hit = |attrs: &std::collections::HashMap<String, String>| {
+        attrs
+            .get("hits")
+            .and_then(|h| h.parse::<i64>().ok())
+            .is_some_and(|h| h > 0)
+    };
+
+    let mut by_file: BTreeMap<String, FileCoverage> = BTreeMap::new();
+    for caps in CLASS_RE.captures_iter(content) {
+        let attrs = xml_attrs(&caps[1]);
+        let Some(filename) = attrs.get("filename") else {
+            continue;
+        };
+        let file_path = relative_path(filename, source.as_deref(), project_path);
+        let body = caps.get(2).map_or("", |m| m.as_str());
+        let entry = by_file.entry(file_path.clone()).or_insert_with(|| FileCoverage {
+            file_path,
+            ..Default::default()
+        });
+
+        for method in METHOD_RE.captures_iter(body) {
+            let method_attrs = xml_attrs(&method[1]);
+            let covered = method_attrs
+                .get("line-rate")
+                .and_then(|r| r.parse::<f64>().ok())
+                .map(|rate| rate > 0.0)
+                .unwrap_or_else(|| {
+                    let lines = method.get(2).map_or("", |m| m.as_str());
+                    LINE_RE
+                        .captures_iter(lines)
+                        .any(|l| line_hit(&xml_attrs(&l[1])))
+                });
+            entry.functions_total += 1;
+            entry.functions_covered += i64::from(covered);
+        }
+
+        let class_lines = METHODS_RE.replace_all(body, "");
+        for line in LINE_RE.captures_iter(&class_lines) {
+            let line_attrs = xml_attrs(&line[1]);
+            entry.lines_total += 1;
+            entry.lines_covered += i64::from(line_hit(&line_attrs));
+            if line_attrs.get("branch").is_some_and(|b| b == "true") {
+                if let Some(c) = line_attrs
+                    .get("condition-coverage")
+                    .and_then(|cc| CONDITION_RE.captures(cc))
+                {
+                    entry.branches_covered += c[1].parse::<i64>().unwrap_or(0);
+                    entry.branches_total += c[2].parse::<i64>().unwrap_or(0);
+                }
+            }
+        }
+    }
+
+    by_file.into_values().collect()
+}
+
+/// Directory bucket for a file: its first `depth` directory components
+fn directory_key(file_path: &str, depth: usize) -> String {
+    let dirs: Vec<&str> = file_path.split('/').collect();
+    let dirs = &dirs[..dirs.len().saturating_sub(1)];
+    if dirs.is_empty() || depth == 0 {
+        ".".to_string()
+    } else {
+        dirs[..dirs.len().min(depth)].join("/")
+    }
+}
+
+/// Line coverage per directory for two runs, biggest drops first
+fn directory_deltas(
+    base: &[FileCoverage],
+    head: &[FileCoverage],
+    depth: usize,
+) -> Vec<CoverageDirectoryDelta> {
+    let mut dirs: BTreeMap<String, [i64; 4]> = BTreeMap::new();
+    for f in base {
+        let entry = dirs.entry(directory_key(&f.file_path, depth)).or_default();
+        entry[0] += f.lines_covered;
+        entry[1] += f.lines_total;
+    }
+    for f in head {
+        let entry = dirs.entry(directory_key(&f.file_path, depth)).or_default();
+        entry[2] += f.lines_covered;
+        entry[3] += f.lines_total;
+    }
+
+    let mut deltas: Vec<CoverageDirectoryDelta> = dirs
+        .into_iter()
+        .map(|(directory, [bc, bt, hc, ht])| {
+            let base_pct = pct(bc, bt);
+            let head_pct = pct(hc, ht);
+            CoverageDirectoryDelta {
+                directory,
+                base_lines_covered: bc,
+                base_lines_total: bt,
+                head_lines_covered: hc,
+                head_lines_total: ht,
+                base_pct,
+                head_pct,
+                delta: base_pct.zip(head_pct).map(|(b, h)| h - b),
+            }
+        })
+        .collect();
+    deltas.sort_by(|a, b| match (a.delta, b.delta) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.directory.cmp(&b.directory),
+    });
+    deltas
+}
+
+fn load_coverage_files(conn: &Connection, test_run_id: &str) -> Result<Vec<FileCoverage>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT file_path, lines_total, lines_covered, branches_total, branches_covered,
+                    functions_total, functions_covered
+             FROM test_coverage_files WHERE test_run_id = ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let files = stmt
+        .query_map(params![test_run_id], |row| {
+            Ok(FileCoverage {
+                file_path: row.get(0)?,
+                lines_total: row.get(1)?,
+                lines_covered: row.get(2)?,
+                branches_total: row.get(3)?,
+                branches_covered: row.get(4)?,
+                functions_total: row.get(5)?,
+                functions_covered: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(files)
+}
+
+/// Compare a completed phase's latest coverage against the last run before the
+/// phase started, and add a notification when line coverage dropped
+pub(crate) fn check_phase_coverage_drop(
+    conn: &Connection,
+    project_id: &str,
+    phase: &str,
+) -> Result<Option<Notification>, String> {
+    let head: Option<(String, f64, String)> = conn
+        .query_row(
+            "SELECT id, coverage_lines,
+                    (SELECT MIN(created_at) FROM test_runs
+                     WHERE project_id = ?1 AND CAST(phase AS REAL) = CAST(?2 AS REAL)
+                       AND coverage_lines IS NOT NULL)
+             FROM test_runs
+             WHERE project_id = ?1 AND CAST(phase AS REAL) = CAST(?2 AS REAL)
+               AND coverage_lines IS NOT NULL
+             ORDER BY created_at DESC, rowid DESC LIMIT 1",
+            params![project_id, phase],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let Some((head_id, head_lines, phase_started)) = head else {
+        return Ok(None);
+    };
+
+    let base: Option<(String, f64)> = conn
+        .query_row(
+            "SELECT id, coverage_lines FROM test_runs
+             WHERE project_id = ?1 AND coverage_lines IS NOT NULL AND created_at < ?3
+               AND (phase IS NULL OR CAST(phase AS REAL) != CAST(?2 AS REAL))
+             ORDER BY created_at DESC, rowid DESC LIMIT 1",
+            params![project_id, phase, phase_started],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let Some((base_id, base_lines)) = base else {
+        return Ok(None);
+    };
+    if head_lines >= base_lines - COVERAGE_DROP_TOLERANCE {
+        return Ok(None);
+    }
+
+    let read_setting = |key: &str| -> Option<String> {
+        conn.query_row("SELECT value FROM settings WHERE key = ?1", params![key], |row| {
+            row.get(0)
+        })
+        .ok()
+    };
+    if !(read_setting("notifications_enabled").is_none_or(|v| v == "true")
+        && read_setting("notify_on_phase_complete").is_none_or(|v| v == "true"))
+    {
+        return Ok(None);
+    }
+
+    let worst: Vec<String> = directory_deltas(
+        &load_coverage_files(conn, &base_id)?,
+        &load_coverage_files(conn, &head_id)?,
+        2,
+    )
+    .into_iter()
+    .filter_map(|d| d.delta.filter(|delta| *delta < 0.0).map(|delta| (d.directory, delta)))
+    .take(DROP_NOTIFICATION_DIRS)
+    .map(|(dir, delta)| format!("{} ({:+.1})", dir, delta))
+    .collect();
+
+    let mut message = format!(
+        "Line coverage fell from {:.1}% to {:.1}% ({:+.1} pts).",
+        base_lines,
+        head_lines,
+        head_lines - base_lines
+    );
+    if !worst.is_empty() {
+        message.push_str(&format!(" Biggest drops: {}.", worst.join(", ")));
+    }
+
+    insert_notification(
+        conn,
+        &CreateNotificationInput {
+            project_id: Some(project_id.to_string()),
+            notification_type: "warning".to_string(),
+            title: format!("Coverage dropped in phase {}", phase),
+            message,
+            link: Some(format!("/projects/{}", project_id)),
+        },
+    )
+    .map(Some)
+}
+
+/// Import an lcov or cobertura report as per-file coverage for a test run
+#[tauri::command]
+pub async fn import_coverage_report(
+    db: tauri::State<'_, DbState>,
+    input: ImportCoverageInput,
+) -> Result<CoverageImportResult, String> {
+    let project_path: String = {
+        let conn = db.read().await;
+        conn.query_row(
+            "SELECT path FROM projects WHERE id = ?1",
+            params![input.project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Project not found: {}", e))?
+    };
+
+    // Read and parse the report (and, for a new run, its phase and commit)
+    // before taking the write lock
+    let parse_input = input.clone();
+    let (files, new_run) = tauri::async_runtime::spawn_blocking(move || {
+        let input = parse_input;
+        let content = match (&input.content, &input.report_path) {
+            (Some(content), _) => content.clone(),
+            (None, Some(report_path)) => {
+                let path = Path::new(report_path);
+                let path = if path.is_absolute() {
+                    path.to_path_buf()
+                } else {
+                    Path::new(&project_path).join(path)
+                };
+                fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read report {}: {}", path.display(), e))?
+            }
+            (None, None) => return Err("Provide either content or report_path".to_string()),
+        };
+
+        let format = match input.format.as_deref() {
+            Some(f) => f.to_lowercase(),
+            None => detect_coverage_format(&content)
+                .ok_or_else(|| "Could not detect coverage report format".to_string())?
+                .to_string(),
+        };
+        let files = match format.as_str() {
+            "lcov" => parse_lcov(&content, &project_path),
+            "cobertura" => parse_cobertura(&content, &project_path),
+            other => {
+                return Err(format!(
+                    "Unsupported coverage format '{}'. Use lcov or cobertura.",
+                    other
+                ))
+            }
+        };
+        if files.is_empty() {
+            return Err(format!("No files found in {} report", format));
+        }
+
+        let new_run = input.test_run_id.is_none().then(|| {
+            let phase = input.phase.clone().or_else(|| {
+                input
+                    .use_current_phase
+                    .then(|| crate::commands::gsd::read_current_phase_plan(&project_path).0)
+                    .flatten()
+            });
+            (phase, crate::commands::test_runs::worktree_state(&project_path))
+        });
+        Ok((files, new_run))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let db = db.write().await;
+    let conn = db.conn();
+
+    if let Some(id) = &input.test_run_id {
+        let run_project: String = conn
+            .query_row(
+                "SELECT project_id FROM test_runs WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Test run not found: {}", e))?;
+        if run_project != input.project_id {
+            return Err("Test run belongs to a different project".to_string());
+        }
+    }
+
+    // The new run (if any) and its coverage rows are written together, so a
+    // failed import leaves no empty run behind
+    let sum = |f: fn(&FileCoverage) -> i64| files.iter().map(f).sum::<i64>();
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let import = (|| -> Result<String, String> {
+        // `new_run` is prepared exactly when no test_run_id was given
+        let test_run_id = match &new_run {
+            Some((phase, commit_sha)) => write_test_run(
+                conn,
+                &input.project_id,
+                None,
+                phase.as_deref(),
+                commit_sha.as_deref(),
+                &ParsedTestReport::default(),
+                RunCoverage::default(),
+            )?,
+            None => input.test_run_id.clone().unwrap_or_default(),
+        };
+        conn.execute(
+            "DELETE FROM test_coverage_files WHERE test_run_id = ?1",
+            params![test_run_id],
+        )
+        .map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "INSERT INTO test_coverage_files (id, test_run_id, project_id, file_path, lines_total,
+                     lines_covered, branches_total, branches_covered, functions_total, functions_covered)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )
+            .map_err(|e| e.to_string())?;
+        for f in &files {
+            stmt.execute(params![
+                format!("{:032x}", rand::random::<u128>()),
+                test_run_id,
+                input.project_id,
+                f.file_path,
+                f.lines_total,
+                f.lines_covered,
+                f.branches_total,
+                f.branches_covered,
+                f.functions_total,
+                f.functions_covered,
+            ])
+            .map_err(|e| e.to_string())?;
+        }
+        conn.execute(
+            "UPDATE test_runs SET coverage_lines = ?2, coverage_branches = ?3, coverage_functions = ?4
+             WHERE id = ?1",
+            params![
+                test_run_id,
+                pct(sum(|f| f.lines_covered), sum(|f| f.lines_total)),
+                pct(sum(|f| f.branches_covered), sum(|f| f.branches_total)),
+                pct(sum(|f| f.functions_covered), sum(|f| f.functions_total)),
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(test_run_id)
+    })();
+    let test_run_id = match import {
+        Ok(test_run_id) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            test_run_id
+        }
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+    };
+
+    Ok(CoverageImportResult {
+        test_run: crate::commands::test_runs::fetch_test_run(conn, &test_run_id)?,
+        files_imported: files.len() as i32,
+    })
+}
+
+/// Coverage over time for a project, oldest first. With `path_prefix` the
+/// percentages are computed from per-file coverage under that path.
+#[tauri::command]
+pub async fn get_coverage_trend(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    path_prefix: Option<String>,
+    limit: Option<i32>,
+) -> Result<Vec<CoverageTrendPoint>, String> {
+    let conn = db.read().await;
+    let limit = limit.unwrap_or(50);
+
+    let mut points = match path_prefix.filter(|p| !p.is_empty()) {
+        None => {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, phase, commit_sha, created_at, coverage_lines, coverage_branches,
+                            coverage_functions
+                     FROM test_runs
+                     WHERE project_id = ?1 AND coverage_lines IS NOT NULL
+                     ORDER BY created_at DESC, rowid DESC LIMIT ?2",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![project_id, limit], |row| {
+                    Ok(CoverageTrendPoint {
+                        test_run_id: row.get(0)?,
+                        phase: row.get(1)?,
+                        commit_sha: row.get(2)?,
+                        created_at: row.get(3)?,
+                        lines_pct: row.get(4)?,
+                        branches_pct: row.get(5)?,
+                        functions_pct: row.get(6)?,
+                    })
+                })
+                .map_err(|e| e.to_string())?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?;
+            rows
+        }
+        Some(prefix) => {
+            let prefix = prefix.trim_start_matches("./").trim_end_matches('/').to_string();
+            let mut stmt = conn
+                .prepare(
+                    "SELECT t.id, t.phase, t.commit_sha, t.created_at,
+                            SUM(f.lines_covered), SUM(f.lines_total),
+                            SUM(f.branches_covered), SUM(f.branches_total),
+                            SUM(f.functions_covered), SUM(f.functions_total)
+                     FROM test_runs t
+                     JOIN test_coverage_files f ON f.test_run_id = t.id
+                     WHERE t.project_id = ?1 AND (f.file_path = ?2 OR substr(f.file_path, 1, length(?2) + 1) = ?2 || '/')
+                     GROUP BY t.id
+                     ORDER BY t.created_at DESC, t.rowid DESC LIMIT ?3",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![project_id, prefix, limit], |row| {
+                    Ok(CoverageTrendPoint {
+                        test_run_id: row.get(0)?,
+                        phase: row.get(1)?,
+                        commit_sha: row.get(2)?,
+                        created_at: row.get(3)?,
+                        lines_pct: pct(row.get(4)?, row.get(5)?),
+                        branches_pct: pct(row.get(6)?, row.get(7)?),
+                        functions_pct: pct(row.get(8)?, row.get(9)?),
+                    })
+                })
+                .map_err(|e| e.to_string())?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?;
+            rows
+        }
+    };
+
+    points.reverse();
+    Ok(points)
+}
+
+/// Per-directory line coverage change between two runs, biggest drops first.
+/// `depth` is how many directory levels to group by (default 2).
+#[tauri::command]
+pub async fn get_coverage_delta(
+    db: tauri::State<'_, DbState>,
+    base_run_id: String,
+    head_run_id: String,
+    depth: Option<usize>,
+) -> Result<Vec<CoverageDirectoryDelta>, String> {
+    let conn = db.read().await;
+    let base = load_coverage_files(&conn, &base_run_id)?;
+    let head = load_coverage_files(&conn, &head_run_id)?;
+    if base.is_empty() || head.is_empty() {
+        return Err("Both runs need imported per-file coverage".to_string());
+    }
+    Ok(directory_deltas(&base, &head, depth.unwrap_or(2)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lcov() {
+        let lcov = "TN:\nSF:/work/app/src/lib/a.ts\nFNF:2\nFNH:1\nDA:1,3\nDA:2,0\nLF:2\nLH:1\nBRF:4\nBRH:3\nend_of_record\nSF:src/b.ts\nDA:1,1\nDA:2,1\nBRDA:1,0,0,1\nBRDA:1,0,1,-\nend_of_record\n";
+        assert_eq!(detect_coverage_format(lcov), Some("lcov"));
+        let files = parse_lcov(lcov, "/work/app");
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].file_path, "src/b.ts");
+        assert_eq!((files[0].lines_covered, files[0].lines_total), (2, 2));
+        assert_eq!((files[0].branches_covered, files[0].branches_total), (1, 2));
+        assert_eq!(files[1].file_path, "src/lib/a.ts");
+        assert_eq!((files[1].functions_covered, files[1].functions_total), (1, 2));
+        assert_eq!((files[1].branches_covered, files[1].branches_total), (3, 4));
+    }
+
+    #[test]
+    fn test_parse_cobertura() {
+        let xml = r#"<?xml version="1.0" ?>
+<coverage line-rate="0.75">
+  <sources><source>/work/app</source></sources>
+  <packages><package name="api"><classes>
+    <class name="handlers" filename="api/handlers.py" line-rate="0.75">
+      <methods>
+        <method name="get" line-rate="1.0"><lines><line number="2" hits="1"/></lines></method>
+        <method name="put" line-rate="0.0"><lines><line number="5" hits="0"/></lines></method>
+      </methods>
+      <lines>
+        <line number="1" hits="1"/>
+        <line number="2" hits="1" branch="true" condition-coverage="50% (1/2)"/>
+        <line number="4" hits="1"/>
+        <line number="5" hits="0"/>
+      </lines>
+    </class>
+  </classes></package></packages>
+</coverage>"#;
+        assert_eq!(detect_coverage_format(xml), Some("cobertura"));
+        let files = parse_cobertura(xml, "/work/app");
+        assert_eq!(files.len(), 1);
+        let f = &files[0];
+        assert_eq!(f.file_path, "api/handlers.py");
+        assert_eq!((f.lines_covered, f.lines_total), (3, 4));
+        assert_eq!((f.branches_covered, f.branches_total), (1, 2));
+        assert_eq!((f.functions_covered, f.functions_total), (1, 2));
+    }
+
+    #[test]
+    fn test_directory_deltas() {
+        let file = |path: &str, covered: i64, total: i64| FileCoverage {
+            file_path: path.to_string(),
+            lines_covered: covered,
+            lines_total: total,
+            ..Default::default()
+        };
+        let base = vec![file("src/api/a.ts", 8, 10), file("src/ui/b.ts", 5, 10), file("main.rs", 1, 1)];
+        let head = vec![file("src/api/a.ts", 5, 10), file("src/ui/b.ts", 6, 10), file("src/new/c.ts", 0, 4)];
+        let deltas = directory_deltas(&base, &head, 2);
+        assert_eq!(deltas[0].directory, "src/api");
+        assert_eq!(deltas[0].delta.map(|d| d.round()), Some(-30.0));
+        assert_eq!(deltas[1].directory, "src/ui");
+        assert!(deltas[2..].iter().all(|d| d.delta.is_none()));
+        assert_eq!(directory_key("README.md", 2), ".");
+        assert_eq!(directory_key("a/b/c/d.rs", 1), "a");
+    }
+}
@@ -356,6 +356,7 @@ pub async fn clear_selected_data(
                     .map_err(|e| e.to_string())?;
             }
             "costs" => {
+                conn.execute("DELETE FROM cost_ingest_state", []).ok();
                 conn.execute("DELETE FROM costs", [])
                     .map_err(|e| e.to_string())?;
             }
@@ -393,6 +394,7 @@ pub async fn clear_selected_data(
                 conn.execute("DELETE FROM gsd_phase_research", []).ok();
             }
             "tests" => {
+                conn.execute("DELETE FROM test_coverage_files", []).ok();
                 conn.execute("DELETE FROM test_results", []).ok();
                 conn.execute("DELETE FROM test_runs", []).ok();
                 conn.execute("DELETE FROM flaky_tests", []).ok();
@@ -400,6 +402,8 @@ pub async fn clear_selected_data(
             "knowledge" => {
                 conn.execute("DELETE FROM knowledge_bookmarks", []).ok();
                 conn.execute("DELETE FROM knowledge", []).ok();
+                conn.execute("DELETE FROM knowledge_tags", []).ok();
+                conn.execute("DELETE FROM knowledge_custom_categories", []).ok();
             }
             _ => {
                 tracing::warn!("Unknown data category: {}", category);
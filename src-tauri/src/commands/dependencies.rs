@@ -1,10 +1,13 @@
 // Track Your Shit - Dependency Status Commands
 // Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
 
+use crate::commands::audit_history::{record_audit_snapshot, SnapshotPackage};
+use crate::commands::lockfiles::build_inventory;
 use crate::models::DependencyStatus;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 use std::path::Path;
 use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
 
 type DbState = Arc<crate::db::DbPool>;
 
@@ -278,7 +281,7 @@ pub struct AuditResult {
 }
 
 impl AuditResult {
-    fn empty(ecosystem: &str) -> Self {
+    pub(crate) fn empty(ecosystem: &str) -> Self {
         AuditResult {
             ecosystem: ecosystem.to_string(),
             vulnerabilities: vec![],
@@ -292,14 +295,14 @@ impl AuditResult {
         }
     }
 
-    fn with_error(ecosystem: &str, error: impl Into<String>) -> Self {
+    pub(crate) fn with_error(ecosystem: &str, error: impl Into<String>) -> Self {
         AuditResult {
             error: Some(error.into()),
             ..Self::empty(ecosystem)
         }
     }
 
-    fn tally(mut self) -> Self {
+    pub(crate) fn tally(mut self) -> Self {
         self.total = self.vulnerabilities.len();
         self.critical = self.vulnerabilities.iter().filter(|v| v.severity == "critical").count();
         self.high = self.vulnerabilities.iter().filter(|v| v.severity == "high").count();
@@ -310,9 +313,12 @@ impl AuditResult {
 }
 
 /// Detect ecosystems present in the project and run the appropriate audit tools.
-/// Returns one AuditResult per detected ecosystem.
+/// Returns one AuditResult per detected ecosystem. Runs for a known project are
+/// kept as audit snapshots (see audit_history).
 #[tauri::command]
 pub async fn run_dependency_audit(
+    app: AppHandle,
+    db: tauri::State<'_, DbState>,
     project_path: String,
 ) -> Result<Vec<AuditResult>, String> {
     let path = std::path::Path::new(&project_path);
@@ -333,9 +339,56 @@ pub async fn run_dependency_audit(
         results.push(audit_pip(&project_path).await);
     }
 
+    if let Err(e) = snapshot_audit(&app, &db, &project_path, &results).await {
+        tracing::warn!("Failed to record audit snapshot for {}: {}", project_path, e);
+    }
+
     Ok(results)
 }
 
+/// Record an audit run with the project's locked packages, emitting a
+/// notification when it turned up new high/critical vulnerabilities
+async fn snapshot_audit(
+    app: &AppHandle,
+    db: &DbState,
+    project_path: &str,
+    results: &[AuditResult],
+) -> Result<(), String> {
+    let project_id: Option<String> = {
+        let conn = db.read().await;
+        conn.query_row(
+            "SELECT id FROM projects WHERE path = ?1",
+            params![project_path],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+    };
+    let Some(project_id) = project_id else {
+        return Ok(());
+    };
+
+    let path = project_path.to_string();
+    let inventory = tauri::async_runtime::spawn_blocking(move || build_inventory(&path))
+        .await
+        .map_err(|e| e.to_string())?;
+    let packages: Vec<SnapshotPackage> = inventory
+        .packages
+        .into_iter()
+        .map(|p| SnapshotPackage {
+            ecosystem: p.ecosystem,
+            name: p.name,
+            version: p.version,
+        })
+        .collect();
+
+    let db = db.write().await;
+    if let Some(notification) = record_audit_snapshot(db.conn(), &project_id, results, &packages)? {
+        let _ = app.emit("notification:new", &notification);
+    }
+    Ok(())
+}
+
 // ----- npm audit -----
 
 async fn audit_npm(project_path: &str) -> AuditResult {
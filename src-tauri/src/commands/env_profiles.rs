@@ -0,0 +1,268 @@
+// Track Your Shit - Env Profile Commands
+// Per-project environment profiles (keychain secrets and literals) for PTY sessions
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use crate::commands::auto_commands::project_for_path;
+use crate::commands::secrets::{read_secret, DEFAULT_SERVICE};
+use crate::models::{EnvProfile, EnvProfileInput, EnvProfileVar};
+use crate::pty::SessionOptions;
+use rusqlite::{params, Connection};
+use std::sync::Arc;
+
+type DbState = Arc<crate::db::DbPool>;
+
+const ENV_PROFILE_COLUMNS: &str = "id, project_id, name, is_default, vars_json, created_at, updated_at";
+
+fn row_to_env_profile(row: &rusqlite::Row) -> rusqlite::Result<EnvProfile> {
+    let vars_json: String = row.get(4)?;
+    Ok(EnvProfile {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        name: row.get(2)?,
+        is_default: row.get(3)?,
+        vars: serde_json::from_str(&vars_json).unwrap_or_default(),
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn fetch_env_profile(conn: &Connection, id: &str) -> Result<EnvProfile, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM env_profiles WHERE id = ?1", ENV_PROFILE_COLUMNS),
+        params![id],
+        row_to_env_profile,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Whether `name` can be used as an environment variable name
+pub(crate) fn is_valid_env_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn validate_input(input: &EnvProfileInput) -> Result<(), String> {
+    if input.name.trim().is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    let mut seen = std::collections::HashSet::new();
+    for var in &input.vars {
+        if !is_valid_env_name(&var.name) {
+            return Err(format!("Invalid environment variable name '{}'", var.name));
+        }
+        if !seen.insert(var.name.as_str()) {
+            return Err(format!("Variable '{}' is set more than once", var.name));
+        }
+        match var.source.as_str() {
+            "literal" => {}
+            "secret" if var.value.is_empty() => {
+                return Err(format!("Variable '{}' needs a keychain key", var.name));
+            }
+            "secret" => {}
+            other => return Err(format!("Invalid source '{}'. Use secret or literal.", other)),
+        }
+    }
+    Ok(())
+}
+
+/// Make `id` the project's only default profile
+fn set_default(conn: &Connection, project_id: &str, id: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE env_profiles SET is_default = (id = ?2) WHERE project_id = ?1",
+        params![project_id, id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Resolve profile vars to (name, value) pairs, reading secrets from the
+/// keychain. The secret values are also returned for output masking.
+pub(crate) fn session_env(vars: &[EnvProfileVar]) -> Result<SessionOptions, String> {
+    let mut env = SessionOptions::default();
+    for var in vars {
+        let value = if var.source == "secret" {
+            let secret = read_secret(DEFAULT_SERVICE, &var.value)?.ok_or_else(|| {
+                format!("Secret '{}' for {} is not in the keychain", var.value, var.name)
+            })?;
+            env.masked.push(secret.clone());
+            secret
+        } else {
+            var.value.clone()
+        };
+        env.env.push((var.name.clone(), value));
+    }
+    Ok(env)
+}
+
+/// The profile vars for a session in `working_dir`: `profile_id` if given
+/// (it must be one of the containing project's), otherwise that project's
+/// default profile (if any)
+pub(crate) fn session_profile_vars(
+    conn: &Connection,
+    working_dir: &str,
+    profile_id: Option<&str>,
+) -> Result<Vec<EnvProfileVar>, String> {
+    let project = project_for_path(conn, working_dir);
+    if let Some(id) = profile_id {
+        let profile = fetch_env_profile(conn, id).map_err(|_| format!("Env profile {} not found", id))?;
+        if project.as_ref().is_none_or(|(project_id, _)| *project_id != profile.project_id) {
+            return Err(format!("Env profile '{}' belongs to another project", profile.name));
+        }
+        return Ok(profile.vars);
+    }
+    let Some((project_id, _)) = project else {
+        return Ok(Vec::new());
+    };
+    let vars_json: Option<String> = conn
+        .query_row(
+            "SELECT vars_json FROM env_profiles WHERE project_id = ?1 AND is_default = 1 LIMIT 1",
+            params![project_id],
+            |row| row.get(0),
+        )
+        .ok();
+    Ok(vars_json
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+/// Secret keys referenced by any profile of the project containing `working_dir`,
+/// so a reattached session masks whichever profile it was started with
+pub(crate) fn project_secret_vars(conn: &Connection, working_dir: &str) -> Vec<EnvProfileVar> {
+    let Some((project_id, _)) = project_for_path(conn, working_dir) else {
+        return Vec::new();
+    };
+    let Ok(mut stmt) = conn.prepare("SELECT vars_json FROM env_profiles WHERE project_id = ?1") else {
+        return Vec::new();
+    };
+    stmt.query_map(params![project_id], |row| row.get::<_, String>(0))
+        .map(|rows| {
+            rows.filter_map(|r| r.ok())
+                .flat_map(|json| serde_json::from_str::<Vec<EnvProfileVar>>(&json).unwrap_or_default())
+                .filter(|var| var.source == "secret")
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Secret values to mask for `vars`, skipping keys that can't be read
+pub(crate) fn masked_values(vars: &[EnvProfileVar]) -> Vec<String> {
+    vars.iter()
+        .filter(|var| var.source == "secret")
+        .filter_map(|var| read_secret(DEFAULT_SERVICE, &var.value).ok().flatten())
+        .collect()
+}
+
+/// List a project's env profiles, default first
+#[tauri::command]
+pub async fn list_env_profiles(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+) -> Result<Vec<EnvProfile>, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM env_profiles
+             WHERE project_id = ?1
+             ORDER BY is_default DESC, name ASC",
+            ENV_PROFILE_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let profiles = stmt
+        .query_map(params![project_id], row_to_env_profile)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(profiles)
+}
+
+/// Create an env profile. A project's first profile becomes its default.
+#[tauri::command]
+pub async fn create_env_profile(
+    db: tauri::State<'_, DbState>,
+    input: EnvProfileInput,
+) -> Result<EnvProfile, String> {
+    validate_input(&input)?;
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let profile_id = format!("{:032x}", rand::random::<u128>());
+    let existing: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM env_profiles WHERE project_id = ?1",
+            params![input.project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let vars_json = serde_json::to_string(&input.vars).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO env_profiles (id, project_id, name, vars_json) VALUES (?1, ?2, ?3, ?4)",
+        params![profile_id, input.project_id, input.name.trim(), vars_json],
+    )
+    .map_err(|e| e.to_string())?;
+
+    if input.is_default.unwrap_or(existing == 0) {
+        set_default(conn, &input.project_id, &profile_id)?;
+    }
+
+    fetch_env_profile(conn, &profile_id)
+}
+
+/// Update an env profile's name, variables and default flag
+#[tauri::command]
+pub async fn update_env_profile(
+    db: tauri::State<'_, DbState>,
+    id: String,
+    input: EnvProfileInput,
+) -> Result<EnvProfile, String> {
+    validate_input(&input)?;
+    let db = db.write().await;
+    let conn = db.conn();
+
+    // Profiles don't move between projects; the stored project is the one
+    // whose other defaults get cleared
+    let project_id = fetch_env_profile(conn, &id)
+        .map_err(|_| format!("Env profile {} not found", id))?
+        .project_id;
+
+    let vars_json = serde_json::to_string(&input.vars).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE env_profiles SET name = ?1, vars_json = ?2, updated_at = datetime('now')
+         WHERE id = ?3",
+        params![input.name.trim(), vars_json, id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    match input.is_default {
+        Some(true) => set_default(conn, &project_id, &id)?,
+        Some(false) => {
+            conn.execute(
+                "UPDATE env_profiles SET is_default = 0 WHERE id = ?1",
+                params![id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        None => {}
+    }
+
+    fetch_env_profile(conn, &id)
+}
+
+/// Delete an env profile
+#[tauri::command]
+pub async fn delete_env_profile(
+    db: tauri::State<'_, DbState>,
+    id: String,
+) -> Result<bool, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let count = conn
+        .execute("DELETE FROM env_profiles WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(count > 0)
+}
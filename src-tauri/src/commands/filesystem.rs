@@ -1490,6 +1490,7 @@ pub async fn index_project_markdown(
             category: Some("reference".to_string()),
             source: Some(format!("scan://{}", file.relative_path)),
             metadata: Some(metadata),
+            tags: None,
         });
 
         // Emit progress every 10 files
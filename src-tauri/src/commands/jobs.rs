@@ -0,0 +1,670 @@
+// Track Your Shit - Job Queue Commands
+// Persistent queue of headless Claude/shell jobs with concurrency limits and retries
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use crate::db::DbPool;
+use crate::models::{Job, JobInput};
+use crate::pty::session_state::strip_ansi;
+use crate::pty::{OutputSink, SessionOptions, ShellProfile, TerminalManager, TerminalManagerState};
+use crate::security::shell_escape_path;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+
+type DbState = Arc<crate::db::DbPool>;
+
+/// How often running jobs are checked and queued jobs started
+const JOB_POLL_INTERVAL_SECS: u64 = 2;
+
+/// Job output kept in memory and in the database (bytes, tail)
+const MAX_LOG_BYTES: usize = 256 * 1024;
+
+const MAX_RETRIES: i32 = 5;
+
+/// Shell jobs run under, so the exit-code wrapper has one syntax
+const JOB_SHELL: &str = "/bin/sh";
+
+/// Runtime side of the job queue: output of running native jobs and the
+/// runner's wakeup. Job state itself lives in the `jobs` table.
+pub struct JobQueue {
+    /// Exit code files written by the job wrapper
+    dir: PathBuf,
+    logs: StdMutex<HashMap<String, Arc<StdMutex<Vec<u8>>>>>,
+    wake: Notify,
+}
+
+pub type JobQueueState = Arc<JobQueue>;
+
+impl JobQueue {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            logs: StdMutex::new(HashMap::new()),
+            wake: Notify::new(),
+        }
+    }
+
+    fn exit_path(&self, job_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.exit", job_id))
+    }
+
+    /// The exit code the wrapper recorded, once the job's command has finished
+    fn exit_code(&self, job_id: &str) -> Option<i32> {
+        std::fs::read_to_string(self.exit_path(job_id))
+            .ok()
+            .and_then(|code| code.trim().parse().ok())
+    }
+
+    fn take_log(&self, job_id: &str) -> Option<Vec<u8>> {
+        let buf = self.logs.lock().ok()?.remove(job_id)?;
+        let log = buf.lock().ok()?.clone();
+        Some(log)
+    }
+
+    fn live_log(&self, job_id: &str) -> Option<Vec<u8>> {
+        let logs = self.logs.lock().ok()?;
+        let log = logs.get(job_id)?.lock().ok()?.clone();
+        Some(log)
+    }
+}
+
+const JOB_COLUMNS: &str = "id, project_id, kind, command, status, attempts, max_retries, exit_code, error,
+     session_id, tmux_name, created_at, started_at, finished_at, updated_at";
+
+fn row_to_job(row: &rusqlite::Row) -> rusqlite::Result<Job> {
+    Ok(Job {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        kind: row.get(2)?,
+        command: row.get(3)?,
+        status: row.get(4)?,
+        attempts: row.get(5)?,
+        max_retries: row.get(6)?,
+        exit_code: row.get(7)?,
+        error: row.get(8)?,
+        session_id: row.get(9)?,
+        tmux_name: row.get(10)?,
+        created_at: row.get(11)?,
+        started_at: row.get(12)?,
+        finished_at: row.get(13)?,
+        updated_at: row.get(14)?,
+    })
+}
+
+fn fetch_job(conn: &Connection, id: &str) -> Result<Job, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS),
+        params![id],
+        row_to_job,
+    )
+    .map_err(|e| format!("Job not found: {}", e))
+}
+
+fn read_limit(conn: &Connection, key: &str, default: i64) -> i64 {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        params![key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(default)
+    .max(1)
+}
+
+/// Plain-text tail of a job's terminal output
+fn log_text(raw: &[u8]) -> String {
+    let text = strip_ansi(&String::from_utf8_lossy(raw)).replace("\r\n", "\n");
+    let text = text.trim_end();
+    if text.len() <= MAX_LOG_BYTES {
+        return text.to_string();
+    }
+    let mut start = text.len() - MAX_LOG_BYTES;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    text[start..].to_string()
+}
+
+/// The command line a job runs: records its exit code for the runner, which
+/// also covers tmux jobs that outlive an app restart. The subshell keeps an
+/// `exit` in the command from skipping the record.
+fn job_command_line(job: &Job, exit_path: &Path) -> String {
+    let command = match job.kind.as_str() {
+        "claude" => format!(
+            "claude -p --dangerously-skip-permissions '{}'",
+            shell_escape_path(&job.command)
+        ),
+        _ => job.command.clone(),
+    };
+    format!(
+        "(\n{}\n)\n__tys_status=$?; printf '%s' \"$__tys_status\" > '{}'; exit \"$__tys_status\"",
+        command,
+        shell_escape_path(&exit_path.to_string_lossy())
+    )
+}
+
+fn emit_job(app: &AppHandle, conn: &Connection, id: &str) {
+    if let Ok(job) = fetch_job(conn, id) {
+        let _ = app.emit("job:updated", &job);
+    }
+}
+
+/// Record a finished run. Failures go back to the queue while retries remain.
+fn complete_job(
+    conn: &Connection,
+    id: &str,
+    exit_code: Option<i32>,
+    error: Option<String>,
+    log: Option<String>,
+) -> Result<(), String> {
+    let (attempts, max_retries): (i32, i32) = conn
+        .query_row(
+            "SELECT attempts, max_retries FROM jobs WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    let status = match exit_code {
+        Some(0) => "succeeded",
+        _ if attempts <= max_retries => "queued",
+        _ => "failed",
+    };
+    let error = match exit_code {
+        Some(0) => None,
+        Some(code) => Some(error.unwrap_or_else(|| format!("Exited with code {}", code))),
+        None => Some(error.unwrap_or_else(|| "Job was interrupted".to_string())),
+    };
+    conn.execute(
+        "UPDATE jobs SET status = ?2, exit_code = ?3, error = ?4, log = COALESCE(?5, log),
+             finished_at = CASE WHEN ?2 = 'queued' THEN NULL ELSE datetime('now') END,
+             updated_at = datetime('now')
+         WHERE id = ?1 AND status = 'running'",
+        params![id, status, exit_code, error, log],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Release a job's terminal: close its PTY session, or kill a tmux session
+/// left from before a restart
+async fn release_session(app: &AppHandle, job: &Job) {
+    let Some(terminal_manager) = app.try_state::<TerminalManagerState>() else {
+        return;
+    };
+    let mut tm = terminal_manager.lock().await;
+    let attached = job
+        .session_id
+        .as_deref()
+        .is_some_and(|sid| tm.close(app, sid).is_ok());
+    if !attached {
+        if let Some(name) = &job.tmux_name {
+            TerminalManager::kill_tmux_session(name);
+        }
+    }
+}
+
+/// The job's output so far: tmux scrollback, or the native session's buffer
+fn job_output(queue: &JobQueue, job: &Job, take: bool) -> Option<String> {
+    let raw = match &job.tmux_name {
+        Some(name) => TerminalManager::capture_tmux_output(name).map(|out| {
+            // Drop tmux's "Pane is dead (status N, ...)" footer
+            match out.trim_end().rsplit_once('\n') {
+                Some((body, last)) if last.starts_with("Pane is dead") => body.as_bytes().to_vec(),
+                _ => out.into_bytes(),
+            }
+        }),
+        None if take => queue.take_log(&job.id),
+        None => queue.live_log(&job.id),
+    };
+    raw.map(|raw| log_text(&raw))
+}
+
+/// Finish running jobs whose command has exited
+async fn reconcile_running(app: &AppHandle, pool: &DbPool, queue: &JobQueue) -> Result<(), String> {
+    let running: Vec<Job> = {
+        let conn = pool.read().await;
+        let mut stmt = conn
+            .prepare(&format!("SELECT {} FROM jobs WHERE status = 'running'", JOB_COLUMNS))
+            .map_err(|e| e.to_string())?;
+        let jobs = stmt
+            .query_map([], row_to_job)
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        jobs
+    };
+
+    for job in running {
+        let exit_code = queue.exit_code(&job.id);
+        let alive = match (&job.tmux_name, &job.session_id) {
+            (Some(name), _) => TerminalManager::tmux_pane_dead(name) == Some(false),
+            (None, Some(sid)) => match app.try_state::<TerminalManagerState>() {
+                Some(tm) => tm.lock().await.is_active(sid),
+                None => false,
+            },
+            (None, None) => false,
+        };
+        if exit_code.is_none() && alive {
+            continue;
+        }
+
+        let log = job_output(queue, &job, true);
+        release_session(app, &job).await;
+        let _ = std::fs::remove_file(queue.exit_path(&job.id));
+
+        let db = pool.write().await;
+        let conn = db.conn();
+        complete_job(conn, &job.id, exit_code, None, log)?;
+        tracing::info!("[jobs] Job {} finished (exit code: {:?})", job.id, exit_code);
+        emit_job(app, conn, &job.id);
+    }
+    Ok(())
+}
+
+/// Start queued jobs, oldest first, within the global and per-project limits
+async fn start_queued(app: &AppHandle, pool: &DbPool, queue: &JobQueue) -> Result<(), String> {
+    loop {
+        let next = {
+            let conn = pool.read().await;
+            let max_total = read_limit(&conn, "job_max_concurrent", 2);
+            let max_per_project = read_limit(&conn, "job_max_per_project", 1);
+            let running: i64 = conn
+                .query_row("SELECT COUNT(*) FROM jobs WHERE status = 'running'", [], |row| row.get(0))
+                .map_err(|e| e.to_string())?;
+            if running >= max_total {
+                return Ok(());
+            }
+            conn.query_row(
+                &format!(
+                    "SELECT {}, (SELECT path FROM projects p WHERE p.id = j.project_id)
+                     FROM jobs j
+                     WHERE status = 'queued'
+                       AND (SELECT COUNT(*) FROM jobs r
+                            WHERE r.project_id = j.project_id AND r.status = 'running') < ?1
+                     ORDER BY created_at ASC, rowid ASC LIMIT 1",
+                    JOB_COLUMNS
+                ),
+                params![max_per_project],
+                |row| Ok((row_to_job(row)?, row.get::<_, Option<String>>(15)?)),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?
+        };
+        let Some((job, project_path)) = next else {
+            return Ok(());
+        };
+        start_job(app, pool, queue, job, project_path).await?;
+    }
+}
+
+async fn start_job(
+    app: &AppHandle,
+    pool: &DbPool,
+    queue: &JobQueue,
+    job: Job,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    {
+        let db = pool.write().await;
+        let claimed = db
+            .conn()
+            .execute(
+                "UPDATE jobs SET status = 'running', attempts = attempts + 1, session_id = ?2,
+                     tmux_name = NULL, exit_code = NULL, error = NULL, started_at = datetime('now'),
+                     finished_at = NULL, updated_at = datetime('now')
+                 WHERE id = ?1 AND status = 'queued'",
+                params![job.id, session_id],
+            )
+            .map_err(|e| e.to_string())?;
+        if claimed == 0 {
+            // Cancelled or deleted since it was picked
+            return Ok(());
+        }
+    }
+
+    let exit_path = queue.exit_path(&job.id);
+    let _ = std::fs::remove_file(&exit_path);
+    let log = Arc::new(StdMutex::new(Vec::new()));
+    let sink_log = log.clone();
+    let output: OutputSink = Arc::new(move |data: &[u8]| {
+        if let Ok(mut log) = sink_log.lock() {
+            log.extend_from_slice(data);
+            if log.len() > MAX_LOG_BYTES {
+                let excess = log.len() - MAX_LOG_BYTES;
+                log.drain(..excess);
+            }
+        }
+    });
+    let options = SessionOptions {
+        shell: ShellProfile {
+            shell: Some(JOB_SHELL.to_string()),
+            ..Default::default()
+        },
+        output: Some(output),
+        remain_on_exit: true,
+        ..Default::default()
+    };
+
+    let started = match (project_path, app.try_state::<TerminalManagerState>()) {
+        (None, _) => Err("Project not found".to_string()),
+        (_, None) => Err("Terminal manager unavailable".to_string()),
+        (Some(path), Some(tm)) => {
+            if let Ok(mut logs) = queue.logs.lock() {
+                logs.insert(job.id.clone(), log);
+            }
+            let mut tm = tm.lock().await;
+            tm.create_session(
+                app,
+                session_id.clone(),
+                &path,
+                Some(&job_command_line(&job, &exit_path)),
+                120,
+                30,
+                &options,
+            )
+        }
+    };
+
+    let db = pool.write().await;
+    let conn = db.conn();
+    match started {
+        Ok(tmux_name) => {
+            if tmux_name.is_some() {
+                // Output comes from the pane's scrollback instead
+                queue.take_log(&job.id);
+            }
+            conn.execute(
+                "UPDATE jobs SET tmux_name = ?2 WHERE id = ?1",
+                params![job.id, tmux_name],
+            )
+            .map_err(|e| e.to_string())?;
+            tracing::info!("[jobs] Started job {} in session {}", job.id, session_id);
+        }
+        Err(e) => {
+            queue.take_log(&job.id);
+            tracing::warn!("[jobs] Failed to start job {}: {}", job.id, e);
+            complete_job(conn, &job.id, None, Some(format!("Failed to start: {}", e)), None)?;
+        }
+    }
+    emit_job(app, conn, &job.id);
+    Ok(())
+}
+
+/// Run the job queue in the background. Each pass finishes jobs whose command
+/// exited and starts queued ones. Jobs left running by a previous app run are
+/// picked up by the first pass: tmux jobs are followed until they exit, native
+/// ones (killed with the app) count as interrupted and retry if allowed.
+pub fn spawn_job_runner(app: AppHandle, pool: Arc<DbPool>, queue: JobQueueState) {
+    if let Err(e) = std::fs::create_dir_all(&queue.dir) {
+        tracing::warn!("[jobs] Failed to create job directory {:?}: {}", queue.dir, e);
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = reconcile_running(&app, &pool, &queue).await {
+                tracing::warn!("[jobs] Failed to check running jobs: {}", e);
+            }
+            if let Err(e) = start_queued(&app, &pool, &queue).await {
+                tracing::warn!("[jobs] Failed to start queued jobs: {}", e);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(JOB_POLL_INTERVAL_SECS)) => {}
+                _ = queue.wake.notified() => {}
+            }
+        }
+    });
+}
+
+/// Queue a Claude prompt or shell command for a project
+#[tauri::command]
+pub async fn enqueue_job(
+    app: AppHandle,
+    db: tauri::State<'_, DbState>,
+    queue: tauri::State<'_, JobQueueState>,
+    input: JobInput,
+) -> Result<Job, String> {
+    if !matches!(input.kind.as_str(), "claude" | "shell") {
+        return Err(format!("Invalid job kind '{}'. Use claude or shell.", input.kind));
+    }
+    if input.command.trim().is_empty() {
+        return Err("Job command cannot be empty".to_string());
+    }
+    let max_retries = input.max_retries.unwrap_or(0);
+    if !(0..=MAX_RETRIES).contains(&max_retries) {
+        return Err(format!("Retries must be between 0 and {}", MAX_RETRIES));
+    }
+
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let job_id = format!("{:032x}", rand::random::<u128>());
+    conn.execute(
+        "INSERT INTO jobs (id, project_id, kind, command, max_retries) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![job_id, input.project_id, input.kind, input.command.trim(), max_retries],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let job = fetch_job(conn, &job_id)?;
+    let _ = app.emit("job:updated", &job);
+    queue.wake.notify_one();
+    Ok(job)
+}
+
+/// List jobs, newest first, optionally for one project and/or status
+#[tauri::command]
+pub async fn list_jobs(
+    db: tauri::State<'_, DbState>,
+    project_id: Option<String>,
+    status: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<Job>, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM jobs
+             WHERE (?1 IS NULL OR project_id = ?1) AND (?2 IS NULL OR status = ?2)
+             ORDER BY created_at DESC, rowid DESC
+             LIMIT ?3",
+            JOB_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let jobs = stmt
+        .query_map(params![project_id, status, limit.unwrap_or(100)], row_to_job)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(jobs)
+}
+
+/// A job's output: live for running jobs, stored for finished ones
+#[tauri::command]
+pub async fn get_job_log(
+    db: tauri::State<'_, DbState>,
+    queue: tauri::State<'_, JobQueueState>,
+    id: String,
+) -> Result<String, String> {
+    let (job, log): (Job, Option<String>) = {
+        let conn = db.read().await;
+        let job = fetch_job(&conn, &id)?;
+        let log = conn
+            .query_row("SELECT log FROM jobs WHERE id = ?1", params![id], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        (job, log)
+    };
+    if job.status == "running" {
+        if let Some(live) = job_output(&queue, &job, false) {
+            return Ok(live);
+        }
+    }
+    Ok(log.unwrap_or_default())
+}
+
+/// Cancel a queued or running job. A running job's terminal is closed.
+#[tauri::command]
+pub async fn cancel_job(
+    app: AppHandle,
+    db: tauri::State<'_, DbState>,
+    queue: tauri::State<'_, JobQueueState>,
+    id: String,
+) -> Result<Job, String> {
+    let job = {
+        let conn = db.read().await;
+        fetch_job(&conn, &id)?
+    };
+    if !matches!(job.status.as_str(), "queued" | "running") {
+        return Err(format!("Job is already {}", job.status));
+    }
+
+    let log = if job.status == "running" {
+        job_output(&queue, &job, true)
+    } else {
+        None
+    };
+    {
+        let db = db.write().await;
+        db.conn()
+            .execute(
+                "UPDATE jobs SET status = 'cancelled', log = COALESCE(?2, log),
+                     finished_at = datetime('now'), updated_at = datetime('now')
+                 WHERE id = ?1 AND status IN ('queued', 'running')",
+                params![id, log],
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    if job.status == "running" {
+        release_session(&app, &job).await;
+        let _ = std::fs::remove_file(queue.exit_path(&id));
+        // Frees a concurrency slot
+        queue.wake.notify_one();
+    }
+
+    let db = db.read().await;
+    let job = fetch_job(&db, &id)?;
+    let _ = app.emit("job:updated", &job);
+    Ok(job)
+}
+
+/// Queue a failed or cancelled job again with a fresh retry budget
+#[tauri::command]
+pub async fn retry_job(
+    app: AppHandle,
+    db: tauri::State<'_, DbState>,
+    queue: tauri::State<'_, JobQueueState>,
+    id: String,
+) -> Result<Job, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let updated = conn
+        .execute(
+            "UPDATE jobs SET status = 'queued', attempts = 0, exit_code = NULL, error = NULL,
+                 session_id = NULL, tmux_name = NULL, started_at = NULL, finished_at = NULL,
+                 updated_at = datetime('now')
+             WHERE id = ?1 AND status IN ('failed', 'cancelled')",
+            params![id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err("Only failed or cancelled jobs can be retried".to_string());
+    }
+
+    let job = fetch_job(conn, &id)?;
+    let _ = app.emit("job:updated", &job);
+    queue.wake.notify_one();
+    Ok(job)
+}
+
+/// Delete a job that isn't running
+#[tauri::command]
+pub async fn delete_job(db: tauri::State<'_, DbState>, id: String) -> Result<bool, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let count = conn
+        .execute(
+            "DELETE FROM jobs WHERE id = ?1 AND status != 'running'",
+            params![id],
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    fn job(status: &str, attempts: i32, max_retries: i32) -> Database {
+        let db = Database::open_in_memory();
+        db.conn()
+            .execute("INSERT INTO projects (id, name, path) VALUES ('p', 'p', '/tmp/p')", [])
+            .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO jobs (id, project_id, kind, command, status, attempts, max_retries)
+                 VALUES ('j', 'p', 'shell', 'true', ?1, ?2, ?3)",
+                params![status, attempts, max_retries],
+            )
+            .unwrap();
+        db
+    }
+
+    fn state(conn: &Connection) -> (String, Option<i32>, Option<String>) {
+        conn.query_row("SELECT status, exit_code, error FROM jobs WHERE id = 'j'", [], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn failed_jobs_retry_until_budget_is_spent() {
+        let db = job("running", 1, 1);
+        complete_job(db.conn(), "j", Some(2), None, Some("boom".to_string())).unwrap();
+        assert_eq!(state(db.conn()), ("queued".to_string(), Some(2), Some("Exited with code 2".to_string())));
+
+        db.conn().execute("UPDATE jobs SET status = 'running', attempts = 2", []).unwrap();
+        complete_job(db.conn(), "j", None, None, None).unwrap();
+        assert_eq!(state(db.conn()), ("failed".to_string(), None, Some("Job was interrupted".to_string())));
+
+        // A cancelled job isn't overwritten by its exit
+        let db = job("cancelled", 1, 0);
+        complete_job(db.conn(), "j", Some(0), None, None).unwrap();
+        assert_eq!(state(db.conn()).0, "cancelled");
+    }
+
+    #[test]
+    fn wraps_commands_to_record_exit_code() {
+        let job = Job {
+            id: "j".to_string(),
+            project_id: "p".to_string(),
+            kind: "claude".to_string(),
+            command: "fix the user's bug".to_string(),
+            status: "queued".to_string(),
+            attempts: 0,
+            max_retries: 0,
+            exit_code: None,
+            error: None,
+            session_id: None,
+            tmux_name: None,
+            created_at: String::new(),
+            started_at: None,
+            finished_at: None,
+            updated_at: String::new(),
+        };
+        let line = job_command_line(&job, Path::new("/tmp/jobs/j.exit"));
+        assert_eq!(
+            line,
+            "(\nclaude -p --dangerously-skip-permissions 'fix the user'\\''s bug'\n)\n\
+             __tys_status=$?; printf '%s' \"$__tys_status\" > '/tmp/jobs/j.exit'; exit \"$__tys_status\""
+        );
+    }
+}
@@ -3,16 +3,265 @@
 //
 // Implements knowledge/memory system for persistent context (PRD FR-9)
 
-use crate::models::{Knowledge, KnowledgeBookmark, KnowledgeInput, KnowledgeSearchResult};
-use rusqlite::params;
+use crate::models::{
+    Knowledge, KnowledgeBookmark, KnowledgeCategory, KnowledgeDiffLine, KnowledgeImportResult,
+    KnowledgeInput, KnowledgeRevision, KnowledgeRevisionDiff, KnowledgeSearchResult, KnowledgeTag,
+    KnowledgeVaultConflict, KnowledgeVaultExportResult, KnowledgeVaultImportResult,
+};
+use rusqlite::{params, Connection};
 use std::sync::Arc;
 
 type DbState = Arc<crate::db::DbPool>;
 
+/// FR-9.2: built-in knowledge categories. Projects can add their own on top of these
+/// (see `knowledge_custom_categories`).
+const BUILTIN_CATEGORIES: [&str; 4] = ["learning", "decision", "reference", "fact"];
+
+/// Column list read by `row_to_knowledge`. Queries must alias the knowledge table as `k`.
+/// Tags are folded into one comma-separated column (tag names never contain commas).
+const KNOWLEDGE_COLUMNS: &str =
+    "k.id, k.project_id, k.title, k.content, k.category, k.source, k.metadata, k.deleted_at, k.created_at, k.updated_at,
+     (SELECT GROUP_CONCAT(t.name, ',') FROM knowledge_tag_links l
+      JOIN knowledge_tags t ON t.id = l.tag_id WHERE l.knowledge_id = k.id)";
+
+/// Deserialize a Knowledge entry from a SQLite row selected with `KNOWLEDGE_COLUMNS`
+fn row_to_knowledge(row: &rusqlite::Row) -> rusqlite::Result<Knowledge> {
+    let metadata_str: Option<String> = row.get(6)?;
+    let metadata: Option<serde_json::Value> =
+        metadata_str.and_then(|s| serde_json::from_str(&s).ok());
+    let tags_str: Option<String> = row.get(10)?;
+    let mut tags: Vec<String> = tags_str
+        .map(|s| s.split(',').map(|t| t.to_string()).collect())
+        .unwrap_or_default();
+    tags.sort();
+    Ok(Knowledge {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        title: row.get(2)?,
+        content: row.get(3)?,
+        category: row.get(4)?,
+        source: row.get(5)?,
+        metadata,
+        deleted_at: row.get(7)?,
+        tags,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+fn fetch_knowledge(conn: &Connection, knowledge_id: &str) -> Result<Knowledge, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM knowledge k WHERE k.id = ?1", KNOWLEDGE_COLUMNS),
+        params![knowledge_id],
+        row_to_knowledge,
+    )
+    .map_err(|e| format!("Knowledge entry not found: {}", e))
+}
+
+/// A category is valid if it is built in or defined for the project
+fn validate_category(conn: &Connection, project_id: &str, category: &str) -> Result<(), String> {
+    if BUILTIN_CATEGORIES.contains(&category) {
+        return Ok(());
+    }
+    let custom_exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM knowledge_custom_categories WHERE project_id = ?1 AND name = ?2",
+            params![project_id, category],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if custom_exists {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid category: {}. Use a built-in category ({}) or create it for this project first",
+            category,
+            BUILTIN_CATEGORIES.join(", ")
+        ))
+    }
+}
+
+/// Normalize a tag name: trimmed, lowercase, whitespace and commas collapsed to `-`
+fn normalize_tag(tag: &str) -> Option<String> {
+    let normalized = tag
+        .trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if normalized.is_empty() {
+        None
+    } else {
+        Some(normalized)
+    }
+}
+
+/// Replace the tag set of a knowledge entry, creating project tags as needed
+fn set_entry_tags(
+    conn: &Connection,
+    project_id: &str,
+    knowledge_id: &str,
+    tags: &[String],
+) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM knowledge_tag_links WHERE knowledge_id = ?1",
+        params![knowledge_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    for tag in tags.iter().filter_map(|t| normalize_tag(t)) {
+        let tag_id = format!("{:032x}", rand::random::<u128>());
+        conn.execute(
+            "INSERT OR IGNORE INTO knowledge_tags (id, project_id, name) VALUES (?1, ?2, ?3)",
+            params![tag_id, project_id, tag],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT OR IGNORE INTO knowledge_tag_links (knowledge_id, tag_id)
+             SELECT ?1, id FROM knowledge_tags WHERE project_id = ?2 AND name = ?3",
+            params![knowledge_id, project_id, tag],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Append a "has all of these tags" filter to a dynamic WHERE clause on `knowledge k`
+fn push_tag_filter(
+    tags: &Option<Vec<String>>,
+    conditions: &mut Vec<String>,
+    params_vec: &mut Vec<Box<dyn rusqlite::ToSql>>,
+) {
+    let tags: Vec<String> = tags
+        .iter()
+        .flatten()
+        .filter_map(|t| normalize_tag(t))
+        .collect();
+    if tags.is_empty() {
+        return;
+    }
+    let placeholders = vec!["?"; tags.len()].join(", ");
+    conditions.push(format!(
+        "k.id IN (SELECT l.knowledge_id FROM knowledge_tag_links l
+                  JOIN knowledge_tags t ON t.id = l.tag_id
+                  WHERE t.name IN ({}) GROUP BY l.knowledge_id HAVING COUNT(DISTINCT t.name) = ?)",
+        placeholders
+    ));
+    let count = tags.len() as i64;
+    for tag in tags {
+        params_vec.push(Box::new(tag));
+    }
+    params_vec.push(Box::new(count));
+}
+
+/// Snapshot the current state of a knowledge entry into `knowledge_revisions`.
+/// Called before any change that would otherwise overwrite or hide the content.
+fn record_revision(conn: &Connection, knowledge_id: &str, change_type: &str) -> Result<i32, String> {
+    let next_revision: i32 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(revision_number), 0) + 1 FROM knowledge_revisions WHERE knowledge_id = ?1",
+            params![knowledge_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let revision_id = format!("{:032x}", rand::random::<u128>());
+    let inserted = conn
+        .execute(
+            "INSERT INTO knowledge_revisions
+                (id, knowledge_id, project_id, revision_number, title, content, category, source, metadata, change_type)
+             SELECT ?1, id, project_id, ?2, title, content, category, source, metadata, ?3
+             FROM knowledge WHERE id = ?4",
+            params![revision_id, next_revision, change_type, knowledge_id],
+        )
+        .map_err(|e| e.to_string())?;
+
+    if inserted == 0 {
+        return Err(format!("Knowledge entry not found: {}", knowledge_id));
+    }
+    Ok(next_revision)
+}
+
+fn row_to_revision(row: &rusqlite::Row) -> rusqlite::Result<KnowledgeRevision> {
+    let metadata_str: Option<String> = row.get(8)?;
+    Ok(KnowledgeRevision {
+        id: row.get(0)?,
+        knowledge_id: row.get(1)?,
+        project_id: row.get(2)?,
+        revision_number: row.get(3)?,
+        title: row.get(4)?,
+        content: row.get(5)?,
+        category: row.get(6)?,
+        source: row.get(7)?,
+        metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
+        change_type: row.get(9)?,
+        created_at: row.get(10)?,
+    })
+}
+
+fn fetch_revision(
+    conn: &Connection,
+    knowledge_id: &str,
+    revision_number: i32,
+) -> Result<KnowledgeRevision, String> {
+    conn.query_row(
+        "SELECT id, knowledge_id, project_id, revision_number, title, content, category, source, metadata, change_type, created_at
+         FROM knowledge_revisions WHERE knowledge_id = ?1 AND revision_number = ?2",
+        params![knowledge_id, revision_number],
+        row_to_revision,
+    )
+    .map_err(|e| format!("Revision {} not found: {}", revision_number, e))
+}
+
+/// Line-level diff using a longest-common-subsequence table.
+/// Knowledge entries are small markdown documents, so O(n*m) is fine here.
+fn diff_lines(old: &str, new: &str) -> Vec<KnowledgeDiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let (n, m) = (a.len(), b.len());
+
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let line = |op: &str, text: &str| KnowledgeDiffLine {
+        op: op.to_string(),
+        text: text.to_string(),
+    };
+
+    let mut out = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            out.push(line("equal", a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(line("delete", a[i]));
+            i += 1;
+        } else {
+            out.push(line("insert", b[j]));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|l| line("delete", l)));
+    out.extend(b[j..].iter().map(|l| line("insert", l)));
+    out
+}
+
 /// Store a knowledge entry
 ///
 /// FR-9.1: System SHALL store knowledge entries with content
-/// FR-9.2: System SHALL categorize knowledge (learning, decision, reference, fact)
+/// FR-9.2: System SHALL categorize knowledge (learning, decision, reference, fact,
+/// plus any categories defined for the project)
 #[tauri::command]
 pub async fn knowledge_store(
     db: tauri::State<'_, DbState>,
@@ -24,13 +273,7 @@ pub async fn knowledge_store(
 
     // Validate category
     let category = input.category.unwrap_or_else(|| "learning".to_string());
-    let valid_categories = ["learning", "decision", "reference", "fact"];
-    if !valid_categories.contains(&category.as_str()) {
-        return Err(format!(
-            "Invalid category: {}. Valid: {:?}",
-            category, valid_categories
-        ));
-    }
+    validate_category(conn, &project_id, &category)?;
 
     // Serialize metadata
     let metadata_str = input
@@ -55,43 +298,25 @@ pub async fn knowledge_store(
     )
     .map_err(|e| e.to_string())?;
 
-    // Return created knowledge entry
-    let knowledge = conn
-        .query_row(
-            "SELECT id, project_id, title, content, category, source, metadata, created_at, updated_at
-             FROM knowledge WHERE id = ?1",
-            params![knowledge_id],
-            |row| {
-                let metadata_str: Option<String> = row.get(6)?;
-                let metadata: Option<serde_json::Value> =
-                    metadata_str.and_then(|s| serde_json::from_str(&s).ok());
-                Ok(Knowledge {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    title: row.get(2)?,
-                    content: row.get(3)?,
-                    category: row.get(4)?,
-                    source: row.get(5)?,
-                    metadata,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
+    if let Some(tags) = &input.tags {
+        set_entry_tags(conn, &project_id, &knowledge_id, tags)?;
+    }
 
-    Ok(knowledge)
+    // Return created knowledge entry
+    fetch_knowledge(conn, &knowledge_id)
 }
 
 /// Search knowledge by text query
 ///
 /// FR-9.3: System SHALL support knowledge search by project
+/// When `tags` is given, only entries carrying all of those tags are returned.
 #[tauri::command]
 pub async fn knowledge_search(
     db: tauri::State<'_, DbState>,
     project_id: String,
     query: String,
     category: Option<String>,
+    tags: Option<Vec<String>>,
     limit: Option<i32>,
 ) -> Result<Vec<KnowledgeSearchResult>, String> {
     let db = db.write().await;
@@ -99,51 +324,48 @@ pub async fn knowledge_search(
     let limit = limit.unwrap_or(20);
     let search_pattern = format!("%{}%", query);
 
-    let (sql, params_vec): (String, Vec<Box<dyn rusqlite::ToSql>>) = if let Some(ref cat) = category
-    {
-        (
-            "SELECT id, project_id, title, content, category, created_at
-             FROM knowledge
-             WHERE project_id = ?1 AND category = ?2 AND (title LIKE ?3 OR content LIKE ?3)
-             ORDER BY created_at DESC
-             LIMIT ?4"
-                .to_string(),
-            vec![
-                Box::new(project_id) as Box<dyn rusqlite::ToSql>,
-                Box::new(cat.clone()),
-                Box::new(search_pattern),
-                Box::new(limit),
-            ],
-        )
-    } else {
-        (
-            "SELECT id, project_id, title, content, category, created_at
-             FROM knowledge
-             WHERE project_id = ?1 AND (title LIKE ?2 OR content LIKE ?2)
-             ORDER BY created_at DESC
-             LIMIT ?3"
-                .to_string(),
-            vec![
-                Box::new(project_id) as Box<dyn rusqlite::ToSql>,
-                Box::new(search_pattern),
-                Box::new(limit),
-            ],
-        )
-    };
+    let mut conditions = vec![
+        "k.project_id = ?".to_string(),
+        "k.deleted_at IS NULL".to_string(),
+        "(k.title LIKE ? OR k.content LIKE ?)".to_string(),
+    ];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![
+        Box::new(project_id),
+        Box::new(search_pattern.clone()),
+        Box::new(search_pattern),
+    ];
+    if let Some(cat) = category {
+        conditions.push("k.category = ?".to_string());
+        params_vec.push(Box::new(cat));
+    }
+    push_tag_filter(&tags, &mut conditions, &mut params_vec);
+    params_vec.push(Box::new(limit));
+
+    let sql = format!(
+        "SELECT {}
+         FROM knowledge k
+         WHERE {}
+         ORDER BY k.created_at DESC
+         LIMIT ?",
+        KNOWLEDGE_COLUMNS,
+        conditions.join(" AND ")
+    );
 
     let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
     let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
 
     let results = stmt
         .query_map(params_refs.as_slice(), |row| {
+            let entry = row_to_knowledge(row)?;
             Ok(KnowledgeSearchResult {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                title: row.get(2)?,
-                content: row.get(3)?,
-                category: row.get(4)?,
+                id: entry.id,
+                project_id: entry.project_id,
+                title: entry.title,
+                content: entry.content,
+                category: entry.category,
+                tags: entry.tags,
                 relevance_score: None, // Would require embeddings for true relevance
-                created_at: row.get(5)?,
+                created_at: entry.created_at,
             })
         })
         .map_err(|e| e.to_string())?
@@ -153,12 +375,13 @@ pub async fn knowledge_search(
     Ok(results)
 }
 
-/// Get all knowledge for a project
+/// Get all knowledge for a project, optionally filtered by category and tags
 #[tauri::command]
 pub async fn knowledge_list(
     db: tauri::State<'_, DbState>,
     project_id: String,
     category: Option<String>,
+    tags: Option<Vec<String>>,
     limit: Option<i32>,
     offset: Option<i32>,
 ) -> Result<Vec<Knowledge>, String> {
@@ -167,56 +390,34 @@ pub async fn knowledge_list(
     let limit = limit.unwrap_or(50);
     let offset = offset.unwrap_or(0);
 
-    let (sql, params_vec): (String, Vec<Box<dyn rusqlite::ToSql>>) = if let Some(ref cat) = category
-    {
-        (
-            "SELECT id, project_id, title, content, category, source, metadata, created_at, updated_at
-             FROM knowledge
-             WHERE project_id = ?1 AND category = ?2
-             ORDER BY created_at DESC
-             LIMIT ?3 OFFSET ?4".to_string(),
-            vec![
-                Box::new(project_id) as Box<dyn rusqlite::ToSql>,
-                Box::new(cat.clone()),
-                Box::new(limit),
-                Box::new(offset),
-            ],
-        )
-    } else {
-        (
-            "SELECT id, project_id, title, content, category, source, metadata, created_at, updated_at
-             FROM knowledge
-             WHERE project_id = ?1
-             ORDER BY created_at DESC
-             LIMIT ?2 OFFSET ?3".to_string(),
-            vec![
-                Box::new(project_id) as Box<dyn rusqlite::ToSql>,
-                Box::new(limit),
-                Box::new(offset),
-            ],
-        )
-    };
+    let mut conditions = vec![
+        "k.project_id = ?".to_string(),
+        "k.deleted_at IS NULL".to_string(),
+    ];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(project_id)];
+    if let Some(cat) = category {
+        conditions.push("k.category = ?".to_string());
+        params_vec.push(Box::new(cat));
+    }
+    push_tag_filter(&tags, &mut conditions, &mut params_vec);
+    params_vec.push(Box::new(limit));
+    params_vec.push(Box::new(offset));
+
+    let sql = format!(
+        "SELECT {}
+         FROM knowledge k
+         WHERE {}
+         ORDER BY k.created_at DESC
+         LIMIT ? OFFSET ?",
+        KNOWLEDGE_COLUMNS,
+        conditions.join(" AND ")
+    );
 
     let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
     let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
 
     let knowledge_entries = stmt
-        .query_map(params_refs.as_slice(), |row| {
-            let metadata_str: Option<String> = row.get(6)?;
-            let metadata: Option<serde_json::Value> =
-                metadata_str.and_then(|s| serde_json::from_str(&s).ok());
-            Ok(Knowledge {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                title: row.get(2)?,
-                content: row.get(3)?,
-                category: row.get(4)?,
-                source: row.get(5)?,
-                metadata,
-                created_at: row.get(7)?,
-                updated_at: row.get(8)?,
-            })
-        })
+        .query_map(params_refs.as_slice(), row_to_knowledge)
         .map_err(|e| e.to_string())?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
@@ -233,35 +434,12 @@ pub async fn knowledge_get(
     let db = db.write().await;
     let conn = db.conn();
 
-    let knowledge = conn
-        .query_row(
-            "SELECT id, project_id, title, content, category, source, metadata, created_at, updated_at
-             FROM knowledge WHERE id = ?1",
-            params![knowledge_id],
-            |row| {
-                let metadata_str: Option<String> = row.get(6)?;
-                let metadata: Option<serde_json::Value> =
-                    metadata_str.and_then(|s| serde_json::from_str(&s).ok());
-                Ok(Knowledge {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    title: row.get(2)?,
-                    content: row.get(3)?,
-                    category: row.get(4)?,
-                    source: row.get(5)?,
-                    metadata,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            },
-        )
-        .map_err(|e| format!("Knowledge entry not found: {}", e))?;
-
-    Ok(knowledge)
+    fetch_knowledge(conn, &knowledge_id)
 }
 
-/// Update a knowledge entry
+/// Update a knowledge entry, snapshotting the previous version into its revision history
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn knowledge_update(
     db: tauri::State<'_, DbState>,
     knowledge_id: String,
@@ -270,10 +448,16 @@ pub async fn knowledge_update(
     category: Option<String>,
     source: Option<String>,
     metadata: Option<serde_json::Value>,
+    tags: Option<Vec<String>>,
 ) -> Result<Knowledge, String> {
     let db = db.write().await;
     let conn = db.conn();
 
+    let existing = fetch_knowledge(conn, &knowledge_id)?;
+    if existing.deleted_at.is_some() {
+        return Err("Cannot update a knowledge entry that is in the trash".to_string());
+    }
+
     // Build dynamic update
     let mut updates = Vec::new();
     let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
@@ -287,11 +471,7 @@ pub async fn knowledge_update(
         params_vec.push(Box::new(c.clone()));
     }
     if let Some(cat) = &category {
-        // Validate category
-        let valid_categories = ["learning", "decision", "reference", "fact"];
-        if !valid_categories.contains(&cat.as_str()) {
-            return Err(format!("Invalid category: {}", cat));
-        }
+        validate_category(conn, &existing.project_id, cat)?;
         updates.push("category = ?");
         params_vec.push(Box::new(cat.clone()));
     }
@@ -305,7 +485,7 @@ pub async fn knowledge_update(
         params_vec.push(Box::new(metadata_str));
     }
 
-    if updates.is_empty() {
+    if updates.is_empty() && tags.is_none() {
         return Err("No fields to update".to_string());
     }
 
@@ -315,51 +495,265 @@ pub async fn knowledge_update(
     let query = format!("UPDATE knowledge SET {} WHERE id = ?", updates.join(", "));
 
     let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
-    conn.execute(&query, params_refs.as_slice())
-        .map_err(|e| e.to_string())?;
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let result = record_revision(conn, &knowledge_id, "update")
+        .and_then(|_| {
+            conn.execute(&query, params_refs.as_slice())
+                .map_err(|e| e.to_string())
+        })
+        .and_then(|_| match &tags {
+            Some(tags) => set_entry_tags(conn, &existing.project_id, &knowledge_id, tags),
+            None => Ok(()),
+        });
+    match result {
+        Ok(_) => conn.execute_batch("COMMIT").map_err(|e| e.to_string())?,
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+    }
 
     // Return updated entry
-    let knowledge = conn
+    fetch_knowledge(conn, &knowledge_id)
+}
+
+/// Move a knowledge entry to the trash (soft delete).
+/// The entry keeps its revision history and can be restored until the trash is emptied.
+#[tauri::command]
+pub async fn knowledge_delete(
+    db: tauri::State<'_, DbState>,
+    knowledge_id: String,
+) -> Result<bool, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let is_live: bool = conn
         .query_row(
-            "SELECT id, project_id, title, content, category, source, metadata, created_at, updated_at
-             FROM knowledge WHERE id = ?1",
+            "SELECT COUNT(*) > 0 FROM knowledge WHERE id = ?1 AND deleted_at IS NULL",
             params![knowledge_id],
-            |row| {
-                let metadata_str: Option<String> = row.get(6)?;
-                let metadata: Option<serde_json::Value> =
-                    metadata_str.and_then(|s| serde_json::from_str(&s).ok());
-                Ok(Knowledge {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    title: row.get(2)?,
-                    content: row.get(3)?,
-                    category: row.get(4)?,
-                    source: row.get(5)?,
-                    metadata,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            },
+            |row| row.get(0),
         )
         .map_err(|e| e.to_string())?;
+    if !is_live {
+        return Ok(false);
+    }
 
-    Ok(knowledge)
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let result = record_revision(conn, &knowledge_id, "delete").and_then(|_| {
+        conn.execute(
+            "UPDATE knowledge SET deleted_at = datetime('now') WHERE id = ?1",
+            params![knowledge_id],
+        )
+        .map_err(|e| e.to_string())
+    });
+    match result {
+        Ok(deleted) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            Ok(deleted > 0)
+        }
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            Err(e)
+        }
+    }
 }
 
-/// Delete a knowledge entry
+/// List revisions of a knowledge entry, newest first
 #[tauri::command]
-pub async fn knowledge_delete(
+pub async fn knowledge_list_revisions(
     db: tauri::State<'_, DbState>,
     knowledge_id: String,
-) -> Result<bool, String> {
+) -> Result<Vec<KnowledgeRevision>, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, knowledge_id, project_id, revision_number, title, content, category, source, metadata, change_type, created_at
+             FROM knowledge_revisions
+             WHERE knowledge_id = ?1
+             ORDER BY revision_number DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let revisions = stmt
+        .query_map(params![knowledge_id], row_to_revision)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(revisions)
+}
+
+/// Diff two revisions of a knowledge entry.
+/// When `to_revision` is omitted the diff is taken against the current entry.
+#[tauri::command]
+pub async fn knowledge_diff_revisions(
+    db: tauri::State<'_, DbState>,
+    knowledge_id: String,
+    from_revision: i32,
+    to_revision: Option<i32>,
+) -> Result<KnowledgeRevisionDiff, String> {
+    let conn = db.read().await;
+
+    let from = fetch_revision(&conn, &knowledge_id, from_revision)?;
+    let (to_title, to_content) = match to_revision {
+        Some(rev) => {
+            let to = fetch_revision(&conn, &knowledge_id, rev)?;
+            (to.title, to.content)
+        }
+        None => {
+            let current = fetch_knowledge(&conn, &knowledge_id)?;
+            (current.title, current.content)
+        }
+    };
+
+    let lines = diff_lines(&from.content, &to_content);
+    let additions = lines.iter().filter(|l| l.op == "insert").count() as i32;
+    let deletions = lines.iter().filter(|l| l.op == "delete").count() as i32;
+
+    Ok(KnowledgeRevisionDiff {
+        knowledge_id,
+        from_revision,
+        to_revision,
+        from_title: from.title,
+        to_title,
+        lines,
+        additions,
+        deletions,
+    })
+}
+
+/// Restore a knowledge entry to the content of a previous revision.
+/// The current state is snapshotted first, so a restore can itself be undone.
+/// Restoring an entry that is in the trash also brings it back.
+#[tauri::command]
+pub async fn knowledge_restore_revision(
+    db: tauri::State<'_, DbState>,
+    knowledge_id: String,
+    revision_number: i32,
+) -> Result<Knowledge, String> {
     let db = db.write().await;
     let conn = db.conn();
 
-    let deleted = conn
-        .execute("DELETE FROM knowledge WHERE id = ?1", params![knowledge_id])
+    let revision = fetch_revision(conn, &knowledge_id, revision_number)?;
+    let metadata_str = revision
+        .metadata
+        .as_ref()
+        .map(|m| serde_json::to_string(m).unwrap_or_default());
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let result = record_revision(conn, &knowledge_id, "restore").and_then(|_| {
+        conn.execute(
+            "UPDATE knowledge
+             SET title = ?1, content = ?2, category = COALESCE(?3, category), source = ?4, metadata = ?5,
+                 deleted_at = NULL, updated_at = datetime('now')
+             WHERE id = ?6",
+            params![
+                revision.title,
+                revision.content,
+                revision.category,
+                revision.source,
+                metadata_str,
+                knowledge_id
+            ],
+        )
+        .map_err(|e| e.to_string())
+    });
+    match result {
+        Ok(_) => conn.execute_batch("COMMIT").map_err(|e| e.to_string())?,
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+    }
+
+    fetch_knowledge(conn, &knowledge_id)
+}
+
+/// List knowledge entries in the trash for a project, most recently deleted first
+#[tauri::command]
+pub async fn knowledge_list_trash(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+) -> Result<Vec<Knowledge>, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}
+             FROM knowledge k
+             WHERE k.project_id = ?1 AND k.deleted_at IS NOT NULL
+             ORDER BY k.deleted_at DESC",
+            KNOWLEDGE_COLUMNS
+        ))
         .map_err(|e| e.to_string())?;
 
-    Ok(deleted > 0)
+    let entries = stmt
+        .query_map(params![project_id], row_to_knowledge)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(entries)
+}
+
+/// Move a knowledge entry out of the trash
+#[tauri::command]
+pub async fn knowledge_restore_from_trash(
+    db: tauri::State<'_, DbState>,
+    knowledge_id: String,
+) -> Result<Knowledge, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let is_trashed: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM knowledge WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![knowledge_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !is_trashed {
+        return Err(format!("Knowledge entry {} is not in the trash", knowledge_id));
+    }
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let result = record_revision(conn, &knowledge_id, "restore").and_then(|_| {
+        conn.execute(
+            "UPDATE knowledge SET deleted_at = NULL, updated_at = datetime('now') WHERE id = ?1",
+            params![knowledge_id],
+        )
+        .map_err(|e| e.to_string())
+    });
+    match result {
+        Ok(_) => conn.execute_batch("COMMIT").map_err(|e| e.to_string())?,
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+    }
+
+    fetch_knowledge(conn, &knowledge_id)
+}
+
+/// Permanently delete all trashed knowledge entries for a project (and their revisions)
+#[tauri::command]
+pub async fn knowledge_empty_trash(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+) -> Result<i32, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let count = conn
+        .execute(
+            "DELETE FROM knowledge WHERE project_id = ?1 AND deleted_at IS NOT NULL",
+            params![project_id],
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(count as i32)
 }
 
 /// Get knowledge categories for a project
@@ -375,7 +769,7 @@ pub async fn knowledge_categories(
         .prepare(
             "SELECT category, COUNT(*) as count
              FROM knowledge
-             WHERE project_id = ?1
+             WHERE project_id = ?1 AND deleted_at IS NULL
              GROUP BY category
              ORDER BY count DESC",
         )
@@ -390,19 +784,274 @@ pub async fn knowledge_categories(
     Ok(categories)
 }
 
-/// Bulk import knowledge entries
+/// List every category available to a project: the built-ins followed by
+/// project-defined categories, each with its live entry count
+#[tauri::command]
+pub async fn knowledge_list_categories(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+) -> Result<Vec<KnowledgeCategory>, String> {
+    let conn = db.read().await;
+
+    let mut counts: std::collections::HashMap<String, i32> = conn
+        .prepare(
+            "SELECT category, COUNT(*) FROM knowledge
+             WHERE project_id = ?1 AND deleted_at IS NULL
+             GROUP BY category",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(params![project_id], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut categories: Vec<KnowledgeCategory> = BUILTIN_CATEGORIES
+        .iter()
+        .map(|name| KnowledgeCategory {
+            name: name.to_string(),
+            description: None,
+            builtin: true,
+            entry_count: counts.remove(*name).unwrap_or(0),
+        })
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, description FROM knowledge_custom_categories
+             WHERE project_id = ?1 ORDER BY name",
+        )
+        .map_err(|e| e.to_string())?;
+    let custom = stmt
+        .query_map(params![project_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    for (name, description) in custom {
+        let entry_count = counts.remove(&name).unwrap_or(0);
+        categories.push(KnowledgeCategory {
+            name,
+            description,
+            builtin: false,
+            entry_count,
+        });
+    }
+
+    Ok(categories)
+}
+
+/// Define a custom knowledge category for a project (e.g. "gotcha", "runbook")
+#[tauri::command]
+pub async fn knowledge_create_category(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    name: String,
+    description: Option<String>,
+) -> Result<KnowledgeCategory, String> {
+    let name = normalize_tag(&name).ok_or("Category name cannot be empty")?;
+    if BUILTIN_CATEGORIES.contains(&name.as_str()) {
+        return Err(format!("'{}' is a built-in category", name));
+    }
+
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let category_id = format!("{:032x}", rand::random::<u128>());
+    conn.execute(
+        "INSERT INTO knowledge_custom_categories (id, project_id, name, description)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(project_id, name) DO UPDATE SET description = ?4",
+        params![category_id, project_id, name, description],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let entry_count: i32 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM knowledge WHERE project_id = ?1 AND category = ?2 AND deleted_at IS NULL",
+            params![project_id, name],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(KnowledgeCategory {
+        name,
+        description,
+        builtin: false,
+        entry_count,
+    })
+}
+
+/// Remove a custom category. Entries using it (including trashed ones) are moved
+/// to `reassign_to`, which defaults to "learning". Returns the number of entries moved.
+#[tauri::command]
+pub async fn knowledge_delete_category(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    name: String,
+    reassign_to: Option<String>,
+) -> Result<i32, String> {
+    if BUILTIN_CATEGORIES.contains(&name.as_str()) {
+        return Err(format!("Cannot delete built-in category '{}'", name));
+    }
+    let reassign_to = reassign_to.unwrap_or_else(|| "learning".to_string());
+    if reassign_to == name {
+        return Err("Cannot reassign entries to the category being deleted".to_string());
+    }
+
+    let db = db.write().await;
+    let conn = db.conn();
+    validate_category(conn, &project_id, &reassign_to)?;
+
+    let affected: Vec<String> = conn
+        .prepare("SELECT id FROM knowledge WHERE project_id = ?1 AND category = ?2")
+        .and_then(|mut stmt| {
+            stmt.query_map(params![project_id, name], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| e.to_string())?;
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let result = affected
+        .iter()
+        .try_for_each(|id| record_revision(conn, id, "update").map(|_| ()))
+        .and_then(|_| {
+            let moved = conn
+                .execute(
+                    "UPDATE knowledge SET category = ?1, updated_at = datetime('now')
+                     WHERE project_id = ?2 AND category = ?3",
+                    params![reassign_to, project_id, name],
+                )
+                .map_err(|e| e.to_string())?;
+            conn.execute(
+                "DELETE FROM knowledge_custom_categories WHERE project_id = ?1 AND name = ?2",
+                params![project_id, name],
+            )
+            .map_err(|e| e.to_string())?;
+            Ok(moved)
+        });
+    match result {
+        Ok(moved) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            Ok(moved as i32)
+        }
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            Err(e)
+        }
+    }
+}
+
+/// List tags used in a project with their live entry counts
+#[tauri::command]
+pub async fn knowledge_list_tags(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+) -> Result<Vec<KnowledgeTag>, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.id, t.project_id, t.name, COUNT(k.id)
+             FROM knowledge_tags t
+             LEFT JOIN knowledge_tag_links l ON l.tag_id = t.id
+             LEFT JOIN knowledge k ON k.id = l.knowledge_id AND k.deleted_at IS NULL
+             WHERE t.project_id = ?1
+             GROUP BY t.id
+             ORDER BY t.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let tags = stmt
+        .query_map(params![project_id], |row| {
+            Ok(KnowledgeTag {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                name: row.get(2)?,
+                entry_count: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(tags)
+}
+
+/// Replace the tags on a knowledge entry
+#[tauri::command]
+pub async fn knowledge_set_tags(
+    db: tauri::State<'_, DbState>,
+    knowledge_id: String,
+    tags: Vec<String>,
+) -> Result<Knowledge, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let existing = fetch_knowledge(conn, &knowledge_id)?;
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let result = record_revision(conn, &knowledge_id, "update")
+        .and_then(|_| set_entry_tags(conn, &existing.project_id, &knowledge_id, &tags))
+        .and_then(|_| {
+            conn.execute(
+                "UPDATE knowledge SET updated_at = datetime('now') WHERE id = ?1",
+                params![knowledge_id],
+            )
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+        });
+    match result {
+        Ok(_) => conn.execute_batch("COMMIT").map_err(|e| e.to_string())?,
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+    }
+
+    fetch_knowledge(conn, &knowledge_id)
+}
+
+/// Delete a tag from a project, removing it from every entry
+#[tauri::command]
+pub async fn knowledge_delete_tag(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    name: String,
+) -> Result<bool, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let deleted = conn
+        .execute(
+            "DELETE FROM knowledge_tags WHERE project_id = ?1 AND name = ?2",
+            params![project_id, normalize_tag(&name).unwrap_or(name)],
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(deleted > 0)
+}
+
+/// Bulk import knowledge entries. Entries with a category that is neither
+/// built in nor defined for the project are skipped.
 #[tauri::command]
 pub async fn knowledge_import(
     db: tauri::State<'_, DbState>,
     project_id: String,
     entries: Vec<KnowledgeInput>,
-) -> Result<i32, String> {
+) -> Result<KnowledgeImportResult, String> {
     let db = db.write().await;
     let conn = db.conn();
 
     let mut imported = 0;
+    let mut skipped = 0;
     for input in entries {
         let category = input.category.unwrap_or_else(|| "learning".to_string());
+        if validate_category(conn, &project_id, &category).is_err() {
+            skipped += 1;
+            continue;
+        }
         let metadata_str = input
             .metadata
             .as_ref()
@@ -425,11 +1074,432 @@ pub async fn knowledge_import(
         );
 
         if result.is_ok() {
+            if let Some(tags) = &input.tags {
+                let _ = set_entry_tags(conn, &project_id, &knowledge_id, tags);
+            }
             imported += 1;
+        } else {
+            skipped += 1;
         }
     }
 
-    Ok(imported)
+    Ok(KnowledgeImportResult { imported, skipped })
+}
+
+// ============================================================
+// Markdown Vault Export / Import
+// ============================================================
+//
+// Layout: <dir>/<project-slug>/<category>/<title-slug>-<id8>.md
+// Each file carries the entry's identity and timestamps in frontmatter so a
+// vault checked into git can be reviewed and re-imported without duplicates.
+
+/// Lowercase, hyphen-separated slug safe for file and folder names
+fn slugify(value: &str) -> String {
+    let slug = value
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else {
+        slug.chars().take(60).collect()
+    }
+}
+
+/// Render a knowledge entry as markdown with frontmatter.
+/// String values are written as JSON strings (valid YAML double-quoted scalars)
+/// and metadata as inline JSON (valid YAML flow mapping).
+fn render_vault_file(entry: &Knowledge) -> String {
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string());
+    let mut lines = vec![
+        "---".to_string(),
+        format!("id: {}", entry.id),
+        format!("project_id: {}", entry.project_id),
+        format!("title: {}", quote(&entry.title)),
+        format!("category: {}", entry.category),
+    ];
+    match &entry.source {
+        Some(source) => lines.push(format!("source: {}", quote(source))),
+        None => lines.push("source: null".to_string()),
+    }
+    match &entry.metadata {
+        Some(metadata) => lines.push(format!(
+            "metadata: {}",
+            serde_json::to_string(metadata).unwrap_or_else(|_| "null".to_string())
+        )),
+        None => lines.push("metadata: null".to_string()),
+    }
+    lines.push(format!(
+        "tags: {}",
+        serde_json::to_string(&entry.tags).unwrap_or_else(|_| "[]".to_string())
+    ));
+    lines.push(format!("created_at: {}", quote(&entry.created_at)));
+    lines.push(format!("updated_at: {}", quote(&entry.updated_at)));
+    lines.push("---".to_string());
+
+    format!("{}\n\n{}", lines.join("\n"), entry.content)
+}
+
+/// A knowledge entry as read back from a vault file
+#[derive(Debug, Clone, PartialEq)]
+struct VaultEntry {
+    id: Option<String>,
+    title: String,
+    content: String,
+    category: Option<String>,
+    source: Option<String>,
+    metadata: Option<serde_json::Value>,
+    tags: Option<Vec<String>>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+}
+
+/// Decode a frontmatter scalar: JSON-quoted strings are unescaped, `null`/empty become None
+fn frontmatter_string(value: Option<&String>) -> Option<String> {
+    let value = value?.trim();
+    if value.is_empty() || value == "null" || value == "~" {
+        return None;
+    }
+    if value.starts_with('"') {
+        return serde_json::from_str::<String>(value).ok();
+    }
+    Some(value.trim_matches('\'').to_string())
+}
+
+fn parse_vault_file(raw: &str, fallback_title: &str) -> VaultEntry {
+    let (frontmatter, body) = crate::commands::gsd::parse_frontmatter(raw);
+
+    // render_vault_file separates frontmatter and content with one blank line
+    let content = if raw.starts_with("---") {
+        body.strip_prefix("\n\n")
+            .or_else(|| body.strip_prefix('\n'))
+            .unwrap_or(&body)
+            .to_string()
+    } else {
+        raw.to_string()
+    };
+
+    let metadata = frontmatter
+        .get("metadata")
+        .map(|m| m.trim())
+        .filter(|m| !m.is_empty() && *m != "null")
+        .and_then(|m| serde_json::from_str(m).ok());
+
+    VaultEntry {
+        id: frontmatter_string(frontmatter.get("id")),
+        title: frontmatter_string(frontmatter.get("title"))
+            .unwrap_or_else(|| fallback_title.to_string()),
+        content,
+        category: frontmatter_string(frontmatter.get("category")),
+        source: frontmatter_string(frontmatter.get("source")),
+        metadata,
+        tags: frontmatter
+            .get("tags")
+            .and_then(|t| serde_json::from_str(t.trim()).ok()),
+        created_at: frontmatter_string(frontmatter.get("created_at")),
+        updated_at: frontmatter_string(frontmatter.get("updated_at")),
+    }
+}
+
+/// Recursively collect `.md` files, skipping hidden directories such as `.git`
+fn collect_vault_files(dir: &std::path::Path, files: &mut Vec<std::path::PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_vault_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            files.push(path);
+        }
+    }
+}
+
+/// Export knowledge entries as a markdown vault.
+/// Exports a single project when `project_id` is given, otherwise every project.
+/// Entries in the trash are not exported.
+#[tauri::command]
+pub async fn knowledge_export_vault(
+    db: tauri::State<'_, DbState>,
+    directory: String,
+    project_id: Option<String>,
+) -> Result<KnowledgeVaultExportResult, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}, p.name
+             FROM knowledge k
+             JOIN projects p ON p.id = k.project_id
+             WHERE k.deleted_at IS NULL AND (?1 IS NULL OR k.project_id = ?1)
+             ORDER BY p.name, k.category, k.created_at",
+            KNOWLEDGE_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let entries = stmt
+        .query_map(params![project_id], |row| {
+            Ok((row_to_knowledge(row)?, row.get::<_, String>(11)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let root = std::path::Path::new(&directory);
+
+    // Files from earlier exports, by frontmatter id, so an entry whose title or
+    // category changed doesn't leave its old file behind
+    let mut previous_files: std::collections::HashMap<String, Vec<std::path::PathBuf>> =
+        std::collections::HashMap::new();
+    if root.is_dir() {
+        let mut existing = Vec::new();
+        collect_vault_files(root, &mut existing);
+        for path in existing {
+            let Ok(raw) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            if let Some(id) = parse_vault_file(&raw, "").id {
+                previous_files.entry(id).or_default().push(path);
+            }
+        }
+    }
+
+    let mut files_written = 0;
+    for (entry, project_name) in &entries {
+        let folder = root.join(slugify(project_name)).join(slugify(&entry.category));
+        std::fs::create_dir_all(&folder).map_err(|e| e.to_string())?;
+
+        let filename = format!(
+            "{}-{}.md",
+            slugify(&entry.title),
+            entry.id.chars().take(8).collect::<String>()
+        );
+        let path = folder.join(filename);
+        for stale in previous_files.remove(&entry.id).unwrap_or_default() {
+            if stale != path {
+                std::fs::remove_file(&stale).map_err(|e| e.to_string())?;
+            }
+        }
+        std::fs::write(&path, render_vault_file(entry)).map_err(|e| e.to_string())?;
+        files_written += 1;
+    }
+
+    tracing::info!("Exported {} knowledge entries to vault {}", files_written, directory);
+    Ok(KnowledgeVaultExportResult {
+        directory,
+        files_written,
+    })
+}
+
+/// Import a markdown vault into a project, upserting by frontmatter `id`.
+///
+/// An existing entry is only overwritten when it has not changed since the file
+/// was exported (matching `updated_at`); otherwise the file is reported as a
+/// conflict unless `overwrite_conflicts` is set. Overwrites go through the
+/// revision history, so the replaced content can still be restored.
+#[tauri::command]
+pub async fn knowledge_import_vault(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    directory: String,
+    overwrite_conflicts: Option<bool>,
+) -> Result<KnowledgeVaultImportResult, String> {
+    let overwrite_conflicts = overwrite_conflicts.unwrap_or(false);
+    let root = std::path::Path::new(&directory);
+    if !root.is_dir() {
+        return Err(format!("Vault directory not found: {}", directory));
+    }
+
+    let mut files = Vec::new();
+    collect_vault_files(root, &mut files);
+    files.sort();
+
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let mut result = KnowledgeVaultImportResult {
+        created: 0,
+        updated: 0,
+        unchanged: 0,
+        conflicts: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    for path in files {
+        let file_path = path.to_string_lossy().to_string();
+        let raw = match std::fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) => {
+                result.errors.push(format!("{}: {}", file_path, e));
+                continue;
+            }
+        };
+        let fallback_title = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let file = parse_vault_file(&raw, &fallback_title);
+
+        // Category comes from frontmatter, falling back to the containing folder name
+        let category = file
+            .category
+            .clone()
+            .or_else(|| {
+                path.parent()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+            })
+            .filter(|c| validate_category(conn, &project_id, c).is_ok())
+            .unwrap_or_else(|| "learning".to_string());
+        let metadata_str = file
+            .metadata
+            .as_ref()
+            .map(|m| serde_json::to_string(m).unwrap_or_default());
+
+        let existing = match &file.id {
+            Some(id) => fetch_knowledge(conn, id).ok(),
+            None => None,
+        };
+
+        let Some(existing) = existing else {
+            let knowledge_id = file
+                .id
+                .clone()
+                .unwrap_or_else(|| format!("{:032x}", rand::random::<u128>()));
+            conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+            let inserted = conn
+                .execute(
+                    "INSERT INTO knowledge (id, project_id, title, content, category, source, metadata, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, COALESCE(?8, datetime('now')), COALESCE(?9, datetime('now')))",
+                    params![
+                        knowledge_id,
+                        project_id,
+                        file.title,
+                        file.content,
+                        category,
+                        file.source,
+                        metadata_str,
+                        file.created_at,
+                        file.updated_at
+                    ],
+                )
+                .map_err(|e| e.to_string())
+                .and_then(|_| match &file.tags {
+                    Some(tags) => set_entry_tags(conn, &project_id, &knowledge_id, tags),
+                    None => Ok(()),
+                });
+            match inserted {
+                Ok(_) => {
+                    conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+                    result.created += 1;
+                }
+                Err(e) => {
+                    let _ = conn.execute_batch("ROLLBACK");
+                    result.errors.push(format!("{}: {}", file_path, e));
+                }
+            }
+            continue;
+        };
+
+        let conflict = |reason: &str| KnowledgeVaultConflict {
+            knowledge_id: existing.id.clone(),
+            file_path: file_path.clone(),
+            title: file.title.clone(),
+            reason: reason.to_string(),
+        };
+
+        if existing.project_id != project_id {
+            result
+                .conflicts
+                .push(conflict("Entry id belongs to a different project"));
+            continue;
+        }
+
+        let same_content = existing.title == file.title
+            && existing.content == file.content
+            && existing.category == category
+            && existing.source == file.source
+            && existing.metadata == file.metadata
+            && file.tags.as_ref().is_none_or(|tags| {
+                let mut normalized: Vec<String> =
+                    tags.iter().filter_map(|t| normalize_tag(t)).collect();
+                normalized.sort();
+                normalized.dedup();
+                normalized == existing.tags
+            });
+        if same_content && existing.deleted_at.is_none() {
+            result.unchanged += 1;
+            continue;
+        }
+
+        let unchanged_since_export = file.updated_at.as_deref() == Some(existing.updated_at.as_str());
+        if !overwrite_conflicts {
+            if existing.deleted_at.is_some() {
+                result.conflicts.push(conflict("Entry is in the trash"));
+                continue;
+            }
+            if !unchanged_since_export {
+                result
+                    .conflicts
+                    .push(conflict("Entry was modified in the database since this file was exported"));
+                continue;
+            }
+        }
+
+        conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+        let applied = record_revision(conn, &existing.id, "update").and_then(|_| {
+            conn.execute(
+                "UPDATE knowledge
+                 SET title = ?1, content = ?2, category = ?3, source = ?4, metadata = ?5,
+                     deleted_at = NULL, updated_at = datetime('now')
+                 WHERE id = ?6",
+                params![
+                    file.title,
+                    file.content,
+                    category,
+                    file.source,
+                    metadata_str,
+                    existing.id
+                ],
+            )
+            .map_err(|e| e.to_string())
+        })
+        .and_then(|_| match &file.tags {
+            Some(tags) => set_entry_tags(conn, &project_id, &existing.id, tags),
+            None => Ok(()),
+        });
+        match applied {
+            Ok(_) => {
+                conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+                result.updated += 1;
+            }
+            Err(e) => {
+                let _ = conn.execute_batch("ROLLBACK");
+                result.errors.push(format!("{}: {}", file_path, e));
+            }
+        }
+    }
+
+    tracing::info!(
+        "Vault import into {}: {} created, {} updated, {} unchanged, {} conflicts",
+        project_id,
+        result.created,
+        result.updated,
+        result.unchanged,
+        result.conflicts.len()
+    );
+    Ok(result)
 }
 
 // ============================================================
@@ -548,3 +1618,81 @@ pub async fn delete_knowledge_bookmark(
 
     Ok(deleted > 0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ops(lines: &[KnowledgeDiffLine]) -> Vec<(&str, &str)> {
+        lines.iter().map(|l| (l.op.as_str(), l.text.as_str())).collect()
+    }
+
+    #[test]
+    fn diff_lines_marks_changed_line_as_delete_then_insert() {
+        let diff = diff_lines("# Title\nold line\nfooter", "# Title\nnew line\nfooter");
+        assert_eq!(
+            ops(&diff),
+            vec![
+                ("equal", "# Title"),
+                ("delete", "old line"),
+                ("insert", "new line"),
+                ("equal", "footer"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_lines_handles_empty_sides() {
+        assert_eq!(ops(&diff_lines("", "a\nb")), vec![("insert", "a"), ("insert", "b")]);
+        assert_eq!(ops(&diff_lines("a", "")), vec![("delete", "a")]);
+        assert!(diff_lines("", "").is_empty());
+    }
+
+    #[test]
+    fn vault_file_round_trips_through_frontmatter() {
+        let entry = Knowledge {
+            id: "abc123".to_string(),
+            project_id: "proj".to_string(),
+            title: "Use \"WAL\" mode: always".to_string(),
+            content: "# Notes\n\n---\nSeparated body\n".to_string(),
+            category: "decision".to_string(),
+            source: Some("file://docs/db.md".to_string()),
+            metadata: Some(serde_json::json!({"tags": ["db", "sqlite"]})),
+            deleted_at: None,
+            tags: vec!["db".to_string(), "wal".to_string()],
+            created_at: "2026-01-02 03:04:05".to_string(),
+            updated_at: "2026-01-03 03:04:05".to_string(),
+        };
+
+        let parsed = parse_vault_file(&render_vault_file(&entry), "fallback");
+        assert_eq!(parsed.id.as_deref(), Some("abc123"));
+        assert_eq!(parsed.title, entry.title);
+        assert_eq!(parsed.content, entry.content);
+        assert_eq!(parsed.category.as_deref(), Some("decision"));
+        assert_eq!(parsed.source, entry.source);
+        assert_eq!(parsed.metadata, entry.metadata);
+        assert_eq!(parsed.tags, Some(entry.tags.clone()));
+        assert_eq!(parsed.updated_at.as_deref(), Some("2026-01-03 03:04:05"));
+    }
+
+    #[test]
+    fn vault_file_without_frontmatter_uses_fallback_title() {
+        let parsed = parse_vault_file("Just some notes", "my-note");
+        assert_eq!(parsed.id, None);
+        assert_eq!(parsed.title, "my-note");
+        assert_eq!(parsed.content, "Just some notes");
+    }
+
+    #[test]
+    fn normalize_tag_lowercases_and_joins_words() {
+        assert_eq!(normalize_tag("  API Contract "), Some("api-contract".to_string()));
+        assert_eq!(normalize_tag("a,b"), Some("a-b".to_string()));
+        assert_eq!(normalize_tag("   "), None);
+    }
+
+    #[test]
+    fn slugify_collapses_separators() {
+        assert_eq!(slugify("API Contract: v2 / Auth"), "api-contract-v2-auth");
+        assert_eq!(slugify("!!!"), "untitled");
+    }
+}
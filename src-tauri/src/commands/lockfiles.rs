@@ -0,0 +1,1284 @@
+// Track Your Shit - Lockfile Inventory Commands
+// Offline dependency inventory from lockfiles, exported as a CycloneDX or SPDX SBOM
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct InventoryPackage {
+    pub ecosystem: String, // "npm" | "cargo" | "pypi" | "go"
+    pub name: String,
+    pub version: String,
+    /// Package URL; also identifies the package within the inventory
+    pub purl: String,
+    /// Declared by the project itself rather than pulled in by another package
+    pub direct: bool,
+    /// Only needed for development (when the lockfile records it)
+    pub dev: bool,
+    /// Where it resolves from: registry, tarball URL, git or path
+    pub source: Option<String>,
+    /// Checksum as recorded in the lockfile
+    pub checksum: Option<String>,
+    pub lockfile: String,
+    /// purls of the packages it depends on
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct DependencyInventory {
+    pub project_path: String,
+    pub lockfiles: Vec<String>,
+    pub packages: Vec<InventoryPackage>,
+    pub direct_count: usize,
+    pub transitive_count: usize,
+    /// Lockfiles that couldn't be parsed
+    pub errors: Vec<String>,
+}
+
+type LockfileParser = fn(&Path, &str) -> Result<Vec<InventoryPackage>, String>;
+
+/// Supported lockfiles, in the order they're read
+const LOCKFILES: &[(&str, LockfileParser)] = &[
+    ("package-lock.json", parse_package_lock),
+    ("pnpm-lock.yaml", parse_pnpm_lock),
+    ("yarn.lock", parse_yarn_lock),
+    ("Cargo.lock", parse_cargo_lock),
+    ("poetry.lock", parse_poetry_lock),
+    ("uv.lock", parse_uv_lock),
+    ("go.sum", parse_go_sum),
+];
+
+fn purl(ecosystem: &str, name: &str, version: &str) -> String {
+    let version = version.replace('+', "%2B");
+    match ecosystem {
+        "npm" => format!("pkg:npm/{}@{}", name.replacen('@', "%40", 1), version),
+        "pypi" => format!("pkg:pypi/{}@{}", normalize_python_name(name), version),
+        "go" => format!("pkg:golang/{}@{}", name, version),
+        _ => format!("pkg:{}/{}@{}", ecosystem, name, version),
+    }
+}
+
+fn package(ecosystem: &str, name: &str, version: &str, lockfile: &str) -> InventoryPackage {
+    InventoryPackage {
+        ecosystem: ecosystem.to_string(),
+        name: name.to_string(),
+        version: version.to_string(),
+        purl: purl(ecosystem, name, version),
+        direct: false,
+        dev: false,
+        source: None,
+        checksum: None,
+        lockfile: lockfile.to_string(),
+        dependencies: Vec::new(),
+    }
+}
+
+/// PEP 503 name normalization
+fn normalize_python_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !out.ends_with('-') {
+                out.push('-');
+            }
+        } else {
+            out.push(c.to_ascii_lowercase());
+        }
+    }
+    out
+}
+
+/// Split `name@version` where the name may be scoped (`@scope/name@1.0.0`)
+fn split_at_version(spec: &str) -> Option<(&str, &str)> {
+    let at = spec.get(1..)?.find('@')? + 1;
+    Some((&spec[..at], &spec[at + 1..]))
+}
+
+/// For lockfiles that don't record it: anything not reachable from a
+/// runtime root is dev-only
+fn mark_dev_only(packages: &mut [InventoryPackage], runtime_roots: Vec<String>) {
+    let index: HashMap<String, usize> = packages.iter().enumerate().map(|(i, p)| (p.purl.clone(), i)).collect();
+    let mut runtime: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<String> = runtime_roots.into();
+    while let Some(id) = queue.pop_front() {
+        if runtime.insert(id.clone()) {
+            if let Some(i) = index.get(&id) {
+                queue.extend(packages[*i].dependencies.iter().cloned());
+            }
+        }
+    }
+    for pkg in packages.iter_mut() {
+        pkg.dev = !runtime.contains(&pkg.purl);
+    }
+}
+
+// ----- npm -----
+
+/// Dependency specifiers declared in package.json, with whether each is dev-only
+fn package_json_deps(dir: &Path) -> HashMap<String, (String, bool)> {
+    let mut deps = HashMap::new();
+    let Ok(content) = std::fs::read_to_string(dir.join("package.json")) else {
+        return deps;
+    };
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return deps;
+    };
+    for (field, dev) in [
+        ("devDependencies", true),
+        ("peerDependencies", false),
+        ("optionalDependencies", false),
+        ("dependencies", false),
+    ] {
+        if let Some(map) = manifest.get(field).and_then(|v| v.as_object()) {
+            for (name, spec) in map {
+                deps.insert(name.clone(), (spec.as_str().unwrap_or_default().to_string(), dev));
+            }
+        }
+    }
+    deps
+}
+
+fn json_str(value: &serde_json::Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+fn json_keys<'a>(value: &'a serde_json::Value, fields: &[&str]) -> Vec<&'a String> {
+    fields
+        .iter()
+        .filter_map(|field| value.get(*field).and_then(|v| v.as_object()))
+        .flat_map(|map| map.keys())
+        .collect()
+}
+
+/// npm's lockfile: v2/v3 keep a flat `packages` map keyed by install path,
+/// v1 a nested `dependencies` tree
+fn parse_package_lock(dir: &Path, content: &str) -> Result<Vec<InventoryPackage>, String> {
+    let lock: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let Some(packages) = lock.get("packages").and_then(|p| p.as_object()) else {
+        return parse_package_lock_v1(dir, &lock);
+    };
+
+    // The root and any workspace packages (paths outside node_modules) declare the direct deps
+    let mut direct: HashSet<&str> = HashSet::new();
+    for (path, meta) in packages {
+        if !path.contains("node_modules/") {
+            direct.extend(
+                json_keys(meta, &["dependencies", "devDependencies", "optionalDependencies", "peerDependencies"])
+                    .into_iter()
+                    .map(|name| name.as_str()),
+            );
+        }
+    }
+
+    let name_at = |path: &str, meta: &serde_json::Value| {
+        json_str(meta, "name").unwrap_or_else(|| {
+            path.rsplit_once("node_modules/").map_or(path, |(_, name)| name).to_string()
+        })
+    };
+    let purls: HashMap<&str, String> = packages
+        .iter()
+        .filter(|(path, meta)| path.contains("node_modules/") && meta.get("version").is_some())
+        .map(|(path, meta)| {
+            let version = json_str(meta, "version").unwrap_or_default();
+            (path.as_str(), purl("npm", &name_at(path, meta), &version))
+        })
+        .collect();
+
+    // Node resolution: look in the package's own node_modules, then each parent's
+    let resolve = |from: &str, name: &str| -> Option<String> {
+        let mut base = from;
+        loop {
+            let candidate = if base.is_empty() {
+                format!("node_modules/{}", name)
+            } else {
+                format!("{}/node_modules/{}", base, name)
+            };
+            if let Some(purl) = purls.get(candidate.as_str()) {
+                return Some(purl.clone());
+            }
+            if base.is_empty() {
+                return None;
+            }
+            base = base.rfind("node_modules/").map_or("", |i| base[..i].trim_end_matches('/'));
+        }
+    };
+
+    let mut out = Vec::new();
+    for (path, meta) in packages {
+        if !purls.contains_key(path.as_str()) || meta.get("link").and_then(|l| l.as_bool()) == Some(true) {
+            continue;
+        }
+        let name = name_at(path, meta);
+        let version = json_str(meta, "version").unwrap_or_default();
+        let mut pkg = package("npm", &name, &version, "package-lock.json");
+        pkg.direct = *path == format!("node_modules/{}", name) && direct.contains(name.as_str());
+        pkg.dev = meta.get("dev").and_then(|d| d.as_bool()).unwrap_or(false);
+        pkg.source = json_str(meta, "resolved");
+        pkg.checksum = json_str(meta, "integrity");
+        pkg.dependencies = json_keys(meta, &["dependencies", "optionalDependencies", "peerDependencies"])
+            .into_iter()
+            .filter_map(|dep| resolve(path, dep))
+            .collect();
+        out.push(pkg);
+    }
+    Ok(out)
+}
+
+fn parse_package_lock_v1(dir: &Path, lock: &serde_json::Value) -> Result<Vec<InventoryPackage>, String> {
+    let Some(root) = lock.get("dependencies").and_then(|d| d.as_object()) else {
+        return Ok(Vec::new());
+    };
+    let direct = package_json_deps(dir);
+    let mut out = Vec::new();
+    let mut scopes = vec![root];
+    walk_package_lock_v1(root, &mut scopes, &direct, &mut out);
+    Ok(out)
+}
+
+/// `scopes` holds the `dependencies` maps from the root down to `level`
+fn walk_package_lock_v1<'a>(
+    level: &'a serde_json::Map<String, serde_json::Value>,
+    scopes: &mut Vec<&'a serde_json::Map<String, serde_json::Value>>,
+    direct: &HashMap<String, (String, bool)>,
+    out: &mut Vec<InventoryPackage>,
+) {
+    for (name, meta) in level {
+        let Some(version) = json_str(meta, "version") else { continue };
+        let mut pkg = package("npm", name, &version, "package-lock.json");
+        pkg.direct = scopes.len() == 1 && direct.contains_key(name);
+        pkg.dev = meta.get("dev").and_then(|d| d.as_bool()).unwrap_or(false);
+        pkg.source = json_str(meta, "resolved");
+        pkg.checksum = json_str(meta, "integrity");
+
+        let nested = meta.get("dependencies").and_then(|d| d.as_object());
+        pkg.dependencies = json_keys(meta, &["requires"])
+            .into_iter()
+            .filter_map(|dep| {
+                nested
+                    .into_iter()
+                    .chain(scopes.iter().rev().copied())
+                    .find_map(|scope| scope.get(dep.as_str()))
+                    .and_then(|found| json_str(found, "version"))
+                    .map(|v| purl("npm", dep, &v))
+            })
+            .collect();
+        out.push(pkg);
+
+        if let Some(nested) = nested {
+            scopes.push(nested);
+            walk_package_lock_v1(nested, scopes, direct, out);
+            scopes.pop();
+        }
+    }
+}
+
+/// A `key: value` line of the YAML subset lockfiles use: (indent, key, value)
+fn yaml_line(line: &str) -> Option<(usize, String, String)> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('-') {
+        return None;
+    }
+    let indent = line.len() - trimmed.len();
+    let (key, rest) = match trimmed.chars().next() {
+        Some(quote @ ('\'' | '"')) => {
+            let end = trimmed[1..].find(quote)? + 1;
+            (&trimmed[1..end], trimmed[end + 1..].strip_prefix(':')?)
+        }
+        _ => match trimmed.find(": ") {
+            Some(i) => (&trimmed[..i], &trimmed[i + 1..]),
+            None => (trimmed.strip_suffix(':')?, ""),
+        },
+    };
+    let value = rest.trim().trim_matches(|c| c == '\'' || c == '"');
+    Some((indent, key.to_string(), value.to_string()))
+}
+
+/// A flow-map field: `{integrity: sha512-..., tarball: ...}` -> `tarball`
+fn flow_field(value: &str, field: &str) -> Option<String> {
+    value
+        .trim_matches(|c| c == '{' || c == '}')
+        .split(", ")
+        .find_map(|part| part.trim().strip_prefix(field)?.strip_prefix(": ").map(|v| v.trim().to_string()))
+}
+
+/// pnpm package key -> (name, version): `/name@1.0.0(peer@2)` (v6),
+/// `name@1.0.0` (v9) or `/name/1.0.0_peer@2` (v5)
+fn pnpm_key(key: &str) -> Option<(String, String)> {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    let key = key.split('(').next()?;
+    let (name, version) = match split_at_version(key) {
+        Some((name, version)) if !version.is_empty() => (name, version),
+        _ => {
+            let (name, version) = key.rsplit_once('/')?;
+            (name, version.split('_').next()?)
+        }
+    };
+    Some((name.to_string(), version.to_string()))
+}
+
+/// A dependency's resolved version in pnpm's lockfile: a plain version, or a
+/// package key for aliases and v5/v6 cross-references
+fn pnpm_dep(name: &str, version: &str) -> Option<(String, String)> {
+    if version.starts_with("link:") || version.starts_with("file:") {
+        return None;
+    }
+    if version.starts_with(|c: char| c.is_ascii_digit()) {
+        let version = version.split('(').next()?.split('_').next()?;
+        return Some((name.to_string(), version.to_string()));
+    }
+    pnpm_key(version)
+}
+
+fn parse_pnpm_lock(_dir: &Path, content: &str) -> Result<Vec<InventoryPackage>, String> {
+    let mut packages: Vec<InventoryPackage> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+    let mut direct: HashMap<String, bool> = HashMap::new();
+
+    let mut section = String::new();
+    let mut current: Option<String> = None; // purl of the package/snapshot being read
+    let mut group = String::new(); // indent-2/4 sub-key (dependency group or resolution)
+    let mut direct_name: Option<(String, bool)> = None; // importer dep awaiting its `version:`
+
+    for line in content.lines() {
+        let Some((indent, key, value)) = yaml_line(line) else { continue };
+        match (section.as_str(), indent) {
+            (_, 0) => {
+                section = key;
+                current = None;
+            }
+            // v5 single-project lockfiles list direct deps at the top level
+            ("dependencies" | "devDependencies" | "optionalDependencies", 2) => {
+                if let Some((name, version)) = pnpm_dep(&key, &value) {
+                    direct.insert(purl("npm", &name, &version), section == "devDependencies");
+                }
+            }
+            ("importers", 4) => group = key,
+            ("importers", 6) => {
+                let dev = group == "devDependencies";
+                direct_name = None;
+                if value.is_empty() {
+                    direct_name = Some((key, dev));
+                } else if let Some((name, version)) = pnpm_dep(&key, &value) {
+                    direct.insert(purl("npm", &name, &version), dev);
+                }
+            }
+            ("importers", 8) if key == "version" => {
+                if let Some((name, dev)) = direct_name.take() {
+                    if let Some((name, version)) = pnpm_dep(&name, &value) {
+                        direct.insert(purl("npm", &name, &version), dev);
+                    }
+                }
+            }
+            ("packages" | "snapshots", 2) => {
+                current = pnpm_key(&key).map(|(name, version)| {
+                    let pkg = package("npm", &name, &version, "pnpm-lock.yaml");
+                    let id = pkg.purl.clone();
+                    if !index.contains_key(&id) && section == "packages" {
+                        index.insert(id.clone(), packages.len());
+                        packages.push(pkg);
+                    }
+                    id
+                });
+                group.clear();
+            }
+            ("packages" | "snapshots", 4) => {
+                group = key.clone();
+                let Some(pkg) = current.as_ref().and_then(|id| index.get(id)).map(|i| &mut packages[*i]) else {
+                    continue;
+                };
+                if key == "resolution" && !value.is_empty() {
+                    pkg.checksum = flow_field(&value, "integrity");
+                    pkg.source = flow_field(&value, "tarball")
+                        .or_else(|| flow_field(&value, "repo"))
+                        .or_else(|| flow_field(&value, "directory"));
+                }
+            }
+            ("packages" | "snapshots", 6) => match group.as_str() {
+                "dependencies" | "optionalDependencies" => {
+                    if let (Some(id), Some((name, version))) = (&current, pnpm_dep(&key, &value)) {
+                        edges.entry(id.clone()).or_default().push(purl("npm", &name, &version));
+                    }
+                }
+                "resolution" => {
+                    if let Some(pkg) = current.as_ref().and_then(|id| index.get(id)).map(|i| &mut packages[*i]) {
+                        match key.as_str() {
+                            "integrity" => pkg.checksum = Some(value),
+                            "tarball" | "repo" | "directory" => pkg.source = Some(value),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    for pkg in &mut packages {
+        pkg.direct = direct.contains_key(&pkg.purl);
+        if pkg.source.is_none() && pkg.checksum.is_some() {
+            pkg.source = Some("registry".to_string());
+        }
+        if let Some(deps) = edges.remove(&pkg.purl) {
+            pkg.dependencies = deps;
+            pkg.dependencies.sort();
+            pkg.dependencies.dedup();
+        }
+    }
+    let runtime_roots = direct.iter().filter(|(_, dev)| !**dev).map(|(id, _)| id.clone()).collect();
+    mark_dev_only(&mut packages, runtime_roots);
+    Ok(packages)
+}
+
+/// Yarn classic (`name@^1:` / `version "1.0.0"`) and Berry (YAML with
+/// `name@npm:^1:` / `version: 1.0.0`) lockfiles
+fn parse_yarn_lock(dir: &Path, content: &str) -> Result<Vec<InventoryPackage>, String> {
+    struct Entry {
+        descriptors: Vec<String>,
+        fields: HashMap<String, String>,
+        deps: Vec<(String, String)>,
+    }
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut in_deps = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        // Classic separates key and value with a space, Berry with ": "
+        let (key, value) = match trimmed.find(": ").or_else(|| trimmed.strip_suffix(':').map(|s| s.len())) {
+            Some(i) if indent == 0 || trimmed.starts_with('"') || !trimmed[..i].contains(' ') => {
+                (&trimmed[..i], trimmed.get(i + 1..).unwrap_or_default())
+            }
+            _ => trimmed.split_once(' ').unwrap_or((trimmed, "")),
+        };
+        let key = key.trim_matches('"');
+        let value = value.trim().trim_matches('"');
+        match indent {
+            0 => {
+                in_deps = false;
+                entries.push(Entry {
+                    descriptors: key.split(", ").map(|d| d.trim_matches('"').to_string()).collect(),
+                    fields: HashMap::new(),
+                    deps: Vec::new(),
+                });
+            }
+            2 => {
+                in_deps = matches!(key, "dependencies" | "optionalDependencies");
+                if let Some(entry) = entries.last_mut() {
+                    entry.fields.insert(key.to_string(), value.to_string());
+                }
+            }
+            4 if in_deps => {
+                if let Some(entry) = entries.last_mut() {
+                    entry.deps.push((key.to_string(), value.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let declared = package_json_deps(dir);
+    let mut by_descriptor: HashMap<String, String> = HashMap::new();
+    let mut out: Vec<(InventoryPackage, Vec<(String, String)>)> = Vec::new();
+    for entry in entries {
+        let Some(version) = entry.fields.get("version") else { continue };
+        let Some((name, _)) = entry.descriptors.first().and_then(|d| split_at_version(d)) else {
+            continue;
+        };
+        let resolution = entry.fields.get("resolution");
+        if resolution.is_some_and(|r| r.contains("@workspace:") || r.contains("@link:")) {
+            continue;
+        }
+        let mut pkg = package("npm", name, version, "yarn.lock");
+        pkg.source = entry
+            .fields
+            .get("resolved")
+            .map(|url| url.split('#').next().unwrap_or(url).to_string())
+            .or_else(|| resolution.cloned());
+        pkg.checksum = entry.fields.get("integrity").or(entry.fields.get("checksum")).cloned();
+        for descriptor in &entry.descriptors {
+            by_descriptor.insert(descriptor.clone(), pkg.purl.clone());
+            let Some((name, range)) = split_at_version(descriptor) else { continue };
+            let range = range.strip_prefix("npm:").unwrap_or(range);
+            if let Some((spec, dev)) = declared.get(name) {
+                if spec == range {
+                    pkg.direct = true;
+                    pkg.dev = *dev;
+                }
+            }
+        }
+        out.push((pkg, entry.deps));
+    }
+
+    let mut packages: Vec<InventoryPackage> = out
+        .into_iter()
+        .map(|(mut pkg, deps)| {
+            pkg.dependencies = deps
+                .iter()
+                .filter_map(|(name, range)| {
+                    by_descriptor
+                        .get(&format!("{}@{}", name, range))
+                        .or_else(|| by_descriptor.get(&format!("{}@npm:{}", name, range)))
+                        .cloned()
+                })
+                .collect();
+            pkg
+        })
+        .collect();
+    let runtime_roots = packages.iter().filter(|p| p.direct && !p.dev).map(|p| p.purl.clone()).collect();
+    mark_dev_only(&mut packages, runtime_roots);
+    Ok(packages)
+}
+
+// ----- cargo -----
+
+#[derive(Deserialize)]
+struct CargoLock {
+    #[serde(default)]
+    package: Vec<CargoLockPackage>,
+}
+
+#[derive(Deserialize)]
+struct CargoLockPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    checksum: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+/// Workspace members and path dependencies (no `source`) are the project
+/// itself; what they depend on is direct
+fn parse_cargo_lock(_dir: &Path, content: &str) -> Result<Vec<InventoryPackage>, String> {
+    let lock: CargoLock = toml::from_str(content).map_err(|e| e.to_string())?;
+    let mut by_name: HashMap<&str, Vec<&CargoLockPackage>> = HashMap::new();
+    for pkg in &lock.package {
+        by_name.entry(pkg.name.as_str()).or_default().push(pkg);
+    }
+    // "name", "name version" or "name version (source)"
+    let resolve = |dep: &str| -> Option<&CargoLockPackage> {
+        let mut parts = dep.split_whitespace();
+        let name = parts.next()?;
+        let candidates = by_name.get(name)?;
+        match parts.next() {
+            Some(version) => candidates.iter().find(|p| p.version == version).copied(),
+            None => candidates.first().copied(),
+        }
+    };
+
+    let direct: HashSet<(&str, &str)> = lock
+        .package
+        .iter()
+        .filter(|p| p.source.is_none())
+        .flat_map(|p| p.dependencies.iter().filter_map(|d| resolve(d)))
+        .map(|p| (p.name.as_str(), p.version.as_str()))
+        .collect();
+
+    Ok(lock
+        .package
+        .iter()
+        .filter(|p| p.source.is_some())
+        .map(|p| {
+            let mut pkg = package("cargo", &p.name, &p.version, "Cargo.lock");
+            pkg.direct = direct.contains(&(p.name.as_str(), p.version.as_str()));
+            pkg.source = p.source.clone();
+            pkg.checksum = p.checksum.clone();
+            pkg.dependencies = p
+                .dependencies
+                .iter()
+                .filter_map(|d| resolve(d))
+                .filter(|d| d.source.is_some())
+                .map(|d| purl("cargo", &d.name, &d.version))
+                .collect();
+            pkg
+        })
+        .collect())
+}
+
+// ----- python -----
+
+/// Name at the start of a PEP 508 requirement (`requests[socks]>=2; python_version > "3"`)
+fn requirement_name(requirement: &str) -> &str {
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    &requirement[..end]
+}
+
+/// Normalized names declared in pyproject.toml, with whether each is dev-only.
+/// Covers PEP 621 `[project]`, PEP 735 `[dependency-groups]` and Poetry's tables.
+fn pyproject_deps(dir: &Path) -> HashMap<String, bool> {
+    let mut deps: HashMap<String, bool> = HashMap::new();
+    let Some(pyproject) = std::fs::read_to_string(dir.join("pyproject.toml"))
+        .ok()
+        .and_then(|c| toml::from_str::<toml::Value>(&c).ok())
+    else {
+        return deps;
+    };
+    let mut add = |name: &str, dev: bool| {
+        if !name.is_empty() && name != "python" {
+            let entry = deps.entry(normalize_python_name(name)).or_insert(dev);
+            *entry &= dev;
+        }
+    };
+    let requirements = |value: Option<&toml::Value>| -> Vec<String> {
+        value
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|i| i.as_str())
+                    .map(|r| requirement_name(r).to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let table_keys = |value: Option<&toml::Value>| -> Vec<String> {
+        value
+            .and_then(|v| v.as_table())
+            .map(|t| t.keys().cloned().collect())
+            .unwrap_or_default()
+    };
+
+    let project = pyproject.get("project");
+    for name in requirements(project.and_then(|p| p.get("dependencies"))) {
+        add(&name, false);
+    }
+    if let Some(extras) = project.and_then(|p| p.get("optional-dependencies")).and_then(|o| o.as_table()) {
+        for list in extras.values() {
+            for name in requirements(Some(list)) {
+                add(&name, false);
+            }
+        }
+    }
+    if let Some(groups) = pyproject.get("dependency-groups").and_then(|g| g.as_table()) {
+        for list in groups.values() {
+            for name in requirements(Some(list)) {
+                add(&name, true);
+            }
+        }
+    }
+    let poetry = pyproject.get("tool").and_then(|t| t.get("poetry"));
+    for name in table_keys(poetry.and_then(|p| p.get("dependencies"))) {
+        add(&name, false);
+    }
+    for name in table_keys(poetry.and_then(|p| p.get("dev-dependencies"))) {
+        add(&name, true);
+    }
+    if let Some(groups) = poetry.and_then(|p| p.get("group")).and_then(|g| g.as_table()) {
+        for (group, table) in groups {
+            for name in table_keys(table.get("dependencies")) {
+                add(&name, group != "main");
+            }
+        }
+    }
+    deps
+}
+
+#[derive(Deserialize)]
+struct PoetryLock {
+    #[serde(default)]
+    package: Vec<PoetryPackage>,
+}
+
+#[derive(Deserialize)]
+struct PoetryPackage {
+    name: String,
+    version: String,
+    category: Option<String>,
+    #[serde(default)]
+    groups: Vec<String>,
+    source: Option<PoetrySource>,
+    #[serde(default)]
+    dependencies: HashMap<String, toml::Value>,
+    #[serde(default)]
+    files: Vec<PoetryFile>,
+}
+
+#[derive(Deserialize)]
+struct PoetrySource {
+    #[serde(rename = "type")]
+    kind: String,
+    url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PoetryFile {
+    hash: String,
+}
+
+fn parse_poetry_lock(dir: &Path, content: &str) -> Result<Vec<InventoryPackage>, String> {
+    let lock: PoetryLock = toml::from_str(content).map_err(|e| e.to_string())?;
+    let declared = pyproject_deps(dir);
+    let purls: HashMap<String, String> = lock
+        .package
+        .iter()
+        .map(|p| (normalize_python_name(&p.name), purl("pypi", &p.name, &p.version)))
+        .collect();
+
+    Ok(lock
+        .package
+        .iter()
+        .map(|p| {
+            let mut pkg = package("pypi", &p.name, &p.version, "poetry.lock");
+            let declared_dev = declared.get(&normalize_python_name(&p.name));
+            pkg.direct = declared_dev.is_some();
+            pkg.dev = p.category.as_deref() == Some("dev")
+                || (!p.groups.is_empty() && !p.groups.iter().any(|g| g == "main"))
+                || declared_dev == Some(&true);
+            pkg.source = Some(match &p.source {
+                Some(source) => source.url.as_ref().map_or(source.kind.clone(), |url| format!("{}+{}", source.kind, url)),
+                None => "pypi".to_string(),
+            });
+            pkg.checksum = p.files.first().map(|f| f.hash.clone());
+            pkg.dependencies = p
+                .dependencies
+                .keys()
+                .filter_map(|name| purls.get(&normalize_python_name(name)).cloned())
+                .collect();
+            pkg
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct UvLock {
+    #[serde(default)]
+    package: Vec<UvPackage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct UvPackage {
+    name: String,
+    version: Option<String>,
+    #[serde(default)]
+    source: toml::Table,
+    #[serde(default)]
+    dependencies: Vec<UvDependency>,
+    #[serde(default)]
+    optional_dependencies: HashMap<String, Vec<UvDependency>>,
+    #[serde(default)]
+    dev_dependencies: HashMap<String, Vec<UvDependency>>,
+    sdist: Option<UvArtifact>,
+    #[serde(default)]
+    wheels: Vec<UvArtifact>,
+}
+
+#[derive(Deserialize)]
+struct UvDependency {
+    name: String,
+    version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UvArtifact {
+    hash: Option<String>,
+}
+
+/// The project's own packages are `editable`/`virtual` sources; their deps
+/// are direct, and anything only reachable through dev groups is dev-only
+fn parse_uv_lock(_dir: &Path, content: &str) -> Result<Vec<InventoryPackage>, String> {
+    let lock: UvLock = toml::from_str(content).map_err(|e| e.to_string())?;
+    let is_local = |p: &UvPackage| p.source.contains_key("editable") || p.source.contains_key("virtual");
+    let resolve = |dep: &UvDependency| -> Option<String> {
+        let name = normalize_python_name(&dep.name);
+        lock.package
+            .iter()
+            .filter(|p| !is_local(p) && normalize_python_name(&p.name) == name)
+            .find(|p| dep.version.is_none() || p.version == dep.version)
+            .map(|p| purl("pypi", &p.name, p.version.as_deref().unwrap_or_default()))
+    };
+
+    let mut main_roots = Vec::new();
+    let mut dev_roots = Vec::new();
+    for local in lock.package.iter().filter(|p| is_local(p)) {
+        main_roots.extend(
+            local
+                .dependencies
+                .iter()
+                .chain(local.optional_dependencies.values().flatten())
+                .filter_map(&resolve),
+        );
+        dev_roots.extend(local.dev_dependencies.values().flatten().filter_map(&resolve));
+    }
+
+    let mut packages: Vec<InventoryPackage> = lock
+        .package
+        .iter()
+        .filter(|p| !is_local(p))
+        .map(|p| {
+            let version = p.version.as_deref().unwrap_or_default();
+            let mut pkg = package("pypi", &p.name, version, "uv.lock");
+            pkg.source = p.source.iter().next().and_then(|(kind, value)| {
+                let value = value.as_str()?;
+                Some(match kind.as_str() {
+                    "registry" | "url" => value.to_string(),
+                    _ => format!("{}+{}", kind, value),
+                })
+            });
+            pkg.checksum = p
+                .sdist
+                .iter()
+                .chain(p.wheels.iter())
+                .find_map(|a| a.hash.clone());
+            pkg.dependencies = p
+                .dependencies
+                .iter()
+                .chain(p.optional_dependencies.values().flatten())
+                .filter_map(&resolve)
+                .collect();
+            pkg
+        })
+        .collect();
+
+    for pkg in &mut packages {
+        pkg.direct = main_roots.contains(&pkg.purl) || dev_roots.contains(&pkg.purl);
+    }
+    mark_dev_only(&mut packages, main_roots);
+    Ok(packages)
+}
+
+// ----- go -----
+
+/// Modules `require`d in go.mod: (module, version, indirect)
+fn go_mod_requires(dir: &Path) -> Vec<(String, String, bool)> {
+    let Ok(content) = std::fs::read_to_string(dir.join("go.mod")) else {
+        return Vec::new();
+    };
+    let mut requires = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.trim();
+        let spec = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        } else if let Some(spec) = line.strip_prefix("require ") {
+            spec
+        } else {
+            continue;
+        };
+        let indirect = spec.contains("// indirect");
+        let mut parts = spec.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            if !module.starts_with("//") {
+                requires.push((module.to_string(), version.to_string(), indirect));
+            }
+        }
+    }
+    requires
+}
+
+/// go.sum lists every module version consulted; only those with a content
+/// hash (not just `/go.mod`) are built. It has no dependency edges.
+fn parse_go_sum(dir: &Path, content: &str) -> Result<Vec<InventoryPackage>, String> {
+    let requires = go_mod_requires(dir);
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(module), Some(version), Some(hash)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        if version.ends_with("/go.mod") || !seen.insert((module, version)) {
+            continue;
+        }
+        let mut pkg = package("go", module, version, "go.sum");
+        pkg.direct = requires
+            .iter()
+            .any(|(m, v, indirect)| m == module && v == version && !indirect);
+        pkg.checksum = Some(hash.to_string());
+        out.push(pkg);
+    }
+    Ok(out)
+}
+
+// ----- inventory & SBOM -----
+
+/// Parse every supported lockfile in the project directory
+pub(crate) fn build_inventory(project_path: &str) -> DependencyInventory {
+    let dir = Path::new(project_path);
+    let mut lockfiles = Vec::new();
+    let mut packages: Vec<InventoryPackage> = Vec::new();
+    let mut errors = Vec::new();
+    for (file, parse) in LOCKFILES {
+        let Ok(content) = std::fs::read_to_string(dir.join(file)) else { continue };
+        match parse(dir, &content) {
+            Ok(parsed) => {
+                lockfiles.push(file.to_string());
+                packages.extend(parsed);
+            }
+            Err(e) => errors.push(format!("{}: {}", file, e)),
+        }
+    }
+    packages.sort_by(|a, b| (&a.ecosystem, &a.name, &a.version).cmp(&(&b.ecosystem, &b.name, &b.version)));
+    // The same package can appear in two npm lockfiles; keep the first
+    let mut seen = HashSet::new();
+    packages.retain(|p| seen.insert(p.purl.clone()));
+
+    let direct_count = packages.iter().filter(|p| p.direct).count();
+    DependencyInventory {
+        project_path: project_path.to_string(),
+        lockfiles,
+        transitive_count: packages.len() - direct_count,
+        direct_count,
+        packages,
+        errors,
+    }
+}
+
+fn base64_decode(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in input.bytes().filter(|c| *c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// A lockfile checksum as (CycloneDX algorithm, SPDX algorithm, hex digest).
+/// Go's `h1:` and Yarn Berry's checksums aren't plain file hashes and are skipped.
+fn checksum_digest(checksum: &str) -> Option<(&'static str, &'static str, String)> {
+    let checksum = checksum.split_whitespace().next()?;
+    let algorithm = |name: &str| match name {
+        "sha1" => Some(("SHA-1", "SHA1")),
+        "sha256" => Some(("SHA-256", "SHA256")),
+        "sha384" => Some(("SHA-384", "SHA384")),
+        "sha512" => Some(("SHA-512", "SHA512")),
+        _ => None,
+    };
+    // Subresource integrity (npm): sha512-<base64>
+    if let Some((name, b64)) = checksum.split_once('-') {
+        let (cdx, spdx) = algorithm(name)?;
+        let hex = base64_decode(b64)?.iter().map(|b| format!("{:02x}", b)).collect();
+        return Some((cdx, spdx, hex));
+    }
+    // Python: sha256:<hex>
+    if let Some((name, hex)) = checksum.split_once(':') {
+        let (cdx, spdx) = algorithm(name)?;
+        return Some((cdx, spdx, hex.to_lowercase()));
+    }
+    // Cargo: bare sha256 hex
+    (checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| ("SHA-256", "SHA256", checksum.to_lowercase()))
+}
+
+fn project_name(project_path: &str) -> String {
+    Path::new(project_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| project_path.to_string())
+}
+
+fn cyclonedx(inventory: &DependencyInventory) -> serde_json::Value {
+    let root_ref = "project";
+    let components: Vec<serde_json::Value> = inventory
+        .packages
+        .iter()
+        .map(|p| {
+            let mut component = serde_json::json!({
+                "type": "library",
+                "bom-ref": p.purl,
+                "name": p.name,
+                "version": p.version,
+                "purl": p.purl,
+                "scope": if p.dev { "optional" } else { "required" },
+                "properties": [
+                    { "name": "tys:direct", "value": p.direct.to_string() },
+                    { "name": "tys:lockfile", "value": p.lockfile },
+                ],
+            });
+            if let Some((alg, _, content)) = p.checksum.as_deref().and_then(checksum_digest) {
+                component["hashes"] = serde_json::json!([{ "alg": alg, "content": content }]);
+            }
+            if let Some(source) = p.source.as_deref().filter(|s| s.contains("://")) {
+                component["externalReferences"] = serde_json::json!([{ "type": "distribution", "url": source }]);
+            }
+            component
+        })
+        .collect();
+
+    let mut dependencies = vec![serde_json::json!({
+        "ref": root_ref,
+        "dependsOn": inventory.packages.iter().filter(|p| p.direct).map(|p| &p.purl).collect::<Vec<_>>(),
+    })];
+    dependencies.extend(
+        inventory
+            .packages
+            .iter()
+            .map(|p| serde_json::json!({ "ref": p.purl, "dependsOn": p.dependencies })),
+    );
+
+    serde_json::json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", uuid::Uuid::new_v4()),
+        "version": 1,
+        "metadata": {
+            "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "tools": { "components": [{ "type": "application", "name": "Track Your Shit" }] },
+            "component": {
+                "type": "application",
+                "bom-ref": root_ref,
+                "name": project_name(&inventory.project_path),
+            },
+        },
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+fn spdx(inventory: &DependencyInventory) -> serde_json::Value {
+    let name = project_name(&inventory.project_path);
+    let root_id = "SPDXRef-Project";
+    let ids: HashMap<&str, String> = inventory
+        .packages
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let safe: String = p
+                .name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '-' })
+                .collect();
+            (p.purl.as_str(), format!("SPDXRef-Package-{}-{}", i + 1, safe))
+        })
+        .collect();
+
+    let mut packages = vec![serde_json::json!({
+        "name": name,
+        "SPDXID": root_id,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "licenseConcluded": "NOASSERTION",
+        "licenseDeclared": "NOASSERTION",
+        "copyrightText": "NOASSERTION",
+    })];
+    packages.extend(inventory.packages.iter().map(|p| {
+        let mut package = serde_json::json!({
+            "name": p.name,
+            "SPDXID": ids[p.purl.as_str()],
+            "versionInfo": p.version,
+            "downloadLocation": p.source.as_deref().filter(|s| s.contains("://")).unwrap_or("NOASSERTION"),
+            "filesAnalyzed": false,
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": "NOASSERTION",
+            "copyrightText": "NOASSERTION",
+            "externalRefs": [{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": p.purl,
+            }],
+        });
+        if let Some((_, alg, value)) = p.checksum.as_deref().and_then(checksum_digest) {
+            package["checksums"] = serde_json::json!([{ "algorithm": alg, "checksumValue": value }]);
+        }
+        package
+    }));
+
+    let relationship = |from: &str, kind: &str, to: &str| {
+        serde_json::json!({ "spdxElementId": from, "relationshipType": kind, "relatedSpdxElement": to })
+    };
+    let mut relationships = vec![relationship("SPDXRef-DOCUMENT", "DESCRIBES", root_id)];
+    for p in &inventory.packages {
+        let id = &ids[p.purl.as_str()];
+        if p.direct {
+            let kind = if p.dev { "DEV_DEPENDENCY_OF" } else { "DEPENDENCY_OF" };
+            relationships.push(relationship(id, kind, root_id));
+        }
+        for dep in p.dependencies.iter().filter_map(|d| ids.get(d.as_str())) {
+            relationships.push(relationship(id, "DEPENDS_ON", dep));
+        }
+    }
+
+    serde_json::json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": format!("{} dependencies", name),
+        "documentNamespace": format!("https://spdx.org/spdxdocs/{}-{}", name, uuid::Uuid::new_v4()),
+        "creationInfo": {
+            "created": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "creators": ["Tool: Track Your Shit"],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+/// Every dependency pinned in the project's lockfiles, without running any
+/// package manager or touching the network
+#[tauri::command]
+pub async fn get_dependency_inventory(project_path: String) -> Result<DependencyInventory, String> {
+    tauri::async_runtime::spawn_blocking(move || build_inventory(&project_path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export the dependency inventory as an SBOM document (JSON).
+/// `format` is "cyclonedx" (1.5) or "spdx" (2.3).
+#[tauri::command]
+pub async fn export_sbom(project_path: String, format: String) -> Result<String, String> {
+    let inventory = get_dependency_inventory(project_path).await?;
+    if inventory.lockfiles.is_empty() {
+        return Err(match inventory.errors.first() {
+            Some(error) => format!("No lockfile could be read ({})", error),
+            None => "No supported lockfile found".to_string(),
+        });
+    }
+    let document = match format.as_str() {
+        "cyclonedx" => cyclonedx(&inventory),
+        "spdx" => spdx(&inventory),
+        other => return Err(format!("Unknown SBOM format '{}' (expected cyclonedx or spdx)", other)),
+    };
+    serde_json::to_string_pretty(&document).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(packages: &'a [InventoryPackage], name: &str) -> &'a InventoryPackage {
+        packages.iter().find(|p| p.name == name).unwrap()
+    }
+
+    #[test]
+    fn parses_npm_lockfiles() {
+        let dir = Path::new("/nonexistent");
+        let package_lock = r#"{
+          "lockfileVersion": 3,
+          "packages": {
+            "": { "dependencies": { "react": "^18.3.1" }, "devDependencies": { "@types/react": "^18" } },
+            "node_modules/react": { "version": "18.3.1", "resolved": "https://registry.npmjs.org/react/-/react-18.3.1.tgz",
+              "integrity": "sha512-AAAA", "dependencies": { "loose-envify": "^1.1.0" } },
+            "node_modules/loose-envify": { "version": "1.4.0" },
+            "node_modules/react/node_modules/loose-envify": { "version": "1.0.0" },
+            "node_modules/@types/react": { "version": "18.3.27", "dev": true }
+          }
+        }"#;
+        let packages = parse_package_lock(dir, package_lock).unwrap();
+        let react = find(&packages, "react");
+        assert!(react.direct && !react.dev);
+        assert_eq!(react.dependencies, ["pkg:npm/loose-envify@1.0.0"]);
+        assert_eq!(packages.iter().filter(|p| p.name == "loose-envify" && !p.direct).count(), 2);
+        let types = find(&packages, "@types/react");
+        assert!(types.direct && types.dev);
+        assert_eq!(types.purl, "pkg:npm/%40types/react@18.3.27");
+
+        let pnpm = "lockfileVersion: '9.0'\n\nimporters:\n\n  .:\n    dependencies:\n      '@dnd-kit/core':\n        specifier: ^6.3.1\n        version: 6.3.1(react@18.3.1)\n\n\
+                    packages:\n\n  '@dnd-kit/core@6.3.1':\n    resolution: {integrity: sha512-BBBB}\n\n  tslib@2.8.1:\n    resolution: {integrity: sha512-CCCC}\n\n\
+                    snapshots:\n\n  '@dnd-kit/core@6.3.1(react@18.3.1)':\n    dependencies:\n      tslib: 2.8.1\n\n  tslib@2.8.1: {}\n";
+        let packages = parse_pnpm_lock(dir, pnpm).unwrap();
+        let core = find(&packages, "@dnd-kit/core");
+        assert!(core.direct);
+        assert_eq!(core.checksum.as_deref(), Some("sha512-BBBB"));
+        assert_eq!(core.dependencies, ["pkg:npm/tslib@2.8.1"]);
+        assert!(!find(&packages, "tslib").direct);
+
+        let yarn = "# yarn lockfile v1\n\n\"@babel/core@^7.0.0\", \"@babel/core@^7.1.0\":\n  version \"7.24.0\"\n  \
+                    resolved \"https://registry.yarnpkg.com/@babel/core/-/core-7.24.0.tgz#abc\"\n  integrity sha512-DDDD\n  dependencies:\n    \
+                    debug \"^4.1.0\"\n\ndebug@^4.1.0:\n  version \"4.3.4\"\n";
+        let packages = parse_yarn_lock(dir, yarn).unwrap();
+        let babel = find(&packages, "@babel/core");
+        assert_eq!(babel.version, "7.24.0");
+        assert_eq!(babel.source.as_deref(), Some("https://registry.yarnpkg.com/@babel/core/-/core-7.24.0.tgz"));
+        assert_eq!(babel.dependencies, ["pkg:npm/debug@4.3.4"]);
+    }
+
+    #[test]
+    fn parses_cargo_python_and_go_lockfiles() {
+        let dir = Path::new("/nonexistent");
+        let cargo = r#"
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = ["serde", "toml 0.8.2"]
+
+            [[package]]
+            name = "serde"
+            version = "1.0.200"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            checksum = "ddc6f9cc94d67c0e21aaf7eda3a010fd3af78ebf6e096aa6e2e13c79749cce4f"
+
+            [[package]]
+            name = "toml"
+            version = "0.8.2"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            dependencies = ["serde"]
+        "#;
+        let packages = parse_cargo_lock(dir, cargo).unwrap();
+        assert_eq!(packages.len(), 2);
+        assert!(packages.iter().all(|p| p.direct));
+        assert_eq!(find(&packages, "toml").dependencies, ["pkg:cargo/serde@1.0.200"]);
+
+        let uv = r#"
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            source = { editable = "." }
+            dependencies = [{ name = "requests" }]
+            [package.dev-dependencies]
+            dev = [{ name = "pytest" }]
+
+            [[package]]
+            name = "requests"
+            version = "2.32.3"
+            source = { registry = "https://pypi.org/simple" }
+            dependencies = [{ name = "certifi" }]
+            sdist = { url = "https://files.pythonhosted.org/r.tar.gz", hash = "sha256:55365417734eb18255590a9ff9eb97e9e1da868d4ccd6402399eaf68af20a760" }
+
+            [[package]]
+            name = "certifi"
+            version = "2024.8.30"
+            source = { registry = "https://pypi.org/simple" }
+
+            [[package]]
+            name = "pytest"
+            version = "8.3.3"
+            source = { registry = "https://pypi.org/simple" }
+        "#;
+        let packages = parse_uv_lock(dir, uv).unwrap();
+        let requests = find(&packages, "requests");
+        assert!(requests.direct && !requests.dev);
+        assert_eq!(requests.dependencies, ["pkg:pypi/certifi@2024.8.30"]);
+        assert!(!find(&packages, "certifi").direct && !find(&packages, "certifi").dev);
+        assert!(find(&packages, "pytest").direct && find(&packages, "pytest").dev);
+        let (alg, _, hex) = checksum_digest(requests.checksum.as_deref().unwrap()).unwrap();
+        assert_eq!((alg, hex.len()), ("SHA-256", 64));
+
+        let go_sum = "golang.org/x/text v0.14.0 h1:abc=\ngolang.org/x/text v0.14.0/go.mod h1:def=\ngolang.org/x/text v0.3.0/go.mod h1:ghi=\n";
+        let packages = parse_go_sum(dir, go_sum).unwrap();
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].purl, "pkg:golang/golang.org/x/text@v0.14.0");
+
+        let (alg, spdx_alg, hex) = checksum_digest("sha512-3q2+7w==").unwrap();
+        assert_eq!((alg, spdx_alg, hex.as_str()), ("SHA-512", "SHA512", "deadbeef"));
+    }
+}
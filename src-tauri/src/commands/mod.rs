@@ -2,19 +2,30 @@
 // Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
 
 pub mod activity;
+pub mod advisories;
+pub mod audit_history;
+pub mod auto_commands;
+pub mod costs;
+pub mod coverage;
 pub mod data;
 pub mod dependencies;
+pub mod env_profiles;
 pub mod filesystem;
 pub mod git;
 pub mod gsd;
+pub mod jobs;
 pub mod knowledge;
+pub mod lockfiles;
 pub mod logs;
 pub mod notifications;
 pub mod projects;
 pub mod pty;
 pub mod search;
 pub mod secrets;
+pub mod session_state;
 pub mod settings;
 pub mod snippets;
 pub mod terminal;
+pub mod terminal_profiles;
+pub mod test_runs;
 pub mod watcher;
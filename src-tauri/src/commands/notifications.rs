@@ -3,7 +3,7 @@
 // Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
 
 use crate::models::{CreateNotificationInput, Notification};
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 
@@ -70,16 +70,12 @@ pub async fn get_unread_notification_count(db: tauri::State<'_, DbState>) -> Res
     Ok(count)
 }
 
-/// Create a new notification, emit event, and auto-prune old entries
-#[tauri::command]
-pub async fn create_notification(
-    db: tauri::State<'_, DbState>,
-    app: AppHandle,
-    input: CreateNotificationInput,
+/// Insert a notification and auto-prune old entries. Callers emit
+/// "notification:new" themselves once they hold an AppHandle.
+pub(crate) fn insert_notification(
+    conn: &Connection,
+    input: &CreateNotificationInput,
 ) -> Result<Notification, String> {
-    let db = db.write().await;
-    let conn = db.conn();
-
     let notification_id = format!("{:032x}", rand::random::<u128>());
 
     conn.execute(
@@ -116,9 +112,6 @@ pub async fn create_notification(
         )
         .map_err(|e| e.to_string())?;
 
-    // Emit event for real-time updates
-    let _ = app.emit("notification:new", &notification);
-
     // Auto-prune: keep only the most recent MAX_NOTIFICATIONS entries
     conn.execute(
         "DELETE FROM notifications
@@ -134,6 +127,22 @@ pub async fn create_notification(
     Ok(notification)
 }
 
+/// Create a new notification, emit event, and auto-prune old entries
+#[tauri::command]
+pub async fn create_notification(
+    db: tauri::State<'_, DbState>,
+    app: AppHandle,
+    input: CreateNotificationInput,
+) -> Result<Notification, String> {
+    let db = db.write().await;
+    let notification = insert_notification(db.conn(), &input)?;
+
+    // Emit event for real-time updates
+    let _ = app.emit("notification:new", &notification);
+
+    Ok(notification)
+}
+
 /// Mark a single notification as read
 #[tauri::command]
 pub async fn mark_notification_read(
@@ -6,7 +6,7 @@ use crate::models::{
     GitInfo, ImportResult, MarkdownScanResult, Project, ProjectDocs,
     ProjectUpdate, ProjectWithStats, RoadmapProgress, TechStack,
 };
-use crate::pty::PtyManagerState;
+use crate::pty::{PtyManagerState, SessionOptions};
 use crate::security::shell_escape_path;
 use rusqlite::params;
 use std::sync::Arc;
@@ -409,7 +409,7 @@ pub async fn import_project_enhanced(
             );
 
             let mut manager = pty_manager.lock().await;
-            manager.create_session(&app, session_id.clone(), &path, Some(&command), 120, 30)?;
+            manager.create_session(&app, session_id.clone(), &path, Some(&command), 120, 30, &SessionOptions::default())?;
 
             (false, Some(session_id))
         }
@@ -434,7 +434,7 @@ pub async fn import_project_enhanced(
             );
 
             let mut manager = pty_manager.lock().await;
-            manager.create_session(&app, session_id.clone(), &path, Some(&command), 120, 30)?;
+            manager.create_session(&app, session_id.clone(), &path, Some(&command), 120, 30, &SessionOptions::default())?;
 
             (false, Some(session_id))
         }
@@ -688,6 +688,7 @@ pub async fn create_new_project(
         Some(&command),
         120,
         30,
+        &SessionOptions::default(),
     )?;
 
     Ok(CreateProjectResult {
@@ -2,8 +2,32 @@
 // Tauri command handlers for PTY operations
 // Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
 
-use crate::pty::{SessionInfo, TerminalManagerState, TmuxSessionInfo};
-use tauri::{AppHandle, State};
+use crate::commands::auto_commands::{project_for_path, run_hook_chain};
+use crate::commands::env_profiles::{masked_values, project_secret_vars, session_env, session_profile_vars};
+use crate::commands::session_state::{compile_patterns, load_config, state_tracker};
+use crate::commands::terminal_profiles::{apply_terminal_profile, session_terminal_profile};
+use crate::pty::masking::mask_text;
+use crate::pty::output_search::{self, OutputMatch};
+use crate::pty::process_tree::{self, ProcessSampler, SessionProcesses};
+use crate::pty::session_state::SessionState;
+use crate::pty::tmux_panes::{self, SplitDirection, TmuxWindow};
+use crate::pty::{
+    is_claude_command, OutputSource, PtyExitEvent, SessionInfo, TerminalManager, TerminalManagerState, TmuxSessionInfo,
+};
+use std::sync::{Arc, Mutex as StdMutex};
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
+
+type DbState = Arc<crate::db::DbPool>;
+
+/// Shared so CPU usage is measured between consecutive samples
+pub type ProcessSamplerState = Arc<StdMutex<ProcessSampler>>;
+
+/// How often session process trees are sampled and emitted
+const PROCESS_SAMPLE_INTERVAL_SECS: u64 = 5;
+
+/// Longest `pty_create` waits on a Claude session's pre hooks; progress
+/// streams through the `auto-commands:*` events meanwhile
+const PRE_HOOK_BUDGET: std::time::Duration = std::time::Duration::from_secs(60);
 
 /// Input for creating a new PTY session
 #[derive(serde::Deserialize)]
@@ -20,6 +44,10 @@ pub struct CreatePtyInput {
     pub rows: u16,
     /// Optional session name hint
     pub session_name: Option<String>,
+    /// Env profile to apply (default: the project's default profile)
+    pub env_profile_id: Option<String>,
+    /// Terminal profile to start with (default: the project's default profile)
+    pub terminal_profile_id: Option<String>,
 }
 
 /// Result from creating a PTY session
@@ -32,25 +60,143 @@ pub struct CreatePtyResult {
     pub tmux_name: Option<String>,
 }
 
-/// Create a new PTY session
+/// Create a new PTY session. Claude sessions inside a project run the
+/// project's `pre` auto-commands first (within `PRE_HOOK_BUDGET`) and its
+/// `post` auto-commands when the process exits.
+/// The terminal profile picks the shell, its args, env and starting directory;
+/// the env profile's variables are set too and its secret values masked in
+/// the output. Claude sessions get state detection (`pty:state:<id>`).
 #[tauri::command]
 pub async fn pty_create(
     app: AppHandle,
     state: State<'_, TerminalManagerState>,
+    db: State<'_, DbState>,
+    input: CreatePtyInput,
+) -> Result<CreatePtyResult, String> {
+    create_pty_session(&app, &state, &db, input).await
+}
+
+/// Shared by `pty_create` and workspace restore
+pub(crate) async fn create_pty_session(
+    app: &AppHandle,
+    state: &TerminalManagerState,
+    db: &DbState,
     input: CreatePtyInput,
 ) -> Result<CreatePtyResult, String> {
     // Generate a unique session ID
     let session_id = uuid::Uuid::new_v4().to_string();
 
-    let mut manager = state.lock().await;
-    let tmux_name = manager.create_session(
-        &app,
-        session_id.clone(),
-        &input.working_directory,
-        input.command.as_deref(),
-        input.cols,
-        input.rows,
-    )?;
+    let (profile_vars, terminal_profile) = {
+        let conn = db.read().await;
+        (
+            session_profile_vars(&conn, &input.working_directory, input.env_profile_id.as_deref())?,
+            session_terminal_profile(&conn, &input.working_directory, input.terminal_profile_id.as_deref())?,
+        )
+    };
+    let mut options = session_env(&profile_vars)?;
+    let mut working_directory = input.working_directory.clone();
+    if let Some((profile, project_path)) = &terminal_profile {
+        if let Some(dir) = apply_terminal_profile(
+            profile,
+            project_path,
+            &input.working_directory,
+            input.terminal_profile_id.is_some(),
+            &mut options,
+        )? {
+            working_directory = dir;
+        }
+    }
+
+    let is_claude = input.command.as_deref().is_some_and(is_claude_command);
+    let (hook_project, patterns) = if is_claude {
+        let conn = db.read().await;
+        (
+            project_for_path(&conn, &input.working_directory),
+            Some(compile_patterns(&load_config(&conn))?),
+        )
+    } else {
+        (None, None)
+    };
+    if let Some(patterns) = patterns {
+        options.state = Some(state_tracker(
+            app,
+            db,
+            &session_id,
+            patterns,
+            hook_project.clone(),
+        ));
+    }
+
+    if let Some((project_id, project_path)) = &hook_project {
+        let pre = run_hook_chain(
+            app,
+            db,
+            project_id,
+            project_path,
+            "pre",
+            Some(&session_id),
+            Some(PRE_HOOK_BUDGET),
+        )
+        .await?;
+        if pre.stopped {
+            let failed = pre.results.last().map(|r| r.label.as_str()).unwrap_or_default();
+            return Err(format!("Pre hook '{}' failed; Claude session not started", failed));
+        }
+    }
+
+    let tmux_name = {
+        let mut manager = state.lock().await;
+        let tmux_name = manager.create_session(
+            app,
+            session_id.clone(),
+            &working_directory,
+            input.command.as_deref(),
+            input.cols,
+            input.rows,
+            &options,
+        )?;
+        // Typed ahead; the shell reads it once its startup files have run
+        let initial_command = terminal_profile
+            .as_ref()
+            .and_then(|(profile, _)| profile.initial_command.as_deref())
+            .filter(|_| input.command.is_none());
+        if let Some(initial) = initial_command {
+            manager.write(&session_id, format!("{}\r", initial).as_bytes())?;
+        }
+        tmux_name
+    };
+
+    if let Some((project_id, project_path)) = hook_project {
+        let hook_app = app.clone();
+        let pool = db.clone();
+        let sid = session_id.clone();
+        let tmux = tmux_name.clone();
+        app.once(format!("pty:exit:{}", session_id), move |event| {
+            // Closing the tab isn't the session finishing
+            if serde_json::from_str::<PtyExitEvent>(event.payload()).is_ok_and(|exit| exit.closed) {
+                return;
+            }
+            tauri::async_runtime::spawn(async move {
+                // The reader can report EOF from a close's kill before the
+                // close's own event; a closed session is already unregistered
+                let manager = hook_app.state::<TerminalManagerState>();
+                if manager.lock().await.get_session_info(&sid).is_none() {
+                    return;
+                }
+                // A tmux session that outlives its attach client was detached, not finished
+                if tmux.as_ref().is_some_and(|name| {
+                    TerminalManager::list_ct_sessions().iter().any(|s| &s.name == name)
+                }) {
+                    return;
+                }
+                if let Err(e) =
+                    run_hook_chain(&hook_app, &pool, &project_id, &project_path, "post", Some(&sid), None).await
+                {
+                    tracing::warn!("[auto_commands] Post hooks failed for session {}: {}", sid, e);
+                }
+            });
+        });
+    }
 
     Ok(CreatePtyResult {
         session_id,
@@ -60,17 +206,42 @@ pub async fn pty_create(
 
 /// Attach to an existing tmux session (reconnect after app restart)
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn pty_attach(
     app: AppHandle,
     state: State<'_, TerminalManagerState>,
+    db: State<'_, DbState>,
     session_id: String,
     tmux_name: String,
     working_dir: String,
     cols: u16,
     rows: u16,
 ) -> Result<bool, String> {
+    attach_pty_session(&app, &state, &db, session_id, &tmux_name, &working_dir, cols, rows).await
+}
+
+/// Shared by `pty_attach` and workspace restore
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn attach_pty_session(
+    app: &AppHandle,
+    state: &TerminalManagerState,
+    db: &DbState,
+    session_id: String,
+    tmux_name: &str,
+    working_dir: &str,
+    cols: u16,
+    rows: u16,
+) -> Result<bool, String> {
+    // The profile the session started with isn't stored, so mask every secret
+    // any of the project's profiles could have injected
+    let secret_vars = {
+        let conn = db.read().await;
+        project_secret_vars(&conn, working_dir)
+    };
+    let masked = masked_values(&secret_vars);
+
     let mut manager = state.lock().await;
-    manager.attach_session(&app, session_id, &tmux_name, &working_dir, cols, rows)
+    manager.attach_session(app, session_id, tmux_name, working_dir, cols, rows, &masked)
 }
 
 /// Check if tmux is available, returning version string or null
@@ -159,6 +330,285 @@ pub async fn pty_get_session_info(
     Ok(manager.get_session_info(&session_id))
 }
 
+/// Get the detected state of a Claude session (None for untracked sessions)
+#[tauri::command]
+pub async fn pty_get_state(
+    state: State<'_, TerminalManagerState>,
+    session_id: String,
+) -> Result<Option<SessionState>, String> {
+    let manager = state.lock().await;
+    Ok(manager.session_state(&session_id))
+}
+
+/// Sample the process trees under sessions' roots off the async runtime
+async fn sample_processes(
+    sampler: ProcessSamplerState,
+    roots: Vec<(String, Vec<u32>)>,
+) -> Result<Vec<SessionProcesses>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        sampler
+            .lock()
+            .map(|mut sampler| sampler.sample(&roots))
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Emit `pty:processes` with every session's process tree, periodically, so
+/// runaway dev servers and zombie test runners show up while they happen
+pub fn spawn_process_monitor(app: AppHandle, sampler: ProcessSamplerState) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(PROCESS_SAMPLE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let Some(terminal_manager) = app.try_state::<TerminalManagerState>() else {
+                continue;
+            };
+            let roots = terminal_manager.lock().await.session_root_pids(None);
+            if roots.is_empty() {
+                continue;
+            }
+            match sample_processes(sampler.clone(), roots).await {
+                Ok(trees) => {
+                    let _ = app.emit("pty:processes", &trees);
+                }
+                Err(e) => tracing::warn!("[pty] Process sampling failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Process tree (pid, command line, CPU %, RSS, listening ports) under each
+/// session, or under one session
+#[tauri::command]
+pub async fn pty_process_trees(
+    state: State<'_, TerminalManagerState>,
+    sampler: State<'_, ProcessSamplerState>,
+    session_id: Option<String>,
+) -> Result<Vec<SessionProcesses>, String> {
+    let roots = {
+        let manager = state.lock().await;
+        manager.session_root_pids(session_id.as_deref())
+    };
+    sample_processes(sampler.inner().clone(), roots).await
+}
+
+/// Terminate a process running under a session without closing the session.
+/// Only descendants of the session's shell can be killed. A zombie goes away
+/// when its parent is killed or reaps it.
+#[tauri::command]
+pub async fn pty_kill_process(
+    state: State<'_, TerminalManagerState>,
+    session_id: String,
+    pid: u32,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let roots = {
+        let manager = state.lock().await;
+        manager.session_root_pids(Some(&session_id))
+    };
+    let Some((_, roots)) = roots.into_iter().next() else {
+        return Err(format!("Session {} not found", session_id));
+    };
+    if roots.contains(&pid) {
+        return Err("That is the session's shell; close the terminal instead".to_string());
+    }
+    if !process_tree::descendants(&roots).contains(&pid) {
+        return Err(format!("Process {} is not running in session {}", pid, session_id));
+    }
+    let force = force.unwrap_or(false);
+    process_tree::signal_process(pid, force)?;
+    tracing::info!(
+        "Sent {} to process {} in session {}",
+        if force { "SIGKILL" } else { "SIGTERM" },
+        pid,
+        session_id
+    );
+    Ok(())
+}
+
+/// Most matches `pty_search` returns
+const MAX_SEARCH_MATCHES: usize = 500;
+
+/// Search the output of live sessions (or one session) with ANSI escapes
+/// stripped: tmux sessions search every pane's scrollback, native sessions
+/// their last 10,000 lines. `query` is literal unless `regex` is set.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn pty_search(
+    state: State<'_, TerminalManagerState>,
+    query: String,
+    session_id: Option<String>,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    context: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Vec<OutputMatch>, String> {
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let pattern = if regex.unwrap_or(false) { query } else { regex::escape(&query) };
+    let re = regex::RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive.unwrap_or(false))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let context = context.unwrap_or(2);
+    let limit = limit.unwrap_or(MAX_SEARCH_MATCHES).min(MAX_SEARCH_MATCHES);
+
+    let sources = {
+        let manager = state.lock().await;
+        manager.output_sources(session_id.as_deref())
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut matches = Vec::new();
+        for (session_id, source) in sources {
+            let outputs: Vec<(Option<String>, usize, Vec<String>)> = match source {
+                OutputSource::Tmux { tmux_name, masked } => tmux_panes::list_windows(&tmux_name)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flat_map(|window| window.panes)
+                    .filter_map(|pane| {
+                        let text = tmux_panes::capture_pane(&tmux_name, &pane.id, None, false).ok()?;
+                        let text = mask_text(&masked, text);
+                        Some((Some(pane.id), 1, text.lines().map(str::to_string).collect()))
+                    })
+                    .collect(),
+                OutputSource::Scrollback(scrollback) => {
+                    let (first_line, lines) = scrollback.snapshot();
+                    vec![(None, first_line, lines)]
+                }
+            };
+            for (pane_id, first_line, lines) in outputs {
+                for (index, before, after) in output_search::search_lines(&lines, &re, context) {
+                    matches.push(OutputMatch {
+                        session_id: session_id.clone(),
+                        pane_id: pane_id.clone(),
+                        line_number: first_line + index,
+                        line: lines[index].clone(),
+                        before,
+                        after,
+                    });
+                    if matches.len() >= limit {
+                        return matches;
+                    }
+                }
+            }
+        }
+        matches
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// tmux session name behind a terminal session
+async fn tmux_name_for(state: &State<'_, TerminalManagerState>, session_id: &str) -> Result<String, String> {
+    let manager = state.lock().await;
+    manager
+        .get_tmux_name(session_id)
+        .ok_or_else(|| format!("Session {} is not tmux-backed", session_id))
+}
+
+/// Windows of a tmux-backed session with their panes and running commands
+#[tauri::command]
+pub async fn pty_tmux_list_windows(
+    state: State<'_, TerminalManagerState>,
+    session_id: String,
+) -> Result<Vec<TmuxWindow>, String> {
+    let tmux_name = tmux_name_for(&state, &session_id).await?;
+    tmux_panes::list_windows(&tmux_name)
+}
+
+/// Split a pane of a tmux-backed session (default: the active pane),
+/// optionally typing a command into the new shell. Returns the new pane ID.
+#[tauri::command]
+pub async fn pty_tmux_split_pane(
+    state: State<'_, TerminalManagerState>,
+    session_id: String,
+    direction: SplitDirection,
+    pane_id: Option<String>,
+    working_directory: Option<String>,
+    command: Option<String>,
+) -> Result<String, String> {
+    let tmux_name = tmux_name_for(&state, &session_id).await?;
+    tmux_panes::split_pane(
+        &tmux_name,
+        pane_id.as_deref(),
+        direction,
+        working_directory.as_deref(),
+        command.as_deref(),
+    )
+}
+
+/// Open a window in a tmux-backed session, optionally typing a command into
+/// its shell. Returns the new window ID.
+#[tauri::command]
+pub async fn pty_tmux_new_window(
+    state: State<'_, TerminalManagerState>,
+    session_id: String,
+    name: Option<String>,
+    working_directory: Option<String>,
+    command: Option<String>,
+) -> Result<String, String> {
+    let tmux_name = tmux_name_for(&state, &session_id).await?;
+    tmux_panes::new_window(&tmux_name, name.as_deref(), working_directory.as_deref(), command.as_deref())
+}
+
+/// Switch a tmux-backed session to a window
+#[tauri::command]
+pub async fn pty_tmux_select_window(
+    state: State<'_, TerminalManagerState>,
+    session_id: String,
+    window_id: String,
+) -> Result<(), String> {
+    let tmux_name = tmux_name_for(&state, &session_id).await?;
+    tmux_panes::select_window(&tmux_name, &window_id)
+}
+
+/// Focus a pane of a tmux-backed session
+#[tauri::command]
+pub async fn pty_tmux_select_pane(
+    state: State<'_, TerminalManagerState>,
+    session_id: String,
+    pane_id: String,
+) -> Result<(), String> {
+    let tmux_name = tmux_name_for(&state, &session_id).await?;
+    tmux_panes::select_pane(&tmux_name, &pane_id)
+}
+
+/// Text of a pane: the last `lines` lines of scrollback, or all of it
+#[tauri::command]
+pub async fn pty_tmux_capture_pane(
+    state: State<'_, TerminalManagerState>,
+    session_id: String,
+    pane_id: String,
+    lines: Option<u32>,
+    escapes: Option<bool>,
+) -> Result<String, String> {
+    let (tmux_name, masked) = {
+        let manager = state.lock().await;
+        let tmux_name = manager
+            .get_tmux_name(&session_id)
+            .ok_or_else(|| format!("Session {} is not tmux-backed", session_id))?;
+        (tmux_name, manager.masked_values(&session_id))
+    };
+    // tmux keeps the unmasked text; mask it the way the live stream is
+    tmux_panes::capture_pane(&tmux_name, &pane_id, lines, escapes.unwrap_or(false))
+        .map(|text| mask_text(&masked, text))
+}
+
+/// Close a pane or window of a tmux-backed session
+#[tauri::command]
+pub async fn pty_tmux_kill(
+    state: State<'_, TerminalManagerState>,
+    session_id: String,
+    target: String,
+) -> Result<(), String> {
+    let tmux_name = tmux_name_for(&state, &session_id).await?;
+    tmux_panes::kill_target(&tmux_name, &target)
+}
+
 /// Get count of active PTY sessions
 #[tauri::command]
 pub async fn pty_active_count(state: State<'_, TerminalManagerState>) -> Result<usize, String> {
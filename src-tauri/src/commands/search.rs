@@ -3,13 +3,17 @@
 
 use crate::models::{
     DecisionSearchResult, GlobalSearchResults, KnowledgeSearchResultItem, PhaseSearchResult,
-    ProjectSearchResult,
+    ProjectSearchResult, SavedSearch, SavedSearchRunResult, SearchFilters, SearchHistoryEntry,
 };
-use rusqlite::params;
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
 use std::sync::Arc;
 
 type DbState = Arc<crate::db::DbPool>;
 
+const MAX_SEARCH_HISTORY: i32 = 50;
+const RESULT_TYPES: &[&str] = &["project", "phase", "decision", "knowledge"];
+
 /// Escape special FTS5 characters in user input to prevent query syntax errors.
 /// FTS5 special chars: " * ( ) : ^
 fn fts5_escape(query: &str) -> String {
@@ -24,62 +28,132 @@ fn fts5_escape(query: &str) -> String {
         .join(" ")
 }
 
-#[tauri::command]
-pub async fn global_search(
-    db: tauri::State<'_, DbState>,
-    query: String,
-    limit: Option<i32>,
+fn validate_filters(filters: &SearchFilters) -> Result<(), String> {
+    if let Some(types) = &filters.result_types {
+        if let Some(bad) = types.iter().find(|t| !RESULT_TYPES.contains(&t.as_str())) {
+            return Err(format!(
+                "Unknown result type '{}'. Expected one of: {}",
+                bad,
+                RESULT_TYPES.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn type_enabled(filters: &SearchFilters, result_type: &str) -> bool {
+    filters
+        .result_types
+        .as_ref()
+        .is_none_or(|types| types.iter().any(|t| t == result_type))
+}
+
+/// Append project and date-range conditions for one result type.
+/// `project_col` / `created_col` are the qualified column names in that query.
+fn push_scope_filters(
+    filters: &SearchFilters,
+    project_col: &str,
+    created_col: &str,
+    conditions: &mut Vec<String>,
+    params_vec: &mut Vec<Box<dyn rusqlite::ToSql>>,
+) {
+    if let Some(project_id) = &filters.project_id {
+        conditions.push(format!("{} = ?", project_col));
+        params_vec.push(Box::new(project_id.clone()));
+    }
+    if let Some(from) = &filters.date_from {
+        conditions.push(format!("date({}) >= date(?)", created_col));
+        params_vec.push(Box::new(from.clone()));
+    }
+    if let Some(to) = &filters.date_to {
+        conditions.push(format!("date({}) <= date(?)", created_col));
+        params_vec.push(Box::new(to.clone()));
+    }
+}
+
+fn query_scoped<T, F>(
+    conn: &Connection,
+    select: &str,
+    conditions: Vec<String>,
+    mut params_vec: Vec<Box<dyn rusqlite::ToSql>>,
+    order_by: &str,
+    limit: i32,
+    map_row: F,
+) -> Result<Vec<T>, String>
+where
+    F: FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<T>,
+{
+    params_vec.push(Box::new(limit));
+    let sql = format!(
+        "{} WHERE {} ORDER BY {} LIMIT ?",
+        select,
+        conditions.join(" AND "),
+        order_by
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let result = stmt
+        .query_map(params_refs.as_slice(), map_row)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(result)
+}
+
+/// Run a global search across projects, phases, decisions and knowledge
+fn run_global_search(
+    conn: &Connection,
+    query: &str,
+    filters: &SearchFilters,
+    limit: i32,
 ) -> Result<GlobalSearchResults, String> {
-    let conn = db.read().await;
-    
-    let limit = limit.unwrap_or(10);
-    let fts_query = fts5_escape(&query);
+    let fts_query = fts5_escape(query);
     let like_pattern = format!("%{}%", query);
 
     // Search projects using FTS5
-    let projects = {
-        let mut stmt = conn
-            .prepare(
-                "SELECT p.id, p.name, p.description, p.status
-                 FROM projects_fts fts
-                 JOIN projects p ON p.rowid = fts.rowid
-                 WHERE projects_fts MATCH ?1
-                 ORDER BY rank
-                 LIMIT ?2",
-            )
-            .map_err(|e| e.to_string())?;
-
-        let result: Vec<ProjectSearchResult> = stmt
-            .query_map(params![fts_query, limit], |row| {
+    let projects = if type_enabled(filters, "project") {
+        let mut conditions = vec!["projects_fts MATCH ?".to_string()];
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(fts_query.clone())];
+        push_scope_filters(filters, "p.id", "p.created_at", &mut conditions, &mut params_vec);
+        query_scoped(
+            conn,
+            "SELECT p.id, p.name, p.description, p.status
+             FROM projects_fts fts
+             JOIN projects p ON p.rowid = fts.rowid",
+            conditions,
+            params_vec,
+            "rank",
+            limit,
+            |row| {
                 Ok(ProjectSearchResult {
                     id: row.get(0)?,
                     name: row.get(1)?,
                     description: row.get(2)?,
                     status: row.get(3)?,
                 })
-            })
-            .map_err(|e| e.to_string())?
-            .filter_map(|r| r.ok())
-            .collect();
-        result
+            },
+        )?
+    } else {
+        Vec::new()
     };
 
     // Search phases (no FTS table — uses LIKE with indexed join columns)
-    let phases = {
-        let mut stmt = conn
-            .prepare(
-                "SELECT ph.id, ph.name, ph.goal, ph.status, p.id, p.name
-                 FROM phases ph
-                 JOIN roadmaps fp ON ph.roadmap_id = fp.id
-                 JOIN projects p ON fp.project_id = p.id
-                 WHERE ph.name LIKE ?1 OR ph.goal LIKE ?1
-                 ORDER BY ph.phase_number ASC
-                 LIMIT ?2",
-            )
-            .map_err(|e| e.to_string())?;
-
-        let result: Vec<PhaseSearchResult> = stmt
-            .query_map(params![like_pattern, limit], |row| {
+    let phases = if type_enabled(filters, "phase") {
+        let mut conditions = vec!["(ph.name LIKE ? OR ph.goal LIKE ?)".to_string()];
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> =
+            vec![Box::new(like_pattern.clone()), Box::new(like_pattern)];
+        push_scope_filters(filters, "p.id", "ph.created_at", &mut conditions, &mut params_vec);
+        query_scoped(
+            conn,
+            "SELECT ph.id, ph.name, ph.goal, ph.status, p.id, p.name
+             FROM phases ph
+             JOIN roadmaps fp ON ph.roadmap_id = fp.id
+             JOIN projects p ON fp.project_id = p.id",
+            conditions,
+            params_vec,
+            "ph.phase_number ASC",
+            limit,
+            |row| {
                 Ok(PhaseSearchResult {
                     id: row.get(0)?,
                     name: row.get(1)?,
@@ -88,29 +162,28 @@ pub async fn global_search(
                     project_id: row.get(4)?,
                     project_name: row.get(5)?,
                 })
-            })
-            .map_err(|e| e.to_string())?
-            .filter_map(|r| r.ok())
-            .collect();
-        result
+            },
+        )?
+    } else {
+        Vec::new()
     };
 
     // Search decisions using FTS5
-    let decisions = {
-        let mut stmt = conn
-            .prepare(
-                "SELECT d.id, d.question, d.answer, d.category, p.id, p.name
-                 FROM decisions_fts fts
-                 JOIN decisions d ON d.rowid = fts.rowid
-                 JOIN projects p ON d.project_id = p.id
-                 WHERE decisions_fts MATCH ?1
-                 ORDER BY rank
-                 LIMIT ?2",
-            )
-            .map_err(|e| e.to_string())?;
-
-        let result: Vec<DecisionSearchResult> = stmt
-            .query_map(params![fts_query, limit], |row| {
+    let decisions = if type_enabled(filters, "decision") {
+        let mut conditions = vec!["decisions_fts MATCH ?".to_string()];
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(fts_query.clone())];
+        push_scope_filters(filters, "p.id", "d.created_at", &mut conditions, &mut params_vec);
+        query_scoped(
+            conn,
+            "SELECT d.id, d.question, d.answer, d.category, p.id, p.name
+             FROM decisions_fts fts
+             JOIN decisions d ON d.rowid = fts.rowid
+             JOIN projects p ON d.project_id = p.id",
+            conditions,
+            params_vec,
+            "rank",
+            limit,
+            |row| {
                 Ok(DecisionSearchResult {
                     id: row.get(0)?,
                     question: row.get(1)?,
@@ -119,29 +192,31 @@ pub async fn global_search(
                     project_id: row.get(4)?,
                     project_name: row.get(5)?,
                 })
-            })
-            .map_err(|e| e.to_string())?
-            .filter_map(|r| r.ok())
-            .collect();
-        result
+            },
+        )?
+    } else {
+        Vec::new()
     };
 
     // Search knowledge using FTS5
-    let knowledge = {
-        let mut stmt = conn
-            .prepare(
-                "SELECT k.id, k.title, k.category, p.id, p.name
-                 FROM knowledge_fts fts
-                 JOIN knowledge k ON k.rowid = fts.rowid
-                 JOIN projects p ON k.project_id = p.id
-                 WHERE knowledge_fts MATCH ?1
-                 ORDER BY rank
-                 LIMIT ?2",
-            )
-            .map_err(|e| e.to_string())?;
-
-        let result: Vec<KnowledgeSearchResultItem> = stmt
-            .query_map(params![fts_query, limit], |row| {
+    let knowledge = if type_enabled(filters, "knowledge") {
+        let mut conditions = vec![
+            "knowledge_fts MATCH ?".to_string(),
+            "k.deleted_at IS NULL".to_string(),
+        ];
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(fts_query)];
+        push_scope_filters(filters, "p.id", "k.created_at", &mut conditions, &mut params_vec);
+        query_scoped(
+            conn,
+            "SELECT k.id, k.title, k.category, p.id, p.name
+             FROM knowledge_fts fts
+             JOIN knowledge k ON k.rowid = fts.rowid
+             JOIN projects p ON k.project_id = p.id",
+            conditions,
+            params_vec,
+            "rank",
+            limit,
+            |row| {
                 Ok(KnowledgeSearchResultItem {
                     id: row.get(0)?,
                     title: row.get(1)?,
@@ -149,11 +224,10 @@ pub async fn global_search(
                     project_id: row.get(3)?,
                     project_name: row.get(4)?,
                 })
-            })
-            .map_err(|e| e.to_string())?
-            .filter_map(|r| r.ok())
-            .collect();
-        result
+            },
+        )?
+    } else {
+        Vec::new()
     };
 
     Ok(GlobalSearchResults {
@@ -163,3 +237,306 @@ pub async fn global_search(
         knowledge,
     })
 }
+
+/// Stable "<type>:<id>" keys for every result, used to spot new results between runs
+fn result_keys(results: &GlobalSearchResults) -> Vec<String> {
+    results
+        .projects
+        .iter()
+        .map(|r| format!("project:{}", r.id))
+        .chain(results.phases.iter().map(|r| format!("phase:{}", r.id)))
+        .chain(results.decisions.iter().map(|r| format!("decision:{}", r.id)))
+        .chain(results.knowledge.iter().map(|r| format!("knowledge:{}", r.id)))
+        .collect()
+}
+
+/// Upsert a history row for this query + filters and prune old entries
+fn record_search_history(
+    conn: &Connection,
+    query: &str,
+    filters: &SearchFilters,
+    result_count: i32,
+) -> Result<(), String> {
+    let filters_json = serde_json::to_string(filters).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO search_history (id, query, filters, result_count)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(query, filters) DO UPDATE SET
+             result_count = excluded.result_count,
+             search_count = search_count + 1,
+             last_searched_at = datetime('now')",
+        params![
+            format!("{:032x}", rand::random::<u128>()),
+            query,
+            filters_json,
+            result_count
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Auto-prune: keep only the most recent MAX_SEARCH_HISTORY entries
+    conn.execute(
+        "DELETE FROM search_history
+         WHERE id NOT IN (
+             SELECT id FROM search_history
+             ORDER BY last_searched_at DESC
+             LIMIT ?1
+         )",
+        params![MAX_SEARCH_HISTORY],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn row_to_saved_search(row: &rusqlite::Row) -> rusqlite::Result<SavedSearch> {
+    let filters_json: String = row.get(3)?;
+    Ok(SavedSearch {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        query: row.get(2)?,
+        filters: serde_json::from_str(&filters_json).unwrap_or_default(),
+        last_run_at: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn fetch_saved_search(conn: &Connection, id: &str) -> Result<SavedSearch, String> {
+    conn.query_row(
+        "SELECT id, name, query, filters, last_run_at, created_at, updated_at
+         FROM saved_searches WHERE id = ?1",
+        params![id],
+        row_to_saved_search,
+    )
+    .map_err(|e| format!("Saved search not found: {}", e))
+}
+
+#[tauri::command]
+pub async fn global_search(
+    db: tauri::State<'_, DbState>,
+    query: String,
+    limit: Option<i32>,
+    filters: Option<SearchFilters>,
+    record_history: Option<bool>,
+) -> Result<GlobalSearchResults, String> {
+    let limit = limit.unwrap_or(10);
+    let filters = filters.unwrap_or_default();
+    validate_filters(&filters)?;
+
+    let results = {
+        let conn = db.read().await;
+        run_global_search(&conn, &query, &filters, limit)?
+    };
+
+    if record_history.unwrap_or(false) && !query.trim().is_empty() {
+        let db = db.write().await;
+        let result_count = result_keys(&results).len() as i32;
+        record_search_history(db.conn(), query.trim(), &filters, result_count)?;
+    }
+
+    Ok(results)
+}
+
+/// List recent searches, most recent first
+#[tauri::command]
+pub async fn list_search_history(
+    db: tauri::State<'_, DbState>,
+    limit: Option<i32>,
+) -> Result<Vec<SearchHistoryEntry>, String> {
+    let conn = db.read().await;
+    let limit = limit.unwrap_or(20);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, query, filters, result_count, search_count, last_searched_at
+             FROM search_history
+             ORDER BY last_searched_at DESC
+             LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let entries = stmt
+        .query_map(params![limit], |row| {
+            let filters_json: String = row.get(2)?;
+            Ok(SearchHistoryEntry {
+                id: row.get(0)?,
+                query: row.get(1)?,
+                filters: serde_json::from_str(&filters_json).unwrap_or_default(),
+                result_count: row.get(3)?,
+                search_count: row.get(4)?,
+                last_searched_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(entries)
+}
+
+/// Clear all recent-search history
+#[tauri::command]
+pub async fn clear_search_history(db: tauri::State<'_, DbState>) -> Result<i32, String> {
+    let db = db.write().await;
+    let deleted = db
+        .conn()
+        .execute("DELETE FROM search_history", [])
+        .map_err(|e| e.to_string())?;
+    Ok(deleted as i32)
+}
+
+/// List saved searches, alphabetically by name
+#[tauri::command]
+pub async fn list_saved_searches(
+    db: tauri::State<'_, DbState>,
+) -> Result<Vec<SavedSearch>, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, query, filters, last_run_at, created_at, updated_at
+             FROM saved_searches
+             ORDER BY name COLLATE NOCASE ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let searches = stmt
+        .query_map([], row_to_saved_search)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(searches)
+}
+
+/// Save a query with its scope filters
+#[tauri::command]
+pub async fn create_saved_search(
+    db: tauri::State<'_, DbState>,
+    name: String,
+    query: String,
+    filters: Option<SearchFilters>,
+) -> Result<SavedSearch, String> {
+    if name.trim().is_empty() || query.trim().is_empty() {
+        return Err("Saved search name and query are required".to_string());
+    }
+    let filters = filters.unwrap_or_default();
+    validate_filters(&filters)?;
+    let filters_json = serde_json::to_string(&filters).map_err(|e| e.to_string())?;
+
+    let db = db.write().await;
+    let conn = db.conn();
+    let id = format!("{:032x}", rand::random::<u128>());
+
+    conn.execute(
+        "INSERT INTO saved_searches (id, name, query, filters) VALUES (?1, ?2, ?3, ?4)",
+        params![id, name.trim(), query.trim(), filters_json],
+    )
+    .map_err(|e| e.to_string())?;
+
+    fetch_saved_search(conn, &id)
+}
+
+/// Update a saved search. Changing the query or filters resets its "new since
+/// last run" baseline.
+#[tauri::command]
+pub async fn update_saved_search(
+    db: tauri::State<'_, DbState>,
+    id: String,
+    name: Option<String>,
+    query: Option<String>,
+    filters: Option<SearchFilters>,
+) -> Result<SavedSearch, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+    let existing = fetch_saved_search(conn, &id)?;
+
+    let name = name.map(|n| n.trim().to_string()).unwrap_or(existing.name);
+    let new_query = query.map(|q| q.trim().to_string()).unwrap_or(existing.query.clone());
+    let new_filters = filters.unwrap_or(existing.filters.clone());
+    if name.is_empty() || new_query.is_empty() {
+        return Err("Saved search name and query are required".to_string());
+    }
+    validate_filters(&new_filters)?;
+    let scope_changed = new_query != existing.query || new_filters != existing.filters;
+    let filters_json = serde_json::to_string(&new_filters).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE saved_searches
+         SET name = ?1, query = ?2, filters = ?3, updated_at = datetime('now'),
+             last_run_at = CASE WHEN ?4 THEN NULL ELSE last_run_at END,
+             last_result_keys = CASE WHEN ?4 THEN NULL ELSE last_result_keys END
+         WHERE id = ?5",
+        params![name, new_query, filters_json, scope_changed, id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    fetch_saved_search(conn, &id)
+}
+
+/// Delete a saved search
+#[tauri::command]
+pub async fn delete_saved_search(db: tauri::State<'_, DbState>, id: String) -> Result<(), String> {
+    let db = db.write().await;
+    let deleted = db
+        .conn()
+        .execute("DELETE FROM saved_searches WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    if deleted == 0 {
+        return Err("Saved search not found".to_string());
+    }
+    Ok(())
+}
+
+/// Re-run a saved search and report which results were not returned last time.
+/// On the first run every result counts as new.
+#[tauri::command]
+pub async fn run_saved_search(
+    db: tauri::State<'_, DbState>,
+    id: String,
+    limit: Option<i32>,
+) -> Result<SavedSearchRunResult, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+    let saved = fetch_saved_search(conn, &id)?;
+    let limit = limit.unwrap_or(10);
+
+    let previous_keys: HashSet<String> = conn
+        .query_row(
+            "SELECT last_result_keys FROM saved_searches WHERE id = ?1",
+            params![id],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .map_err(|e| e.to_string())?
+        .and_then(|json| serde_json::from_str::<Vec<String>>(&json).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
+    let results = run_global_search(conn, &saved.query, &saved.filters, limit)?;
+    let keys = result_keys(&results);
+    let new_result_keys: Vec<String> = keys
+        .iter()
+        .filter(|k| !previous_keys.contains(*k))
+        .cloned()
+        .collect();
+
+    let keys_json = serde_json::to_string(&keys).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE saved_searches SET last_run_at = datetime('now'), last_result_keys = ?1 WHERE id = ?2",
+        params![keys_json, id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let saved_search = fetch_saved_search(conn, &id)?;
+    let new_count = new_result_keys.len() as i32;
+
+    Ok(SavedSearchRunResult {
+        saved_search,
+        results,
+        new_result_keys,
+        new_count,
+    })
+}
@@ -6,7 +6,7 @@ use keyring::Entry;
 use serde::{Deserialize, Serialize};
 
 /// Default service name for all Track Your Shit keychain entries
-const DEFAULT_SERVICE: &str = "net.fluxlabs.track-your-shit";
+pub(crate) const DEFAULT_SERVICE: &str = "net.fluxlabs.track-your-shit";
 
 /// Known/predefined secret key names that the UI offers as presets
 const PREDEFINED_KEYS: &[&str] = &[
@@ -119,7 +119,12 @@ pub async fn get_secret(service: String, key: String) -> Result<Option<String>,
         return Err("Secret key cannot be empty".to_string());
     }
 
-    let entry = Entry::new(&svc, &key)
+    read_secret(&svc, &key)
+}
+
+/// Read a secret value, `None` if the key doesn't exist
+pub(crate) fn read_secret(service: &str, key: &str) -> Result<Option<String>, String> {
+    let entry = Entry::new(service, key)
         .map_err(|e| format!("Failed to create keychain entry: {}", e))?;
 
     match entry.get_password() {
@@ -0,0 +1,188 @@
+// Track Your Shit - Session State Commands
+// Configurable state detection for Claude PTY sessions and its notifications
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use crate::commands::notifications::insert_notification;
+use crate::models::{CreateNotificationInput, SessionStateConfig};
+use crate::pty::session_state::{
+    SessionState, SessionStateChange, SessionStateTracker, StatePatterns, StateSink,
+};
+use regex::Regex;
+use rusqlite::{params, Connection};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+type DbState = Arc<crate::db::DbPool>;
+
+const CONFIG_KEY: &str = "session_state_config";
+
+fn read_setting(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// The saved config, or the defaults
+pub(crate) fn load_config(conn: &Connection) -> SessionStateConfig {
+    read_setting(conn, CONFIG_KEY)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn compile(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| Regex::new(p).map_err(|e| format!("Invalid pattern '{}': {}", p, e)))
+        .collect()
+}
+
+pub(crate) fn compile_patterns(config: &SessionStateConfig) -> Result<StatePatterns, String> {
+    if config.idle_timeout_secs == 0 {
+        return Err("Idle timeout must be at least 1 second".to_string());
+    }
+    Ok(StatePatterns {
+        awaiting_input: compile(&config.awaiting_input_patterns)?,
+        error: compile(&config.error_patterns)?,
+        finished: compile(&config.finished_patterns)?,
+        input_settle: Duration::from_millis(config.input_settle_ms),
+        idle_timeout: Duration::from_secs(config.idle_timeout_secs),
+    })
+}
+
+/// Notification for a state change, if it's one worth interrupting the user for
+fn notification_for(
+    conn: &Connection,
+    change: &SessionStateChange,
+    project: Option<&(String, String)>,
+) -> Option<CreateNotificationInput> {
+    let enabled = |key: &str| read_setting(conn, key).is_none_or(|v| v == "true");
+    if !enabled("notifications_enabled") {
+        return None;
+    }
+    let (notification_type, title, setting) = match change.state {
+        SessionState::AwaitingInput => ("info", "Claude is waiting for input", "notify_on_complete"),
+        SessionState::Finished => ("complete", "Claude session finished", "notify_on_complete"),
+        SessionState::Errored => ("failed", "Claude session hit an error", "notify_on_error"),
+        SessionState::Running | SessionState::Idle => return None,
+    };
+    if !enabled(setting) {
+        return None;
+    }
+
+    let project_name = project.and_then(|(id, _)| {
+        conn.query_row("SELECT name FROM projects WHERE id = ?1", params![id], |row| {
+            row.get::<_, String>(0)
+        })
+        .ok()
+    });
+    let mut message = match &project_name {
+        Some(name) => format!("Session in {}", name),
+        None => "Terminal session".to_string(),
+    };
+    if let Some(detail) = &change.detail {
+        message.push_str(&format!(": {}", detail));
+    }
+
+    Some(CreateNotificationInput {
+        project_id: project.map(|(id, _)| id.clone()),
+        notification_type: notification_type.to_string(),
+        title: title.to_string(),
+        message,
+        link: project.map(|(id, _)| format!("/terminal/{}?session={}", id, change.session_id)),
+    })
+}
+
+/// State tracker for a Claude session. Changes are emitted as
+/// `pty:state:<id>`; awaiting input, finished and errored also create a
+/// notification linking back to the session.
+pub(crate) fn state_tracker(
+    app: &AppHandle,
+    db: &DbState,
+    session_id: &str,
+    patterns: StatePatterns,
+    project: Option<(String, String)>,
+) -> Arc<SessionStateTracker> {
+    let app = app.clone();
+    let db = db.clone();
+    let sink: StateSink = Arc::new(move |change: &SessionStateChange| {
+        let _ = app.emit(&format!("pty:state:{}", change.session_id), change.clone());
+        if matches!(change.state, SessionState::Running | SessionState::Idle) {
+            return;
+        }
+        let app = app.clone();
+        let db = db.clone();
+        let change = change.clone();
+        let project = project.clone();
+        tauri::async_runtime::spawn(async move {
+            let db = db.write().await;
+            let conn = db.conn();
+            let Some(input) = notification_for(conn, &change, project.as_ref()) else {
+                return;
+            };
+            match insert_notification(conn, &input) {
+                Ok(notification) => {
+                    let _ = app.emit("notification:new", &notification);
+                }
+                Err(e) => tracing::warn!(
+                    "[session_state] Failed to notify for session {}: {}",
+                    change.session_id,
+                    e
+                ),
+            }
+        });
+    });
+    SessionStateTracker::new(session_id, patterns, sink)
+}
+
+/// Get the session state detection config
+#[tauri::command]
+pub async fn get_session_state_config(
+    db: tauri::State<'_, DbState>,
+) -> Result<SessionStateConfig, String> {
+    let conn = db.read().await;
+    Ok(load_config(&conn))
+}
+
+/// Save the session state detection config. Applies to sessions started afterwards.
+#[tauri::command]
+pub async fn update_session_state_config(
+    db: tauri::State<'_, DbState>,
+    config: SessionStateConfig,
+) -> Result<SessionStateConfig, String> {
+    compile_patterns(&config)?;
+    let json = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+
+    let db = db.write().await;
+    db.conn()
+        .execute(
+            "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, datetime('now'))
+             ON CONFLICT(key) DO UPDATE SET value = ?2, updated_at = datetime('now')",
+            params![CONFIG_KEY, json],
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_compiles() {
+        let patterns = compile_patterns(&SessionStateConfig::default()).unwrap();
+        assert!(patterns.awaiting_input[1].is_match("  ❯ 1. Yes\n    2. No"));
+        assert!(patterns.error[0].is_match("API Error: 529 {\"type\":\"overloaded_error\"}"));
+
+        let bad = SessionStateConfig {
+            error_patterns: vec!["(unclosed".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(compile_patterns(&bad), Err(e) if e.contains("(unclosed")));
+    }
+}
@@ -60,6 +60,12 @@ pub async fn get_settings(db: tauri::State<'_, DbState>) -> Result<Settings, Str
                 "debug_logging" => settings.debug_logging = value == "true",
                 // Terminal persistence
                 "use_tmux" => settings.use_tmux = value == "true",
+                "shell_integration" => settings.shell_integration = value == "true",
+                // GSD sync
+                "gsd_extract_knowledge" => settings.gsd_extract_knowledge = value == "true",
+                // Job queue
+                "job_max_concurrent" => settings.job_max_concurrent = value.parse().unwrap_or(2),
+                "job_max_per_project" => settings.job_max_per_project = value.parse().unwrap_or(1),
                 _ => {}
             }
         }
@@ -78,6 +84,7 @@ pub async fn update_settings(
     {
         let mut tm = terminal_manager.lock().await;
         tm.set_use_tmux(settings.use_tmux);
+        tm.set_shell_integration(settings.shell_integration);
     }
 
     let db = db.write().await;
@@ -153,6 +160,18 @@ pub async fn update_settings(
 
     // Terminal persistence
     upsert("use_tmux", &settings.use_tmux.to_string()).map_err(|e| e.to_string())?;
+    upsert("shell_integration", &settings.shell_integration.to_string()).map_err(|e| e.to_string())?;
+
+    // GSD sync
+    upsert(
+        "gsd_extract_knowledge",
+        &settings.gsd_extract_knowledge.to_string(),
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Job queue
+    upsert("job_max_concurrent", &settings.job_max_concurrent.to_string()).map_err(|e| e.to_string())?;
+    upsert("job_max_per_project", &settings.job_max_per_project.to_string()).map_err(|e| e.to_string())?;
 
     Ok(settings)
 }
@@ -283,6 +302,10 @@ pub async fn import_settings(
     upsert("notify_on_cost_warning", &settings.notify_on_cost_warning.to_string()).map_err(|e| e.to_string())?;
     upsert("debug_logging", &settings.debug_logging.to_string()).map_err(|e| e.to_string())?;
     upsert("use_tmux", &settings.use_tmux.to_string()).map_err(|e| e.to_string())?;
+    upsert("shell_integration", &settings.shell_integration.to_string()).map_err(|e| e.to_string())?;
+    upsert("gsd_extract_knowledge", &settings.gsd_extract_knowledge.to_string()).map_err(|e| e.to_string())?;
+    upsert("job_max_concurrent", &settings.job_max_concurrent.to_string()).map_err(|e| e.to_string())?;
+    upsert("job_max_per_project", &settings.job_max_per_project.to_string()).map_err(|e| e.to_string())?;
 
     tracing::info!("Settings imported from {:?}", file_path);
     Ok(settings)
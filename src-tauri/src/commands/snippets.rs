@@ -159,6 +159,26 @@ pub async fn delete_snippet(db: tauri::State<'_, DbState>, id: String) -> Result
 // Auto-commands
 // ============================================================
 
+pub(crate) const AUTO_COMMAND_COLUMNS: &str = "id, project_id, label, command, hook_type, enabled, order_index, preset,
+     COALESCE(timeout_secs, 300), COALESCE(stop_on_failure, 0), created_at, updated_at";
+
+pub(crate) fn row_to_auto_command(row: &rusqlite::Row) -> rusqlite::Result<AutoCommand> {
+    Ok(AutoCommand {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        label: row.get(2)?,
+        command: row.get(3)?,
+        hook_type: row.get(4)?,
+        enabled: row.get(5)?,
+        order_index: row.get(6)?,
+        preset: row.get(7)?,
+        timeout_secs: row.get(8)?,
+        stop_on_failure: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
 /// List auto-commands for a project, ordered by hook_type then order_index
 #[tauri::command]
 pub async fn list_auto_commands(
@@ -169,29 +189,16 @@ pub async fn list_auto_commands(
     let conn = db.conn();
 
     let mut stmt = conn
-        .prepare(
-            "SELECT id, project_id, label, command, hook_type, enabled, order_index, preset, created_at, updated_at
-             FROM auto_commands
+        .prepare(&format!(
+            "SELECT {} FROM auto_commands
              WHERE project_id = ?1
              ORDER BY hook_type ASC, order_index ASC",
-        )
+            AUTO_COMMAND_COLUMNS
+        ))
         .map_err(|e| e.to_string())?;
 
     let commands = stmt
-        .query_map(params![project_id], |row| {
-            Ok(AutoCommand {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                label: row.get(2)?,
-                command: row.get(3)?,
-                hook_type: row.get(4)?,
-                enabled: row.get(5)?,
-                order_index: row.get(6)?,
-                preset: row.get(7)?,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            })
-        })
+        .query_map(params![project_id], row_to_auto_command)
         .map_err(|e| e.to_string())?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
@@ -223,8 +230,9 @@ pub async fn create_auto_command(
         .map_err(|e| e.to_string())?;
 
     conn.execute(
-        "INSERT INTO auto_commands (id, project_id, label, command, hook_type, order_index, preset)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO auto_commands (id, project_id, label, command, hook_type, order_index, preset,
+             timeout_secs, stop_on_failure)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
         params![
             cmd_id,
             project_id,
@@ -232,30 +240,18 @@ pub async fn create_auto_command(
             input.command,
             hook_type,
             next_order,
-            input.preset
+            input.preset,
+            input.timeout_secs.unwrap_or(300).max(1),
+            input.stop_on_failure.unwrap_or(false) as i32,
         ],
     )
     .map_err(|e| e.to_string())?;
 
     let auto_cmd = conn
         .query_row(
-            "SELECT id, project_id, label, command, hook_type, enabled, order_index, preset, created_at, updated_at
-             FROM auto_commands WHERE id = ?1",
+            &format!("SELECT {} FROM auto_commands WHERE id = ?1", AUTO_COMMAND_COLUMNS),
             params![cmd_id],
-            |row| {
-                Ok(AutoCommand {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    label: row.get(2)?,
-                    command: row.get(3)?,
-                    hook_type: row.get(4)?,
-                    enabled: row.get(5)?,
-                    order_index: row.get(6)?,
-                    preset: row.get(7)?,
-                    created_at: row.get(8)?,
-                    updated_at: row.get(9)?,
-                })
-            },
+            row_to_auto_command,
         )
         .map_err(|e| e.to_string())?;
 
@@ -275,31 +271,27 @@ pub async fn update_auto_command(
     let hook_type = input.hook_type.unwrap_or_else(|| "pre".to_string());
 
     conn.execute(
-        "UPDATE auto_commands SET label = ?1, command = ?2, hook_type = ?3, updated_at = datetime('now')
+        "UPDATE auto_commands SET label = ?1, command = ?2, hook_type = ?3,
+             timeout_secs = COALESCE(?5, timeout_secs),
+             stop_on_failure = COALESCE(?6, stop_on_failure),
+             updated_at = datetime('now')
          WHERE id = ?4",
-        params![input.label, input.command, hook_type, id],
+        params![
+            input.label,
+            input.command,
+            hook_type,
+            id,
+            input.timeout_secs.map(|t| t.max(1)),
+            input.stop_on_failure.map(|s| s as i32)
+        ],
     )
     .map_err(|e| e.to_string())?;
 
     let auto_cmd = conn
         .query_row(
-            "SELECT id, project_id, label, command, hook_type, enabled, order_index, preset, created_at, updated_at
-             FROM auto_commands WHERE id = ?1",
+            &format!("SELECT {} FROM auto_commands WHERE id = ?1", AUTO_COMMAND_COLUMNS),
             params![id],
-            |row| {
-                Ok(AutoCommand {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    label: row.get(2)?,
-                    command: row.get(3)?,
-                    hook_type: row.get(4)?,
-                    enabled: row.get(5)?,
-                    order_index: row.get(6)?,
-                    preset: row.get(7)?,
-                    created_at: row.get(8)?,
-                    updated_at: row.get(9)?,
-                })
-            },
+            row_to_auto_command,
         )
         .map_err(|e| e.to_string())?;
 
@@ -340,23 +332,9 @@ pub async fn toggle_auto_command(
 
     let auto_cmd = conn
         .query_row(
-            "SELECT id, project_id, label, command, hook_type, enabled, order_index, preset, created_at, updated_at
-             FROM auto_commands WHERE id = ?1",
+            &format!("SELECT {} FROM auto_commands WHERE id = ?1", AUTO_COMMAND_COLUMNS),
             params![id],
-            |row| {
-                Ok(AutoCommand {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    label: row.get(2)?,
-                    command: row.get(3)?,
-                    hook_type: row.get(4)?,
-                    enabled: row.get(5)?,
-                    order_index: row.get(6)?,
-                    preset: row.get(7)?,
-                    created_at: row.get(8)?,
-                    updated_at: row.get(9)?,
-                })
-            },
+            row_to_auto_command,
         )
         .map_err(|e| e.to_string())?;
 
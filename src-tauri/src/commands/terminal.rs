@@ -2,10 +2,14 @@
 // Command history and script favorites management
 // Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
 
+use crate::commands::auto_commands::project_for_path;
+use crate::commands::pty::{attach_pty_session, create_pty_session, CreatePtyInput};
 use crate::models::{
-    CommandHistoryEntry, SaveTerminalSessionInput, ScriptFavorite, TerminalSession,
+    CommandHistoryEntry, RestoredTab, SaveTerminalSessionInput, ScriptFavorite, TerminalSession,
 };
-use rusqlite::params;
+use crate::pty::shell_integration::FinishedCommand;
+use crate::pty::{CommandSink, TerminalManager, TerminalManagerState};
+use rusqlite::{params, Connection};
 use std::sync::Arc;
 
 type DbState = Arc<crate::db::DbPool>;
@@ -13,6 +17,105 @@ type DbState = Arc<crate::db::DbPool>;
 const MAX_HISTORY: i32 = 50;
 const MAX_FAVORITES: usize = 5;
 
+const HISTORY_COLUMNS: &str =
+    "id, project_id, command, source, exit_code, duration_ms, cwd, session_id, started_at, created_at";
+
+fn row_to_history_entry(row: &rusqlite::Row) -> rusqlite::Result<CommandHistoryEntry> {
+    Ok(CommandHistoryEntry {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        command: row.get(2)?,
+        source: row.get(3)?,
+        exit_code: row.get(4)?,
+        duration_ms: row.get(5)?,
+        cwd: row.get(6)?,
+        session_id: row.get(7)?,
+        started_at: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+/// Insert a history entry, auto-pruning to MAX_HISTORY entries. `shell` is
+/// the session ID and details of a command recorded by shell integration.
+fn insert_history_entry(
+    conn: &Connection,
+    project_id: &str,
+    command: &str,
+    source: &str,
+    shell: Option<(&str, &FinishedCommand)>,
+) -> Result<CommandHistoryEntry, String> {
+    let entry_id = format!("{:032x}", rand::random::<u128>());
+    let finished = shell.map(|(_, finished)| finished);
+
+    conn.execute(
+        "INSERT INTO command_history
+            (id, project_id, command, source, exit_code, duration_ms, cwd, session_id, started_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            entry_id,
+            project_id,
+            command,
+            source,
+            finished.and_then(|f| f.exit_code),
+            finished.map(|f| f.duration_ms),
+            finished.and_then(|f| f.cwd.as_deref()),
+            shell.map(|(session_id, _)| session_id),
+            finished.map(|f| f.started_at.as_str()),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Auto-prune: keep only the most recent MAX_HISTORY entries
+    conn.execute(
+        "DELETE FROM command_history
+         WHERE project_id = ?1
+         AND id NOT IN (
+             SELECT id FROM command_history
+             WHERE project_id = ?1
+             ORDER BY created_at DESC
+             LIMIT ?2
+         )",
+        params![project_id, MAX_HISTORY],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        &format!("SELECT {} FROM command_history WHERE id = ?1", HISTORY_COLUMNS),
+        params![entry_id],
+        row_to_history_entry,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Command sink for shell-integrated PTY sessions: records each finished
+/// command against the project containing its cwd (source `shell`)
+pub(crate) fn command_recorder(db: DbState) -> CommandSink {
+    Arc::new(move |session_id: &str, command: FinishedCommand| {
+        let db = db.clone();
+        let session_id = session_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            let db = db.write().await;
+            let conn = db.conn();
+            let Some((project_id, _)) = command
+                .cwd
+                .as_deref()
+                .and_then(|cwd| project_for_path(conn, cwd))
+            else {
+                return;
+            };
+            if let Err(e) = insert_history_entry(
+                conn,
+                &project_id,
+                &command.command,
+                "shell",
+                Some((&session_id, &command)),
+            ) {
+                tracing::warn!("[terminal] Failed to record shell command: {}", e);
+            }
+        });
+    })
+}
+
 /// Get command history for a project
 #[tauri::command]
 pub async fn get_command_history(
@@ -25,25 +128,18 @@ pub async fn get_command_history(
     let limit = limit.unwrap_or(MAX_HISTORY);
 
     let mut stmt = conn
-        .prepare(
-            "SELECT id, project_id, command, source, created_at
+        .prepare(&format!(
+            "SELECT {}
              FROM command_history
              WHERE project_id = ?1
              ORDER BY created_at DESC
              LIMIT ?2",
-        )
+            HISTORY_COLUMNS
+        ))
         .map_err(|e| e.to_string())?;
 
     let entries = stmt
-        .query_map(params![project_id, limit], |row| {
-            Ok(CommandHistoryEntry {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                command: row.get(2)?,
-                source: row.get(3)?,
-                created_at: row.get(4)?,
-            })
-        })
+        .query_map(params![project_id, limit], row_to_history_entry)
         .map_err(|e| e.to_string())?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
@@ -51,7 +147,9 @@ pub async fn get_command_history(
     Ok(entries)
 }
 
-/// Add a command to history, auto-pruning to MAX_HISTORY entries
+/// Add a command to history, auto-pruning to MAX_HISTORY entries. Native
+/// sessions with shell integration record their commands themselves; this is
+/// for tmux sessions and shells without it.
 #[tauri::command]
 pub async fn add_command_history(
     db: tauri::State<'_, DbState>,
@@ -60,50 +158,9 @@ pub async fn add_command_history(
     source: Option<String>,
 ) -> Result<CommandHistoryEntry, String> {
     let db = db.write().await;
-    let conn = db.conn();
     let source = source.unwrap_or_else(|| "manual".to_string());
 
-    let entry_id = format!("{:032x}", rand::random::<u128>());
-
-    conn.execute(
-        "INSERT INTO command_history (id, project_id, command, source)
-         VALUES (?1, ?2, ?3, ?4)",
-        params![entry_id, project_id, command, source],
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Auto-prune: keep only the most recent MAX_HISTORY entries
-    conn.execute(
-        "DELETE FROM command_history
-         WHERE project_id = ?1
-         AND id NOT IN (
-             SELECT id FROM command_history
-             WHERE project_id = ?1
-             ORDER BY created_at DESC
-             LIMIT ?2
-         )",
-        params![project_id, MAX_HISTORY],
-    )
-    .map_err(|e| e.to_string())?;
-
-    let entry = conn
-        .query_row(
-            "SELECT id, project_id, command, source, created_at
-             FROM command_history WHERE id = ?1",
-            params![entry_id],
-            |row| {
-                Ok(CommandHistoryEntry {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    command: row.get(2)?,
-                    source: row.get(3)?,
-                    created_at: row.get(4)?,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
-
-    Ok(entry)
+    insert_history_entry(db.conn(), &project_id, &command, &source, None)
 }
 
 /// Clear all command history for a project
@@ -241,15 +298,17 @@ pub async fn reorder_script_favorites(
 
 const MAX_SESSIONS: i32 = 10;
 
-/// Save terminal sessions (replaces all existing). Used for session persistence.
-#[tauri::command]
-pub async fn save_terminal_sessions(
-    db: tauri::State<'_, DbState>,
-    sessions: Vec<SaveTerminalSessionInput>,
-) -> Result<i32, String> {
-    let db = db.write().await;
-    let conn = db.conn();
+/// Launch command for a saved tab type, matching the frontend's tab types
+fn command_for_tab_type(tab_type: &str) -> Option<&'static str> {
+    match tab_type {
+        "yolo" => Some("claude --dangerously-skip-permissions"),
+        "claude" => Some("claude"),
+        _ => None,
+    }
+}
 
+/// Replace the saved tabs (at most MAX_SESSIONS)
+fn write_terminal_sessions(conn: &Connection, sessions: &[SaveTerminalSessionInput]) -> Result<i32, String> {
     // Delete all existing sessions
     conn.execute("DELETE FROM terminal_sessions", [])
         .map_err(|e| e.to_string())?;
@@ -258,9 +317,14 @@ pub async fn save_terminal_sessions(
     let count = sessions.len().min(MAX_SESSIONS as usize);
     for session in sessions.iter().take(count) {
         let session_id = format!("{:032x}", rand::random::<u128>());
+        let command = session
+            .command
+            .clone()
+            .or_else(|| command_for_tab_type(&session.tab_type).map(String::from));
         conn.execute(
-            "INSERT INTO terminal_sessions (id, project_id, tab_name, tab_type, working_directory, sort_order, tmux_session)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO terminal_sessions (id, project_id, tab_name, tab_type, working_directory, sort_order,
+                 tmux_session, command, env_profile_id, terminal_profile_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 session_id,
                 session.project_id,
@@ -269,6 +333,9 @@ pub async fn save_terminal_sessions(
                 session.working_directory,
                 session.sort_order,
                 session.tmux_session,
+                command,
+                session.env_profile_id,
+                session.terminal_profile_id,
             ],
         )
         .map_err(|e| e.to_string())?;
@@ -277,17 +344,22 @@ pub async fn save_terminal_sessions(
     Ok(count as i32)
 }
 
-/// Restore terminal sessions from DB
+/// Save terminal sessions (replaces all existing). Used for session persistence.
 #[tauri::command]
-pub async fn restore_terminal_sessions(
+pub async fn save_terminal_sessions(
     db: tauri::State<'_, DbState>,
-) -> Result<Vec<TerminalSession>, String> {
+    sessions: Vec<SaveTerminalSessionInput>,
+) -> Result<i32, String> {
     let db = db.write().await;
-    let conn = db.conn();
+    write_terminal_sessions(db.conn(), &sessions)
+}
 
+/// Saved tabs in display order
+fn load_terminal_sessions(conn: &Connection) -> Result<Vec<TerminalSession>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, project_id, tab_name, tab_type, working_directory, sort_order, tmux_session, created_at
+            "SELECT id, project_id, tab_name, tab_type, working_directory, sort_order, tmux_session, command,
+                 env_profile_id, terminal_profile_id, created_at
              FROM terminal_sessions
              ORDER BY sort_order ASC
              LIMIT ?1",
@@ -304,7 +376,10 @@ pub async fn restore_terminal_sessions(
                 working_directory: row.get(4)?,
                 sort_order: row.get(5)?,
                 tmux_session: row.get(6)?,
-                created_at: row.get(7)?,
+                command: row.get(7)?,
+                env_profile_id: row.get(8)?,
+                terminal_profile_id: row.get(9)?,
+                created_at: row.get(10)?,
             })
         })
         .map_err(|e| e.to_string())?
@@ -313,3 +388,152 @@ pub async fn restore_terminal_sessions(
 
     Ok(sessions)
 }
+
+/// Restore terminal sessions from DB
+#[tauri::command]
+pub async fn restore_terminal_sessions(
+    db: tauri::State<'_, DbState>,
+) -> Result<Vec<TerminalSession>, String> {
+    let db = db.write().await;
+    load_terminal_sessions(db.conn())
+}
+
+/// Bring back the saved tabs: reattach tmux sessions that are still alive,
+/// respawn the rest in their working directory with their launch command,
+/// and drop tabs whose directory is gone or that fail to start. Saved rows
+/// are updated to match.
+#[tauri::command]
+pub async fn restore_workspace(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, TerminalManagerState>,
+    db: tauri::State<'_, DbState>,
+    cols: u16,
+    rows: u16,
+) -> Result<Vec<RestoredTab>, String> {
+    let tabs = {
+        let db = db.write().await;
+        load_terminal_sessions(db.conn())?
+    };
+    let live: Vec<String> = TerminalManager::list_ct_sessions()
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+
+    let mut results = Vec::with_capacity(tabs.len());
+    for tab in tabs {
+        let restored = |outcome: &str, session_id: Option<String>, tmux_name: Option<String>, reason: Option<String>| {
+            RestoredTab {
+                tab: tab.clone(),
+                outcome: outcome.to_string(),
+                session_id,
+                tmux_name,
+                reason,
+            }
+        };
+
+        let reason = match tab.tmux_session.as_deref() {
+            Some(name) if live.iter().any(|l| l == name) => {
+                let attached = state.lock().await.session_for_tmux(name);
+                if let Some(session_id) = attached {
+                    results.push(restored("reattached", Some(session_id), Some(name.to_string()), None));
+                    continue;
+                }
+                let session_id = uuid::Uuid::new_v4().to_string();
+                match attach_pty_session(&app, &state, &db, session_id.clone(), name, &tab.working_directory, cols, rows)
+                    .await
+                {
+                    Ok(_) => {
+                        results.push(restored("reattached", Some(session_id), Some(name.to_string()), None));
+                        continue;
+                    }
+                    Err(e) => e,
+                }
+            }
+            Some(name) => format!("tmux session '{}' no longer exists", name),
+            None => "Native sessions don't survive a restart".to_string(),
+        };
+
+        if !std::path::Path::new(&tab.working_directory).is_dir() {
+            let reason = format!("Working directory {} no longer exists", tab.working_directory);
+            results.push(restored("dropped", None, None, Some(reason)));
+            continue;
+        }
+        let input = CreatePtyInput {
+            working_directory: tab.working_directory.clone(),
+            command: tab.command.clone(),
+            cols,
+            rows,
+            session_name: Some(tab.tab_name.clone()),
+            env_profile_id: tab.env_profile_id.clone(),
+            terminal_profile_id: tab.terminal_profile_id.clone(),
+        };
+        match create_pty_session(&app, &state, &db, input).await {
+            Ok(created) => results.push(restored("respawned", Some(created.session_id), created.tmux_name, Some(reason))),
+            Err(e) => results.push(restored("dropped", None, None, Some(format!("{}; respawn failed: {}", reason, e)))),
+        }
+    }
+
+    let db = db.write().await;
+    let conn = db.conn();
+    for result in &results {
+        match result.outcome.as_str() {
+            "dropped" => conn.execute("DELETE FROM terminal_sessions WHERE id = ?1", params![result.tab.id]),
+            "respawned" => conn.execute(
+                "UPDATE terminal_sessions SET tmux_session = ?1 WHERE id = ?2",
+                params![result.tmux_name, result.tab.id],
+            ),
+            _ => continue,
+        }
+        .map_err(|e| e.to_string())?;
+    }
+
+    let count = |outcome: &str| results.iter().filter(|r| r.outcome == outcome).count();
+    tracing::info!(
+        "Restored workspace: {} reattached, {} respawned, {} dropped",
+        count("reattached"),
+        count("respawned"),
+        count("dropped")
+    );
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    #[test]
+    fn saved_tabs_keep_their_launch_command_and_profiles() {
+        let db = Database::open_in_memory();
+        let conn = db.conn();
+        conn.execute("INSERT INTO projects (id, name, path) VALUES ('p', 'p', '/tmp/p')", [])
+            .unwrap();
+        let tab = |name: &str, tab_type: &str, command: Option<&str>, env_profile: Option<&str>| SaveTerminalSessionInput {
+            project_id: "p".to_string(),
+            tab_name: name.to_string(),
+            tab_type: tab_type.to_string(),
+            working_directory: "/tmp/p".to_string(),
+            sort_order: 0,
+            tmux_session: None,
+            command: command.map(String::from),
+            env_profile_id: env_profile.map(String::from),
+            terminal_profile_id: None,
+        };
+        let mut tabs = vec![
+            tab("dev", "shell", Some("npm run dev"), Some("staging")),
+            tab("claude", "claude", None, None),
+            tab("zsh", "shell", None, None),
+        ];
+        for (i, t) in tabs.iter_mut().enumerate() {
+            t.sort_order = i as i32;
+        }
+        assert_eq!(write_terminal_sessions(conn, &tabs).unwrap(), 3);
+
+        let saved = load_terminal_sessions(conn).unwrap();
+        let launch: Vec<_> = saved
+            .iter()
+            .map(|s| (s.command.as_deref(), s.env_profile_id.as_deref()))
+            .collect();
+        assert_eq!(launch, [(Some("npm run dev"), Some("staging")), (Some("claude"), None), (None, None)]);
+    }
+}
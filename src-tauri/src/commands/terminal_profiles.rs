@@ -0,0 +1,298 @@
+// Track Your Shit - Terminal Profile Commands
+// Per-project shell profiles (shell, args, rc files, env, initial command, cwd) for PTY sessions
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use crate::commands::auto_commands::project_for_path;
+use crate::commands::env_profiles::is_valid_env_name;
+use crate::models::{TerminalProfile, TerminalProfileInput};
+use crate::pty::{SessionOptions, ShellProfile};
+use crate::security::safe_join;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::Arc;
+
+type DbState = Arc<crate::db::DbPool>;
+
+const TERMINAL_PROFILE_COLUMNS: &str = "id, project_id, name, shell_path, args_json, load_rc_files, env_json,
+     initial_command, cwd, is_default, created_at, updated_at";
+
+fn row_to_terminal_profile(row: &rusqlite::Row) -> rusqlite::Result<TerminalProfile> {
+    let args_json: String = row.get(4)?;
+    let env_json: String = row.get(6)?;
+    Ok(TerminalProfile {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        name: row.get(2)?,
+        shell_path: row.get(3)?,
+        args: serde_json::from_str(&args_json).unwrap_or_default(),
+        load_rc_files: row.get(5)?,
+        env: serde_json::from_str(&env_json).unwrap_or_default(),
+        initial_command: row.get(7)?,
+        cwd: row.get(8)?,
+        is_default: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
+fn fetch_terminal_profile(conn: &Connection, id: &str) -> Result<TerminalProfile, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM terminal_profiles WHERE id = ?1", TERMINAL_PROFILE_COLUMNS),
+        params![id],
+        row_to_terminal_profile,
+    )
+    .map_err(|e| format!("Terminal profile not found: {}", e))
+}
+
+fn validate_input(input: &TerminalProfileInput) -> Result<(), String> {
+    if input.name.trim().is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if let Some(shell) = input.shell_path.as_deref() {
+        if shell.trim().is_empty() {
+            return Err("Shell path cannot be empty".to_string());
+        }
+        if Path::new(shell).is_absolute() && !Path::new(shell).exists() {
+            return Err(format!("Shell not found: {}", shell));
+        }
+    }
+    if let Some(name) = input.env.keys().find(|name| !is_valid_env_name(name)) {
+        return Err(format!("Invalid environment variable name '{}'", name));
+    }
+    if let Some(cwd) = input.cwd.as_deref() {
+        if Path::new(cwd).is_absolute() || Path::new(cwd).components().any(|c| c.as_os_str() == "..") {
+            return Err("Working directory must be relative to the project root".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Make `id` the project's only default profile
+fn set_default(conn: &Connection, project_id: &str, id: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE terminal_profiles SET is_default = (id = ?2) WHERE project_id = ?1",
+        params![project_id, id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// The terminal profile for a session in `working_dir` and its project path:
+/// `profile_id` if given (it must be one of the containing project's),
+/// otherwise that project's default profile
+pub(crate) fn session_terminal_profile(
+    conn: &Connection,
+    working_dir: &str,
+    profile_id: Option<&str>,
+) -> Result<Option<(TerminalProfile, String)>, String> {
+    let project = project_for_path(conn, working_dir);
+    let profile = match profile_id {
+        Some(id) => {
+            let profile =
+                fetch_terminal_profile(conn, id).map_err(|_| format!("Terminal profile {} not found", id))?;
+            if project.as_ref().is_none_or(|(project_id, _)| *project_id != profile.project_id) {
+                return Err(format!("Terminal profile '{}' belongs to another project", profile.name));
+            }
+            profile
+        }
+        None => {
+            let Some((project_id, _)) = project else {
+                return Ok(None);
+            };
+            let profile = conn
+                .query_row(
+                    &format!(
+                        "SELECT {} FROM terminal_profiles WHERE project_id = ?1 AND is_default = 1 LIMIT 1",
+                        TERMINAL_PROFILE_COLUMNS
+                    ),
+                    params![project_id],
+                    row_to_terminal_profile,
+                )
+                .ok();
+            match profile {
+                Some(profile) => profile,
+                None => return Ok(None),
+            }
+        }
+    };
+    let project_path: String = conn
+        .query_row(
+            "SELECT path FROM projects WHERE id = ?1",
+            params![profile.project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Project not found: {}", e))?;
+    Ok(Some((profile, project_path)))
+}
+
+/// Apply a terminal profile to the session options. Profile env goes first so
+/// env profile variables win. Returns the working directory to use when the
+/// profile sets one and the session starts at the project root (or `explicit`).
+pub(crate) fn apply_terminal_profile(
+    profile: &TerminalProfile,
+    project_path: &str,
+    working_dir: &str,
+    explicit: bool,
+    options: &mut SessionOptions,
+) -> Result<Option<String>, String> {
+    options.shell = ShellProfile {
+        shell: profile.shell_path.clone(),
+        args: profile.args.clone(),
+        load_rc_files: profile.load_rc_files,
+    };
+    let mut env: Vec<(String, String)> = profile
+        .env
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    env.append(&mut options.env);
+    options.env = env;
+
+    let at_root = Path::new(working_dir) == Path::new(project_path);
+    match profile.cwd.as_deref() {
+        Some(cwd) if explicit || at_root => {
+            let dir = safe_join(project_path, cwd)?;
+            if !dir.is_dir() {
+                return Err(format!("Profile working directory not found: {}", dir.display()));
+            }
+            Ok(Some(dir.to_string_lossy().to_string()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// List a project's terminal profiles, default first
+#[tauri::command]
+pub async fn list_terminal_profiles(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+) -> Result<Vec<TerminalProfile>, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM terminal_profiles
+             WHERE project_id = ?1
+             ORDER BY is_default DESC, name ASC",
+            TERMINAL_PROFILE_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let profiles = stmt
+        .query_map(params![project_id], row_to_terminal_profile)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(profiles)
+}
+
+/// Create a terminal profile. A project's first profile becomes its default.
+#[tauri::command]
+pub async fn create_terminal_profile(
+    db: tauri::State<'_, DbState>,
+    input: TerminalProfileInput,
+) -> Result<TerminalProfile, String> {
+    validate_input(&input)?;
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let profile_id = format!("{:032x}", rand::random::<u128>());
+    let existing: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM terminal_profiles WHERE project_id = ?1",
+            params![input.project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO terminal_profiles (id, project_id, name, shell_path, args_json, load_rc_files,
+             env_json, initial_command, cwd)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            profile_id,
+            input.project_id,
+            input.name.trim(),
+            input.shell_path,
+            serde_json::to_string(&input.args).map_err(|e| e.to_string())?,
+            input.load_rc_files as i32,
+            serde_json::to_string(&input.env).map_err(|e| e.to_string())?,
+            input.initial_command.filter(|c| !c.trim().is_empty()),
+            input.cwd.filter(|c| !c.trim().is_empty()),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    if input.is_default.unwrap_or(existing == 0) {
+        set_default(conn, &input.project_id, &profile_id)?;
+    }
+
+    fetch_terminal_profile(conn, &profile_id)
+}
+
+/// Update a terminal profile
+#[tauri::command]
+pub async fn update_terminal_profile(
+    db: tauri::State<'_, DbState>,
+    id: String,
+    input: TerminalProfileInput,
+) -> Result<TerminalProfile, String> {
+    validate_input(&input)?;
+    let db = db.write().await;
+    let conn = db.conn();
+
+    // Profiles don't move between projects; the stored project is the one
+    // whose other defaults get cleared
+    let project_id = fetch_terminal_profile(conn, &id)
+        .map_err(|_| format!("Terminal profile {} not found", id))?
+        .project_id;
+
+    conn.execute(
+        "UPDATE terminal_profiles SET name = ?1, shell_path = ?2, args_json = ?3,
+             load_rc_files = ?4, env_json = ?5, initial_command = ?6, cwd = ?7,
+             updated_at = datetime('now')
+         WHERE id = ?8",
+        params![
+            input.name.trim(),
+            input.shell_path,
+            serde_json::to_string(&input.args).map_err(|e| e.to_string())?,
+            input.load_rc_files as i32,
+            serde_json::to_string(&input.env).map_err(|e| e.to_string())?,
+            input.initial_command.filter(|c| !c.trim().is_empty()),
+            input.cwd.filter(|c| !c.trim().is_empty()),
+            id,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    match input.is_default {
+        Some(true) => set_default(conn, &project_id, &id)?,
+        Some(false) => {
+            conn.execute(
+                "UPDATE terminal_profiles SET is_default = 0 WHERE id = ?1",
+                params![id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        None => {}
+    }
+
+    fetch_terminal_profile(conn, &id)
+}
+
+/// Delete a terminal profile
+#[tauri::command]
+pub async fn delete_terminal_profile(
+    db: tauri::State<'_, DbState>,
+    id: String,
+) -> Result<bool, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let count = conn
+        .execute("DELETE FROM terminal_profiles WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(count > 0)
+}
@@ -0,0 +1,1372 @@
+// Track Your Shit - Test Run Commands
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+//
+// Parses JUnit XML, TAP, `cargo test --format json` and vitest/jest JSON
+// reports into test_runs / test_results.
+
+use crate::models::{FlakyTest, RecordTestRunInput, TestResult, TestRun};
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+type DbState = Arc<crate::db::DbPool>;
+
+/// A single test case parsed from a report
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParsedTestCase {
+    pub name: String,
+    pub file: Option<String>,
+    pub status: &'static str, // "passed" | "failed" | "skipped"
+    pub duration_ms: i64,
+    pub error_message: Option<String>,
+}
+
+/// A parsed report: the cases plus an overall duration when the report has one
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ParsedTestReport {
+    pub cases: Vec<ParsedTestCase>,
+    pub duration_ms: Option<i64>,
+}
+
+impl ParsedTestReport {
+    fn count(&self, status: &str) -> i32 {
+        self.cases.iter().filter(|c| c.status == status).count() as i32
+    }
+}
+
+/// Guess the report format from its content
+pub(crate) fn detect_format(content: &str) -> Option<&'static str> {
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('<') {
+        return Some("junit");
+    }
+    if trimmed.starts_with('{') {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(trimmed) {
+            if json.get("testResults").is_some() {
+                return Some("jest");
+            }
+        }
+        if trimmed
+            .lines()
+            .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+            .any(|v| matches!(v.get("type").and_then(|t| t.as_str()), Some("test" | "suite")))
+        {
+            return Some("cargo");
+        }
+        return None;
+    }
+    if trimmed.lines().any(|l| {
+        let l = l.trim_start();
+        l.starts_with("TAP version") || l.starts_with("ok ") || l.starts_with("not ok ")
+    }) {
+        return Some("tap");
+    }
+    None
+}
+
+/// Parse a report in the given format ("junit", "tap", "cargo", "jest" or "vitest")
+pub(crate) fn parse_report(format: &str, content: &str) -> Result<ParsedTestReport, String> {
+    match format {
+        "junit" => Ok(parse_junit_xml(content)),
+        "tap" => Ok(parse_tap(content)),
+        "cargo" => Ok(parse_cargo_json(content)),
+        "jest" | "vitest" => parse_jest_json(content),
+        other => Err(format!(
+            "Unsupported test report format '{}'. Use junit, tap, cargo, jest or vitest.",
+            other
+        )),
+    }
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+pub(crate) fn xml_attrs(tag: &str) -> std::collections::HashMap<String, String> {
+    static ATTR_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#"([\w:.-]+)\s*=\s*"([^"]*)""#).unwrap());
+    ATTR_RE
+        .captures_iter(tag)
+        .map(|c| (c[1].to_string(), xml_unescape(&c[2])))
+        .collect()
+}
+
+fn seconds_to_ms(value: Option<&String>) -> i64 {
+    value
+        .and_then(|t| t.parse::<f64>().ok())
+        .map(|secs| (secs * 1000.0).round() as i64)
+        .unwrap_or(0)
+}
+
+/// JUnit XML (also emitted by pytest, vitest, jest-junit, cargo2junit, ...)
+fn parse_junit_xml(content: &str) -> ParsedTestReport {
+    static CASE_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?s)<testcase\b([^>]*?)(?:/>|>(.*?)</testcase>)").unwrap());
+    static FAILURE_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?s)<(failure|error)\b([^>]*?)(?:/>|>(.*?)</(?:failure|error)>)").unwrap());
+    static SUITES_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"<testsuites?\b([^>]*)>").unwrap());
+
+    let cases = CASE_RE
+        .captures_iter(content)
+        .map(|caps| {
+            let attrs = xml_attrs(&caps[1]);
+            let body = caps.get(2).map_or("", |m| m.as_str());
+            let (status, error_message) = if let Some(f) = FAILURE_RE.captures(body) {
+                let attrs = xml_attrs(&f[2]);
+                let text = f.get(3).map(|m| xml_unescape(m.as_str().trim()));
+                let message = attrs
+                    .get("message")
+                    .cloned()
+                    .filter(|m| !m.is_empty())
+                    .or(text.filter(|t| !t.is_empty()));
+                ("failed", message)
+            } else if body.contains("<skipped") {
+                ("skipped", None)
+            } else {
+                ("passed", None)
+            };
+            ParsedTestCase {
+                name: attrs.get("name").cloned().unwrap_or_default(),
+                file: attrs.get("file").or_else(|| attrs.get("classname")).cloned(),
+                status,
+                duration_ms: seconds_to_ms(attrs.get("time")),
+                error_message,
+            }
+        })
+        .collect();
+
+    let duration_ms = SUITES_RE
+        .captures(content)
+        .and_then(|c| xml_attrs(&c[1]).get("time").cloned())
+        .map(|t| seconds_to_ms(Some(&t)));
+
+    ParsedTestReport { cases, duration_ms }
+}
+
+/// TAP 12/13/14. Only top-level test points are counted; indented subtests
+/// are folded into their parent. YAML diagnostics become the error message.
+fn parse_tap(content: &str) -> ParsedTestReport {
+    static POINT_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^(not ok|ok)\b\s*(\d+)?\s*(?:-\s*)?([^#]*)(?:#\s*(\w+)\b(.*))?$").unwrap());
+    let mut cases: Vec<ParsedTestCase> = Vec::new();
+    let mut yaml: Option<Vec<String>> = None;
+
+    for line in content.lines() {
+        if let Some(block) = yaml.as_mut() {
+            let trimmed = line.trim();
+            if trimmed == "..." {
+                let text = block.join("\n");
+                if let Some(last) = cases.last_mut() {
+                    if last.status == "failed" && !text.is_empty() {
+                        last.error_message = Some(text);
+                    }
+                    if let Some(ms) = block
+                        .iter()
+                        .find_map(|l| l.trim().strip_prefix("duration_ms:"))
+                        .and_then(|v| v.trim().parse::<f64>().ok())
+                    {
+                        last.duration_ms = ms.round() as i64;
+                    }
+                }
+                yaml = None;
+            } else {
+                block.push(trimmed.to_string());
+            }
+            continue;
+        }
+        if line.trim() == "---" && !cases.is_empty() {
+            yaml = Some(Vec::new());
+            continue;
+        }
+        if line.starts_with(' ') || line.starts_with('\t') {
+            continue;
+        }
+        let Some(caps) = POINT_RE.captures(line) else {
+            continue;
+        };
+        let ok = &caps[1] == "ok";
+        let directive = caps.get(4).map(|d| d.as_str().to_uppercase());
+        let status = match directive.as_deref() {
+            Some("SKIP") => "skipped",
+            Some("TODO") if !ok => "skipped",
+            _ if ok => "passed",
+            _ => "failed",
+        };
+        let name = caps.get(3).map_or("", |m| m.as_str()).trim();
+        let name = if name.is_empty() {
+            format!("test {}", caps.get(2).map_or("?", |m| m.as_str()))
+        } else {
+            name.to_string()
+        };
+        cases.push(ParsedTestCase {
+            name,
+            file: None,
+            status,
+            duration_ms: 0,
+            error_message: None,
+        });
+    }
+
+    ParsedTestReport {
+        cases,
+        duration_ms: None,
+    }
+}
+
+/// libtest JSON lines from `cargo test -- -Z unstable-options --format json`
+fn parse_cargo_json(content: &str) -> ParsedTestReport {
+    let mut cases = Vec::new();
+    let mut duration_ms: Option<i64> = None;
+
+    for v in content
+        .lines()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l.trim()).ok())
+    {
+        let event = v.get("event").and_then(|e| e.as_str()).unwrap_or("");
+        match v.get("type").and_then(|t| t.as_str()) {
+            Some("test") => {
+                let status = match event {
+                    "ok" => "passed",
+                    "failed" | "timeout" => "failed",
+                    "ignored" => "skipped",
+                    _ => continue, // "started"
+                };
+                let name = v.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+                let duration = v
+                    .get("exec_time")
+                    .and_then(|t| t.as_f64())
+                    .map_or(0, |secs| (secs * 1000.0).round() as i64);
+                cases.push(ParsedTestCase {
+                    name: name.to_string(),
+                    // "module::tests::name" -> module path as the "file"
+                    file: name.rsplit_once("::").map(|(module, _)| module.to_string()),
+                    status,
+                    duration_ms: duration,
+                    error_message: v
+                        .get("stdout")
+                        .or_else(|| v.get("message"))
+                        .and_then(|m| m.as_str())
+                        .filter(|_| status == "failed")
+                        .map(String::from),
+                });
+            }
+            Some("suite") if event != "started" => {
+                if let Some(secs) = v.get("exec_time").and_then(|t| t.as_f64()) {
+                    *duration_ms.get_or_insert(0) += (secs * 1000.0).round() as i64;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ParsedTestReport { cases, duration_ms }
+}
+
+/// Plain `cargo test` console output ("test a::b ... ok"), used when a
+/// command is run without a machine-readable reporter
+fn parse_cargo_text(output: &str) -> ParsedTestReport {
+    static LINE_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^test (\S+) \.\.\. (ok|FAILED|ignored)").unwrap());
+    static RESULT_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^test result: .*finished in ([\d.]+)s").unwrap());
+    let mut cases: Vec<ParsedTestCase> = Vec::new();
+    let mut duration_ms: Option<i64> = None;
+    let mut failure_output: std::collections::HashMap<String, Vec<String>> =
+        std::collections::HashMap::new();
+    let mut current_failure: Option<String> = None;
+
+    for line in output.lines() {
+        if let Some(caps) = LINE_RE.captures(line) {
+            let name = caps[1].to_string();
+            cases.push(ParsedTestCase {
+                file: name.rsplit_once("::").map(|(module, _)| module.to_string()),
+                name,
+                status: match &caps[2] {
+                    "ok" => "passed",
+                    "FAILED" => "failed",
+                    _ => "skipped",
+                },
+                duration_ms: 0,
+                error_message: None,
+            });
+        } else if let Some(caps) = RESULT_RE.captures(line) {
+            if let Ok(secs) = caps[1].parse::<f64>() {
+                *duration_ms.get_or_insert(0) += (secs * 1000.0).round() as i64;
+            }
+        } else if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        {
+            current_failure = Some(name.to_string());
+        } else if line == "failures:" || line.starts_with("test result:") {
+            current_failure = None;
+        } else if let Some(name) = &current_failure {
+            failure_output.entry(name.clone()).or_default().push(line.to_string());
+        }
+    }
+
+    for case in cases.iter_mut().filter(|c| c.status == "failed") {
+        if let Some(lines) = failure_output.get(&case.name) {
+            let text = lines.join("\n").trim().to_string();
+            if !text.is_empty() {
+                case.error_message = Some(text);
+            }
+        }
+    }
+
+    ParsedTestReport { cases, duration_ms }
+}
+
+/// Jest `--json` output; vitest's `--reporter=json` uses the same shape
+fn parse_jest_json(content: &str) -> Result<ParsedTestReport, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid JSON report: {}", e))?;
+    let mut cases = Vec::new();
+    let mut min_start: Option<i64> = None;
+    let mut max_end: Option<i64> = None;
+
+    for file in json
+        .get("testResults")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let file_name = file
+            .get("name")
+            .or_else(|| file.get("testFilePath"))
+            .and_then(|n| n.as_str())
+            .map(String::from);
+        if let Some(start) = file.get("startTime").and_then(|t| t.as_i64()) {
+            min_start = Some(min_start.map_or(start, |m| m.min(start)));
+        }
+        if let Some(end) = file.get("endTime").and_then(|t| t.as_i64()) {
+            max_end = Some(max_end.map_or(end, |m| m.max(end)));
+        }
+
+        for assertion in file
+            .get("assertionResults")
+            .and_then(|a| a.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let status = match assertion.get("status").and_then(|s| s.as_str()) {
+                Some("passed") => "passed",
+                Some("failed") => "failed",
+                _ => "skipped", // pending, skipped, todo, disabled
+            };
+            let failure_messages: Vec<&str> = assertion
+                .get("failureMessages")
+                .and_then(|m| m.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|m| m.as_str())
+                .collect();
+            cases.push(ParsedTestCase {
+                name: assertion
+                    .get("fullName")
+                    .or_else(|| assertion.get("title"))
+                    .and_then(|n| n.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                file: file_name.clone(),
+                status,
+                duration_ms: assertion
+                    .get("duration")
+                    .and_then(|d| d.as_f64())
+                    .map_or(0, |d| d.round() as i64),
+                error_message: (!failure_messages.is_empty()).then(|| failure_messages.join("\n")),
+            });
+        }
+    }
+
+    let duration_ms = match (min_start, max_end) {
+        (Some(start), Some(end)) if end >= start => Some(end - start),
+        _ => None,
+    };
+    Ok(ParsedTestReport { cases, duration_ms })
+}
+
+fn row_to_test_run(row: &rusqlite::Row) -> rusqlite::Result<TestRun> {
+    Ok(TestRun {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        execution_id: row.get(2)?,
+        phase: row.get(3)?,
+        total_tests: row.get(4)?,
+        passed: row.get(5)?,
+        failed: row.get(6)?,
+        skipped: row.get(7)?,
+        duration_ms: row.get(8)?,
+        coverage_lines: row.get(9)?,
+        coverage_branches: row.get(10)?,
+        coverage_functions: row.get(11)?,
+        commit_sha: row.get(12)?,
+        started_at: row.get(13)?,
+        completed_at: row.get(14)?,
+        created_at: row.get(15)?,
+    })
+}
+
+const TEST_RUN_COLUMNS: &str = "id, project_id, execution_id, phase, total_tests, passed, failed, skipped,
+     duration_ms, coverage_lines, coverage_branches, coverage_functions, commit_sha, started_at, completed_at,
+     created_at";
+
+pub(crate) fn fetch_test_run(conn: &Connection, test_run_id: &str) -> Result<TestRun, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM test_runs WHERE id = ?1", TEST_RUN_COLUMNS),
+        params![test_run_id],
+        row_to_test_run,
+    )
+    .map_err(|e| format!("Test run not found: {}", e))
+}
+
+/// Coverage percentages for a run, any of which may be unknown
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RunCoverage {
+    pub lines: Option<f64>,
+    pub branches: Option<f64>,
+    pub functions: Option<f64>,
+}
+
+/// Write a parsed report as a test run with its results, and fold the results
+/// into the project's flaky test stats. Runs inside the caller's transaction;
+/// returns the new run's id.
+pub(crate) fn write_test_run(
+    conn: &Connection,
+    project_id: &str,
+    execution_id: Option<&str>,
+    phase: Option<&str>,
+    commit_sha: Option<&str>,
+    report: &ParsedTestReport,
+    coverage: RunCoverage,
+) -> Result<String, String> {
+    let run_id = format!("{:032x}", rand::random::<u128>());
+    let duration_ms = report
+        .duration_ms
+        .unwrap_or_else(|| report.cases.iter().map(|c| c.duration_ms).sum());
+
+    conn.execute(
+        "INSERT INTO test_runs (id, project_id, execution_id, phase, total_tests, passed, failed, skipped,
+             duration_ms, coverage_lines, coverage_branches, coverage_functions, commit_sha, started_at, completed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+             datetime('now', printf('-%d seconds', ?9 / 1000)), datetime('now'))",
+        params![
+            run_id,
+            project_id,
+            execution_id,
+            phase,
+            report.cases.len() as i32,
+            report.count("passed"),
+            report.count("failed"),
+            report.count("skipped"),
+            duration_ms,
+            coverage.lines,
+            coverage.branches,
+            coverage.functions,
+            commit_sha,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "INSERT INTO test_results (id, test_run_id, test_name, test_file, status, duration_ms, error_message)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .map_err(|e| e.to_string())?;
+    for case in &report.cases {
+        stmt.execute(params![
+            format!("{:032x}", rand::random::<u128>()),
+            run_id,
+            case.name,
+            case.file,
+            case.status,
+            case.duration_ms,
+            case.error_message,
+        ])
+        .map_err(|e| e.to_string())?;
+    }
+
+    update_flaky_tests(conn, project_id, &run_id, commit_sha, &report.cases)?;
+    Ok(run_id)
+}
+
+/// `write_test_run` in its own transaction, returning the stored run
+pub(crate) fn insert_test_run(
+    conn: &Connection,
+    project_id: &str,
+    execution_id: Option<&str>,
+    phase: Option<&str>,
+    commit_sha: Option<&str>,
+    report: &ParsedTestReport,
+    coverage: RunCoverage,
+) -> Result<TestRun, String> {
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let run_id = match write_test_run(conn, project_id, execution_id, phase, commit_sha, report, coverage) {
+        Ok(run_id) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            run_id
+        }
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+    };
+
+    fetch_test_run(conn, &run_id)
+}
+
+// ============================================================
+// Running test commands
+// ============================================================
+
+/// Lines of combined output kept for the run result
+const OUTPUT_TAIL_LINES: usize = 200;
+
+/// Work out which test runner a command uses, falling back to the
+/// framework named in VALIDATION.md (e.g. "vitest 1.x", "cargo test")
+pub(crate) fn detect_framework(command: &str, declared: Option<&str>) -> Option<&'static str> {
+    fn from_text(text: &str) -> Option<&'static str> {
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| c.is_whitespace() || c == '/' || c == '@')
+            .collect();
+        if words.contains(&"vitest") {
+            Some("vitest")
+        } else if words.contains(&"jest") {
+            Some("jest")
+        } else if words.contains(&"pytest") {
+            Some("pytest")
+        } else if words.contains(&"cargo") || words.contains(&"nextest") {
+            Some("cargo")
+        } else if lower.contains("node --test") || words.contains(&"tap") {
+            Some("tap")
+        } else {
+            None
+        }
+    }
+    from_text(command).or_else(|| declared.and_then(from_text))
+}
+
+/// Add a machine-readable reporter writing to `report_path` when the command
+/// invokes the runner directly. Script wrappers (`npm test`) and compound shell
+/// commands are left alone and fall back to parsing console output.
+pub(crate) fn with_reporter(command: &str, framework: &str, report_path: &Path) -> Option<String> {
+    if ["&&", "||", "|", ";", ">"].iter().any(|op| command.contains(op)) {
+        return None;
+    }
+    let invokes = |runner: &str| {
+        command
+            .split_whitespace()
+            .any(|w| w.rsplit('/').next() == Some(runner))
+    };
+    let path = format!("'{}'", report_path.to_string_lossy().replace('\'', "'\\''"));
+    match framework {
+        "vitest" if invokes("vitest") => Some(format!(
+            "{} --reporter=default --reporter=json --outputFile.json={}",
+            command, path
+        )),
+        "jest" if invokes("jest") => Some(format!("{} --json --outputFile={}", command, path)),
+        "pytest" if invokes("pytest") => Some(format!("{} --junitxml={}", command, path)),
+        _ => None,
+    }
+}
+
+/// Outcome of a test command run
+#[derive(Debug, Default)]
+pub(crate) struct TestCommandOutcome {
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: i64,
+    pub report: Option<ParsedTestReport>,
+    pub output_tail: Vec<String>,
+}
+
+impl TestCommandOutcome {
+    /// Apply the quarantine list: returns the report without quarantined
+    /// tests, the names of quarantined tests that failed, and whether the run
+    /// passes once those failures are ignored
+    pub(crate) fn judge(
+        &self,
+        quarantine: &[(String, Option<String>)],
+    ) -> (Option<ParsedTestReport>, Vec<String>, bool) {
+        let quarantined_failures: Vec<String> = self
+            .report
+            .iter()
+            .flat_map(|r| r.cases.iter())
+            .filter(|c| c.status == "failed" && is_quarantined(quarantine, c))
+            .map(|c| c.name.clone())
+            .collect();
+        let counted = self.report.clone().map(|mut r| {
+            r.cases.retain(|c| !is_quarantined(quarantine, c));
+            r
+        });
+        let failed_tests = counted
+            .as_ref()
+            .is_some_and(|r| r.cases.iter().any(|c| c.status == "failed"));
+        let passed = !self.timed_out
+            && !failed_tests
+            && (self.exit_code == Some(0) || !quarantined_failures.is_empty());
+        (counted, quarantined_failures, passed)
+    }
+}
+
+#[cfg(unix)]
+fn kill_process_group(pid: u32) {
+    // The shell runs in its own process group so test workers die with it
+    unsafe {
+        libc::kill(-(pid as i32), libc::SIGKILL);
+    }
+}
+
+fn spawn_line_reader<R, F>(
+    stream: &'static str,
+    pipe: R,
+    on_line: Arc<F>,
+    lines: Arc<std::sync::Mutex<Vec<String>>>,
+) -> tokio::task::JoinHandle<()>
+where
+    R: tokio::io::AsyncRead + Unpin + Send + 'static,
+    F: Fn(&str, &str) + Send + Sync + 'static,
+{
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(pipe).lines();
+        while let Ok(Some(line)) = reader.next_line().await {
+            on_line(stream, &line);
+            if let Ok(mut lines) = lines.lock() {
+                lines.push(line);
+            }
+        }
+    })
+}
+
+/// Run a test command through the shell in `project_path`, calling
+/// `on_line(stream, line)` for each output line, and parse whatever results
+/// the run produced. The command is killed once `timeout` elapses.
+pub(crate) async fn run_test_command<F>(
+    project_path: &str,
+    command: &str,
+    framework: Option<&str>,
+    timeout: std::time::Duration,
+    on_line: F,
+) -> Result<TestCommandOutcome, String>
+where
+    F: Fn(&str, &str) + Send + Sync + 'static,
+{
+    let report_path = std::env::temp_dir().join(format!(
+        "tys-test-report-{:032x}",
+        rand::random::<u128>()
+    ));
+    let reporter_command = framework.and_then(|f| with_reporter(command, f, &report_path));
+    let shell_command = reporter_command.as_deref().unwrap_or(command);
+
+    #[cfg(unix)]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", shell_command]).process_group(0);
+        cmd
+    };
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.args(["/C", shell_command]);
+        cmd
+    };
+    cmd.current_dir(project_path)
+        .env("CI", "1")
+        .env("FORCE_COLOR", "0")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+
+    let started = std::time::Instant::now();
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+
+    let on_line = Arc::new(on_line);
+    let lines: Arc<std::sync::Mutex<Vec<String>>> = Arc::default();
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(spawn_line_reader("stdout", stdout, on_line.clone(), lines.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(spawn_line_reader("stderr", stderr, on_line.clone(), lines.clone()));
+    }
+
+    let (exit_code, timed_out) = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(status) => (
+            status
+                .map_err(|e| format!("Failed to wait for '{}': {}", command, e))?
+                .code(),
+            false,
+        ),
+        Err(_) => {
+            #[cfg(unix)]
+            if let Some(pid) = child.id() {
+                kill_process_group(pid);
+            }
+            let _ = child.kill().await;
+            (None, true)
+        }
+    };
+
+    // Grandchildren can keep the pipes open; don't wait on them forever
+    for reader in readers {
+        let abort = reader.abort_handle();
+        if tokio::time::timeout(std::time::Duration::from_secs(2), reader)
+            .await
+            .is_err()
+        {
+            abort.abort();
+        }
+    }
+    let duration_ms = started.elapsed().as_millis() as i64;
+
+    let output = lines.lock().map(|l| l.clone()).unwrap_or_default();
+    let report = match fs::read_to_string(&report_path) {
+        Ok(content) => {
+            let _ = fs::remove_file(&report_path);
+            detect_format(&content).and_then(|format| parse_report(format, &content).ok())
+        }
+        Err(_) => parse_console_output(framework, &output.join("\n")),
+    }
+    .filter(|r| !r.cases.is_empty());
+
+    let output_tail = output[output.len().saturating_sub(OUTPUT_TAIL_LINES)..].to_vec();
+    Ok(TestCommandOutcome {
+        exit_code,
+        timed_out,
+        duration_ms,
+        report,
+        output_tail,
+    })
+}
+
+/// Best-effort parse of console output when no report file was written
+fn parse_console_output(framework: Option<&str>, output: &str) -> Option<ParsedTestReport> {
+    if framework == Some("cargo") || output.contains("\ntest result: ") {
+        return Some(parse_cargo_text(output));
+    }
+    match detect_format(output) {
+        Some("tap") => Some(parse_tap(output)),
+        _ => None,
+    }
+}
+
+// ============================================================
+// Flaky tests and quarantine
+// ============================================================
+
+/// Identifies the code a run was made against: HEAD for a clean worktree,
+/// otherwise HEAD plus a hash of the uncommitted diff. None outside git repos.
+pub(crate) fn worktree_state(project_path: &str) -> Option<String> {
+    use std::hash::{Hash, Hasher};
+
+    let git = |args: &[&str]| {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(project_path)
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| out.stdout)
+    };
+    let head = String::from_utf8_lossy(&git(&["rev-parse", "HEAD"])?)
+        .trim()
+        .to_string();
+    let diff = git(&["diff", "HEAD"]).unwrap_or_default();
+    if diff.is_empty() {
+        return Some(head);
+    }
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    diff.hash(&mut hasher);
+    Some(format!("{}+dirty.{:016x}", head, hasher.finish()))
+}
+
+/// Pass/fail history for one test, oldest first
+fn test_status_history(
+    conn: &Connection,
+    project_id: &str,
+    test_name: &str,
+    test_file: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.status FROM test_results r
+             JOIN test_runs t ON t.id = r.test_run_id
+             WHERE t.project_id = ?1 AND r.test_name = ?2
+               AND COALESCE(r.test_file, '') = COALESCE(?3, '')
+               AND r.status IN ('passed', 'failed')
+             ORDER BY t.created_at, t.rowid",
+        )
+        .map_err(|e| e.to_string())?;
+    let statuses = stmt
+        .query_map(params![project_id, test_name, test_file], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(statuses)
+}
+
+fn flake_rate(flip_count: i32, total_runs: i32) -> f64 {
+    if total_runs > 1 {
+        flip_count as f64 / (total_runs - 1) as f64
+    } else {
+        0.0
+    }
+}
+
+/// Create a flaky_tests row for a test from its full history
+fn insert_flaky_from_history(
+    conn: &Connection,
+    project_id: &str,
+    test_name: &str,
+    test_file: Option<&str>,
+) -> Result<String, String> {
+    let history = test_status_history(conn, project_id, test_name, test_file)?;
+    let total_runs = history.len() as i32;
+    let failure_count = history.iter().filter(|s| *s == "failed").count() as i32;
+    let flip_count = history.windows(2).filter(|w| w[0] != w[1]).count() as i32;
+    let id = format!("{:032x}", rand::random::<u128>());
+
+    conn.execute(
+        "INSERT INTO flaky_tests (id, project_id, test_name, test_file, total_runs, failure_count,
+             flake_rate, flip_count, last_status, last_failure)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, CASE WHEN ?6 > 0 THEN datetime('now') END)",
+        params![
+            id,
+            project_id,
+            test_name,
+            test_file,
+            total_runs,
+            failure_count,
+            flake_rate(flip_count, total_runs),
+            flip_count,
+            history.last(),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+/// Update flaky_tests with the results of a run that was just inserted.
+/// Tests are only tracked once they have failed at least once.
+fn update_flaky_tests(
+    conn: &Connection,
+    project_id: &str,
+    run_id: &str,
+    commit_sha: Option<&str>,
+    cases: &[ParsedTestCase],
+) -> Result<(), String> {
+    for case in cases.iter().filter(|c| c.status != "skipped") {
+        let failed = case.status == "failed";
+        let same_commit_flip = match commit_sha {
+            Some(sha) => conn
+                .query_row(
+                    "SELECT 1 FROM test_results r
+                     JOIN test_runs t ON t.id = r.test_run_id
+                     WHERE t.project_id = ?1 AND t.commit_sha = ?2 AND t.id != ?3
+                       AND r.test_name = ?4 AND COALESCE(r.test_file, '') = COALESCE(?5, '')
+                       AND r.status IN ('passed', 'failed') AND r.status != ?6
+                     LIMIT 1",
+                    params![project_id, sha, run_id, case.name, case.file, case.status],
+                    |_| Ok(()),
+                )
+                .optional()
+                .map_err(|e| e.to_string())?
+                .is_some(),
+            None => false,
+        };
+
+        let existing: Option<(String, i32, i32, Option<String>)> = conn
+            .query_row(
+                "SELECT id, COALESCE(total_runs, 0), COALESCE(flip_count, 0), last_status
+                 FROM flaky_tests
+                 WHERE project_id = ?1 AND test_name = ?2 AND COALESCE(test_file, '') = COALESCE(?3, '')",
+                params![project_id, case.name, case.file],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+
+        let id = match existing {
+            Some((id, total_runs, flip_count, last_status)) => {
+                let total_runs = total_runs + 1;
+                let flip_count = flip_count
+                    + i32::from(last_status.as_deref().is_some_and(|s| s != case.status));
+                conn.execute(
+                    "UPDATE flaky_tests
+                     SET total_runs = ?2,
+                         failure_count = COALESCE(failure_count, 0) + ?3,
+                         flip_count = ?4,
+                         flake_rate = ?5,
+                         last_status = ?6,
+                         last_failure = CASE WHEN ?3 > 0 THEN datetime('now') ELSE last_failure END
+                     WHERE id = ?1",
+                    params![
+                        id,
+                        total_runs,
+                        i32::from(failed),
+                        flip_count,
+                        flake_rate(flip_count, total_runs),
+                        case.status,
+                    ],
+                )
+                .map_err(|e| e.to_string())?;
+                id
+            }
+            None if failed || same_commit_flip => {
+                insert_flaky_from_history(conn, project_id, &case.name, case.file.as_deref())?
+            }
+            None => continue,
+        };
+
+        if same_commit_flip {
+            conn.execute(
+                "UPDATE flaky_tests
+                 SET same_commit_flips = COALESCE(same_commit_flips, 0) + 1, last_flip_commit = ?2
+                 WHERE id = ?1",
+                params![id, commit_sha],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Quarantined (test_name, test_file) pairs for a project
+pub(crate) fn quarantined_tests(
+    conn: &Connection,
+    project_id: &str,
+) -> Result<Vec<(String, Option<String>)>, String> {
+    let mut stmt = conn
+        .prepare("SELECT test_name, test_file FROM flaky_tests WHERE project_id = ?1 AND quarantined = 1")
+        .map_err(|e| e.to_string())?;
+    let tests = stmt
+        .query_map(params![project_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(tests)
+}
+
+/// Whether a parsed case is on the quarantine list. A quarantine entry
+/// without a file matches the test name in any file.
+pub(crate) fn is_quarantined(quarantine: &[(String, Option<String>)], case: &ParsedTestCase) -> bool {
+    quarantine.iter().any(|(name, file)| {
+        *name == case.name && (file.is_none() || file.as_deref() == case.file.as_deref())
+    })
+}
+
+const FLAKY_TEST_COLUMNS: &str = "id, project_id, test_name, test_file, COALESCE(total_runs, 0),
+     COALESCE(failure_count, 0), COALESCE(flake_rate, 0), COALESCE(flip_count, 0),
+     COALESCE(same_commit_flips, 0), last_flip_commit, last_status, last_failure,
+     COALESCE(quarantined, 0), quarantined_at, quarantine_reason, first_seen";
+
+fn row_to_flaky_test(row: &rusqlite::Row) -> rusqlite::Result<FlakyTest> {
+    Ok(FlakyTest {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        test_name: row.get(2)?,
+        test_file: row.get(3)?,
+        total_runs: row.get(4)?,
+        failure_count: row.get(5)?,
+        flake_rate: row.get(6)?,
+        flip_count: row.get(7)?,
+        same_commit_flips: row.get(8)?,
+        last_flip_commit: row.get(9)?,
+        last_status: row.get(10)?,
+        last_failure: row.get(11)?,
+        quarantined: row.get::<_, i32>(12)? != 0,
+        quarantined_at: row.get(13)?,
+        quarantine_reason: row.get(14)?,
+        first_seen: row.get(15)?,
+    })
+}
+
+/// Parse a test report and record it as a test run for the project
+#[tauri::command]
+pub async fn record_test_run(
+    db: tauri::State<'_, DbState>,
+    input: RecordTestRunInput,
+) -> Result<TestRun, String> {
+    let project_path: String = {
+        let conn = db.read().await;
+        conn.query_row(
+            "SELECT path FROM projects WHERE id = ?1",
+            params![input.project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Project not found: {}", e))?
+    };
+
+    // Read and parse the report and look up the phase and commit before
+    // taking the write lock (worktree_state runs git)
+    let parse_input = input.clone();
+    let (format, report, phase, commit_sha) = tauri::async_runtime::spawn_blocking(move || {
+        let input = parse_input;
+        let content = match (&input.content, &input.report_path) {
+            (Some(content), _) => content.clone(),
+            (None, Some(report_path)) => {
+                let path = Path::new(report_path);
+                let path = if path.is_absolute() {
+                    path.to_path_buf()
+                } else {
+                    Path::new(&project_path).join(path)
+                };
+                fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read report {}: {}", path.display(), e))?
+            }
+            (None, None) => return Err("Provide either content or report_path".to_string()),
+        };
+
+        let format = match input.format.as_deref() {
+            Some(f) => f.to_lowercase(),
+            None => detect_format(&content)
+                .ok_or_else(|| "Could not detect test report format".to_string())?
+                .to_string(),
+        };
+        let report = parse_report(&format, &content)?;
+        if report.cases.is_empty() {
+            return Err(format!("No test cases found in {} report", format));
+        }
+
+        let phase = input.phase.clone().or_else(|| {
+            input
+                .use_current_phase
+                .then(|| crate::commands::gsd::read_current_phase_plan(&project_path).0)
+                .flatten()
+        });
+        Ok((format, report, phase, worktree_state(&project_path)))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let db = db.write().await;
+    let run = insert_test_run(
+        db.conn(),
+        &input.project_id,
+        input.execution_id.as_deref(),
+        phase.as_deref(),
+        commit_sha.as_deref(),
+        &report,
+        RunCoverage {
+            lines: input.coverage_lines,
+            branches: input.coverage_branches,
+            functions: input.coverage_functions,
+        },
+    )?;
+
+    tracing::info!(
+        "[test_runs] Recorded {} run for project {}: {} passed, {} failed, {} skipped",
+        format,
+        input.project_id,
+        run.passed,
+        run.failed,
+        run.skipped
+    );
+
+    Ok(run)
+}
+
+/// List recent test runs for a project
+#[tauri::command]
+pub async fn list_test_runs(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    limit: Option<i32>,
+) -> Result<Vec<TestRun>, String> {
+    let conn = db.read().await;
+    let limit = limit.unwrap_or(20);
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM test_runs WHERE project_id = ?1 ORDER BY created_at DESC LIMIT ?2",
+            TEST_RUN_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let runs = stmt
+        .query_map(params![project_id, limit], row_to_test_run)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(runs)
+}
+
+/// Get the individual results for a test run, failures first
+#[tauri::command]
+pub async fn get_test_results(
+    db: tauri::State<'_, DbState>,
+    test_run_id: String,
+) -> Result<Vec<TestResult>, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, test_run_id, test_name, test_file, status, duration_ms, error_message,
+                    COALESCE(retry_count, 0), created_at
+             FROM test_results
+             WHERE test_run_id = ?1
+             ORDER BY CASE status WHEN 'failed' THEN 0 WHEN 'skipped' THEN 1 ELSE 2 END, test_name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let results = stmt
+        .query_map(params![test_run_id], |row| {
+            Ok(TestResult {
+                id: row.get(0)?,
+                test_run_id: row.get(1)?,
+                test_name: row.get(2)?,
+                test_file: row.get(3)?,
+                status: row.get(4)?,
+                duration_ms: row.get(5)?,
+                error_message: row.get(6)?,
+                retry_count: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(results)
+}
+
+/// List a project's flaky tests, most flaky first. Tests that fail every
+/// time (no flips) are not flaky and are left out unless quarantined.
+#[tauri::command]
+pub async fn list_flaky_tests(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    min_flake_rate: Option<f64>,
+    limit: Option<i32>,
+) -> Result<Vec<FlakyTest>, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM flaky_tests
+             WHERE project_id = ?1
+               AND (COALESCE(flip_count, 0) > 0 OR COALESCE(same_commit_flips, 0) > 0 OR quarantined = 1)
+               AND COALESCE(flake_rate, 0) >= ?2
+             ORDER BY flake_rate DESC, same_commit_flips DESC, failure_count DESC
+             LIMIT ?3",
+            FLAKY_TEST_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let tests = stmt
+        .query_map(
+            params![project_id, min_flake_rate.unwrap_or(0.0), limit.unwrap_or(50)],
+            row_to_flaky_test,
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(tests)
+}
+
+/// List a project's quarantined tests
+#[tauri::command]
+pub async fn list_quarantined_tests(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+) -> Result<Vec<FlakyTest>, String> {
+    let conn = db.read().await;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM flaky_tests WHERE project_id = ?1 AND quarantined = 1
+             ORDER BY quarantined_at DESC",
+            FLAKY_TEST_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let tests = stmt
+        .query_map(params![project_id], row_to_flaky_test)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(tests)
+}
+
+/// Add a test to (or remove it from) the project's quarantine list. Tests
+/// without history yet are tracked from their existing results.
+#[tauri::command]
+pub async fn set_test_quarantine(
+    db: tauri::State<'_, DbState>,
+    project_id: String,
+    test_name: String,
+    test_file: Option<String>,
+    quarantined: bool,
+    reason: Option<String>,
+) -> Result<FlakyTest, String> {
+    let db = db.write().await;
+    let conn = db.conn();
+
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM flaky_tests
+             WHERE project_id = ?1 AND test_name = ?2 AND COALESCE(test_file, '') = COALESCE(?3, '')",
+            params![project_id, test_name, test_file],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let id = match existing {
+        Some(id) => id,
+        None => insert_flaky_from_history(conn, &project_id, &test_name, test_file.as_deref())?,
+    };
+
+    conn.execute(
+        "UPDATE flaky_tests
+         SET quarantined = ?2,
+             quarantined_at = CASE WHEN ?2 = 1 THEN datetime('now') END,
+             quarantine_reason = CASE WHEN ?2 = 1 THEN ?3 END
+         WHERE id = ?1",
+        params![id, quarantined as i32, reason],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        &format!("SELECT {} FROM flaky_tests WHERE id = ?1", FLAKY_TEST_COLUMNS),
+        params![id],
+        row_to_flaky_test,
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_junit_xml() {
+        let xml = r#"<?xml version="1.0"?>
+<testsuites time="1.5">
+  <testsuite name="auth" tests="3">
+    <testcase classname="auth.login" name="accepts valid token" time="0.25"/>
+    <testcase classname="auth.login" name="rejects &quot;bad&quot; token" time="0.5">
+      <failure message="expected 401">stack trace</failure>
+    </testcase>
+    <testcase classname="auth.login" name="refresh" time="0"><skipped/></testcase>
+  </testsuite>
+</testsuites>"#;
+        assert_eq!(detect_format(xml), Some("junit"));
+        let report = parse_junit_xml(xml);
+        assert_eq!(report.cases.len(), 3);
+        assert_eq!(report.cases[0].duration_ms, 250);
+        assert_eq!(report.cases[1].name, "rejects \"bad\" token");
+        assert_eq!(report.cases[1].status, "failed");
+        assert_eq!(report.cases[1].error_message.as_deref(), Some("expected 401"));
+        assert_eq!(report.cases[2].status, "skipped");
+        assert_eq!(report.duration_ms, Some(1500));
+    }
+
+    #[test]
+    fn test_parse_tap() {
+        let tap = "TAP version 13\n1..4\nok 1 - adds numbers\nnot ok 2 - divides\n  ---\n  message: division by zero\n  ...\nok 3 - network # SKIP offline\nnot ok 4 - later # TODO not written\n";
+        assert_eq!(detect_format(tap), Some("tap"));
+        let report = parse_tap(tap);
+        let statuses: Vec<&str> = report.cases.iter().map(|c| c.status).collect();
+        assert_eq!(statuses, vec!["passed", "failed", "skipped", "skipped"]);
+        assert_eq!(report.cases[1].name, "divides");
+        assert_eq!(
+            report.cases[1].error_message.as_deref(),
+            Some("message: division by zero")
+        );
+    }
+
+    #[test]
+    fn test_parse_cargo_json() {
+        let json = r#"{ "type": "suite", "event": "started", "test_count": 2 }
+{ "type": "test", "event": "started", "name": "db::tests::opens" }
+{ "type": "test", "name": "db::tests::opens", "event": "ok", "exec_time": 0.012 }
+{ "type": "test", "name": "db::tests::migrates", "event": "failed", "stdout": "assertion failed" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "exec_time": 0.5 }"#;
+        assert_eq!(detect_format(json), Some("cargo"));
+        let report = parse_cargo_json(json);
+        assert_eq!(report.cases.len(), 2);
+        assert_eq!(report.cases[0].file.as_deref(), Some("db::tests"));
+        assert_eq!(report.cases[0].duration_ms, 12);
+        assert_eq!(report.cases[1].error_message.as_deref(), Some("assertion failed"));
+        assert_eq!(report.duration_ms, Some(500));
+    }
+
+    #[test]
+    fn test_parse_jest_json() {
+        let json = r#"{"numTotalTests":2,"testResults":[{"name":"/app/src/a.test.ts","startTime":1000,"endTime":1800,
+            "assertionResults":[{"fullName":"a works","status":"passed","duration":5},
+                                {"fullName":"a fails","status":"failed","failureMessages":["boom"]}]}]}"#;
+        assert_eq!(detect_format(json), Some("jest"));
+        let report = parse_jest_json(json).unwrap();
+        assert_eq!(report.cases.len(), 2);
+        assert_eq!(report.cases[1].status, "failed");
+        assert_eq!(report.cases[1].error_message.as_deref(), Some("boom"));
+        assert_eq!(report.duration_ms, Some(800));
+    }
+
+    #[test]
+    fn test_parse_cargo_text() {
+        let output = "running 2 tests\ntest db::tests::opens ... ok\ntest db::tests::migrates ... FAILED\n\nfailures:\n\n---- db::tests::migrates stdout ----\nassertion failed: ok\n\nfailures:\n    db::tests::migrates\n\ntest result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.25s\n";
+        let report = parse_cargo_text(output);
+        assert_eq!(report.cases.len(), 2);
+        assert_eq!(report.cases[1].status, "failed");
+        assert_eq!(
+            report.cases[1].error_message.as_deref(),
+            Some("assertion failed: ok")
+        );
+        assert_eq!(report.duration_ms, Some(250));
+    }
+
+    #[test]
+    fn test_detect_framework_and_reporter() {
+        assert_eq!(detect_framework("npx vitest run", None), Some("vitest"));
+        assert_eq!(detect_framework("npm test", Some("Jest 29")), Some("jest"));
+        assert_eq!(detect_framework("cargo test --lib", None), Some("cargo"));
+        assert_eq!(detect_framework("make check", None), None);
+
+        let path = Path::new("/tmp/report");
+        assert_eq!(
+            with_reporter("pytest -q", "pytest", path).as_deref(),
+            Some("pytest -q --junitxml='/tmp/report'")
+        );
+        assert_eq!(with_reporter("npm test", "jest", path), None);
+        assert_eq!(with_reporter("npx vitest run && echo done", "vitest", path), None);
+    }
+
+    #[test]
+    fn test_quarantine_matching_and_flake_rate() {
+        let case = |file: Option<&str>| ParsedTestCase {
+            name: "retries on 503".to_string(),
+            file: file.map(String::from),
+            status: "failed",
+            duration_ms: 0,
+            error_message: None,
+        };
+        let any_file = vec![("retries on 503".to_string(), None)];
+        let one_file = vec![("retries on 503".to_string(), Some("src/http.test.ts".to_string()))];
+        assert!(is_quarantined(&any_file, &case(Some("src/other.test.ts"))));
+        assert!(is_quarantined(&one_file, &case(Some("src/http.test.ts"))));
+        assert!(!is_quarantined(&one_file, &case(Some("src/other.test.ts"))));
+
+        assert_eq!(flake_rate(0, 1), 0.0);
+        assert_eq!(flake_rate(2, 5), 0.5);
+    }
+}
@@ -204,6 +204,16 @@ impl Database {
         &self.conn
     }
 
+    /// In-memory database with the full schema and migrations, for tests
+    #[cfg(test)]
+    pub(crate) fn open_in_memory() -> Self {
+        let conn = Connection::open_in_memory().expect("open in-memory database");
+        conn.pragma_update(None, "foreign_keys", "ON").expect("enable foreign keys");
+        let db = Self { conn };
+        db.initialize_schema().expect("initialize schema");
+        db
+    }
+
     fn initialize_schema(&self) -> SqliteResult<()> {
         // 1. Create tables (IF NOT EXISTS -- safe for existing DBs)
         self.conn.execute_batch(SCHEMA)?;
@@ -744,6 +754,261 @@ impl Database {
             self.record_migration("gsd_table_column_fixes")?;
         }
 
+        // Migration: Add 'deleted_at' column to knowledge for soft deletes (trash)
+        if !self.migration_applied("add_deleted_at_to_knowledge") {
+            let has_deleted_at: bool = self
+                .conn
+                .prepare("SELECT deleted_at FROM knowledge LIMIT 1")
+                .is_ok();
+            if !has_deleted_at {
+                tracing::info!("Running migration: Adding 'deleted_at' column to knowledge table");
+                self.conn
+                    .execute("ALTER TABLE knowledge ADD COLUMN deleted_at TEXT", [])?;
+            }
+            self.record_migration("add_deleted_at_to_knowledge")?;
+        }
+
+        // Migration: Drop the hard-coded category CHECK on knowledge so projects can
+        // define their own categories. SQLite can't alter constraints, so rebuild the
+        // table, keeping rowids stable for the knowledge_fts content table.
+        if !self.migration_applied("relax_knowledge_category_check") {
+            let knowledge_sql: Option<String> = self.conn
+                .query_row(
+                    "SELECT sql FROM sqlite_master WHERE type='table' AND name='knowledge'",
+                    [],
+                    |row| row.get(0),
+                )
+                .ok();
+
+            if knowledge_sql.is_some_and(|sql| sql.contains("CHECK (category IN")) {
+                tracing::info!("Running migration: Relaxing knowledge category CHECK constraint");
+                // Disable FK checks so dropping the old table doesn't cascade to revisions/tags
+                self.conn.pragma_update(None, "foreign_keys", "OFF")?;
+                let result = self.conn.execute_batch(
+                    "BEGIN TRANSACTION;
+                     CREATE TABLE knowledge_new (
+                         id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+                         project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                         title TEXT NOT NULL,
+                         content TEXT NOT NULL,
+                         category TEXT NOT NULL DEFAULT 'learning',
+                         source TEXT,
+                         metadata TEXT,
+                         deleted_at TEXT,
+                         created_at TEXT DEFAULT (datetime('now')),
+                         updated_at TEXT DEFAULT (datetime('now'))
+                     );
+                     INSERT INTO knowledge_new (rowid, id, project_id, title, content, category, source, metadata, deleted_at, created_at, updated_at)
+                         SELECT rowid, id, project_id, title, content, COALESCE(category, 'learning'), source, metadata, deleted_at, created_at, updated_at
+                         FROM knowledge;
+                     DROP TABLE knowledge;
+                     ALTER TABLE knowledge_new RENAME TO knowledge;
+                     COMMIT;",
+                );
+                if result.is_err() {
+                    let _ = self.conn.execute_batch("ROLLBACK");
+                }
+                self.conn.pragma_update(None, "foreign_keys", "ON")?;
+                result?;
+                tracing::info!("Knowledge category migration complete");
+            }
+            self.record_migration("relax_knowledge_category_check")?;
+        }
+
+        // Migration: Content hashes (and a source path for decisions) so entries
+        // extracted from GSD summaries can be deduplicated across syncs
+        if !self.migration_applied("add_content_hash_columns") {
+            let columns: &[(&str, &str)] = &[
+                ("knowledge", "content_hash"),
+                ("decisions", "source"),
+                ("decisions", "content_hash"),
+            ];
+            for (table, column) in columns {
+                let has_column = self
+                    .conn
+                    .prepare(&format!("SELECT {} FROM {} LIMIT 1", column, table))
+                    .is_ok();
+                if !has_column {
+                    tracing::info!("Running migration: Adding '{}' column to {} table", column, table);
+                    self.conn.execute(
+                        &format!("ALTER TABLE {} ADD COLUMN {} TEXT", table, column),
+                        [],
+                    )?;
+                }
+            }
+            self.record_migration("add_content_hash_columns")?;
+        }
+
+        // Migration: Token breakdown and transcript attribution columns on costs
+        if !self.migration_applied("add_cost_transcript_columns") {
+            let columns: &[(&str, &str)] = &[
+                ("cache_creation_tokens", "INTEGER DEFAULT 0"),
+                ("cache_read_tokens", "INTEGER DEFAULT 0"),
+                ("plan", "TEXT"),
+                ("session_id", "TEXT"),
+                ("message_id", "TEXT"),
+            ];
+            for (column, definition) in columns {
+                let has_column = self
+                    .conn
+                    .prepare(&format!("SELECT {} FROM costs LIMIT 1", column))
+                    .is_ok();
+                if !has_column {
+                    tracing::info!("Running migration: Adding '{}' column to costs table", column);
+                    self.conn.execute(
+                        &format!("ALTER TABLE costs ADD COLUMN {} {}", column, definition),
+                        [],
+                    )?;
+                }
+            }
+            self.record_migration("add_cost_transcript_columns")?;
+        }
+
+        // Migration: Threshold evaluation state on cost_thresholds
+        if !self.migration_applied("add_cost_threshold_state_columns") {
+            let columns: &[(&str, &str)] = &[
+                ("interrupt_on_stop", "INTEGER DEFAULT 0"),
+                ("use_global", "INTEGER DEFAULT 0"),
+                ("last_level", "TEXT"),
+                ("stop_triggered_at", "TEXT"),
+                ("acknowledged_at", "TEXT"),
+            ];
+            for (column, definition) in columns {
+                let has_column = self
+                    .conn
+                    .prepare(&format!("SELECT {} FROM cost_thresholds LIMIT 1", column))
+                    .is_ok();
+                if !has_column {
+                    tracing::info!("Running migration: Adding '{}' column to cost_thresholds table", column);
+                    self.conn.execute(
+                        &format!("ALTER TABLE cost_thresholds ADD COLUMN {} {}", column, definition),
+                        [],
+                    )?;
+                }
+            }
+            self.record_migration("add_cost_threshold_state_columns")?;
+        }
+
+        // Migration: Commit (or worktree state) each test run was recorded against
+        if !self.migration_applied("add_test_run_commit_column") {
+            let has_column = self
+                .conn
+                .prepare("SELECT commit_sha FROM test_runs LIMIT 1")
+                .is_ok();
+            if !has_column {
+                tracing::info!("Running migration: Adding 'commit_sha' column to test_runs table");
+                self.conn
+                    .execute("ALTER TABLE test_runs ADD COLUMN commit_sha TEXT", [])?;
+            }
+            self.record_migration("add_test_run_commit_column")?;
+        }
+
+        // Migration: Pass/fail flip tracking and quarantine on flaky_tests
+        if !self.migration_applied("add_flaky_test_tracking_columns") {
+            let columns: &[(&str, &str)] = &[
+                ("flip_count", "INTEGER DEFAULT 0"),
+                ("same_commit_flips", "INTEGER DEFAULT 0"),
+                ("last_flip_commit", "TEXT"),
+                ("last_status", "TEXT"),
+                ("quarantined", "INTEGER DEFAULT 0"),
+                ("quarantined_at", "TEXT"),
+                ("quarantine_reason", "TEXT"),
+            ];
+            for (column, definition) in columns {
+                let has_column = self
+                    .conn
+                    .prepare(&format!("SELECT {} FROM flaky_tests LIMIT 1", column))
+                    .is_ok();
+                if !has_column {
+                    tracing::info!("Running migration: Adding '{}' column to flaky_tests table", column);
+                    self.conn.execute(
+                        &format!("ALTER TABLE flaky_tests ADD COLUMN {} {}", column, definition),
+                        [],
+                    )?;
+                }
+            }
+            self.record_migration("add_flaky_test_tracking_columns")?;
+        }
+
+        // Migration: Per-hook timeout and stop-on-failure for the auto-command runner
+        if !self.migration_applied("add_auto_command_execution_columns") {
+            let columns: &[(&str, &str)] = &[
+                ("timeout_secs", "INTEGER DEFAULT 300"),
+                ("stop_on_failure", "INTEGER DEFAULT 0"),
+            ];
+            for (column, definition) in columns {
+                let has_column = self
+                    .conn
+                    .prepare(&format!("SELECT {} FROM auto_commands LIMIT 1", column))
+                    .is_ok();
+                if !has_column {
+                    tracing::info!("Running migration: Adding '{}' column to auto_commands table", column);
+                    self.conn.execute(
+                        &format!("ALTER TABLE auto_commands ADD COLUMN {} {}", column, definition),
+                        [],
+                    )?;
+                }
+            }
+            self.record_migration("add_auto_command_execution_columns")?;
+        }
+
+        // Migration: Exit code, duration and cwd for commands recorded by shell integration
+        if !self.migration_applied("add_command_history_shell_columns") {
+            let columns: &[(&str, &str)] = &[
+                ("exit_code", "INTEGER"),
+                ("duration_ms", "INTEGER"),
+                ("cwd", "TEXT"),
+                ("session_id", "TEXT"),
+                ("started_at", "TEXT"),
+            ];
+            for (column, definition) in columns {
+                let has_column = self
+                    .conn
+                    .prepare(&format!("SELECT {} FROM command_history LIMIT 1", column))
+                    .is_ok();
+                if !has_column {
+                    tracing::info!("Running migration: Adding '{}' column to command_history table", column);
+                    self.conn.execute(
+                        &format!("ALTER TABLE command_history ADD COLUMN {} {}", column, definition),
+                        [],
+                    )?;
+                }
+            }
+            self.record_migration("add_command_history_shell_columns")?;
+        }
+
+        // Migration: Command for terminal tabs that run one (respawned on restore)
+        if !self.migration_applied("add_terminal_session_command") {
+            let has_command = self
+                .conn
+                .prepare("SELECT command FROM terminal_sessions LIMIT 1")
+                .is_ok();
+            if !has_command {
+                tracing::info!("Running migration: Adding 'command' column to terminal_sessions table");
+                self.conn
+                    .execute("ALTER TABLE terminal_sessions ADD COLUMN command TEXT", [])?;
+            }
+            self.record_migration("add_terminal_session_command")?;
+        }
+
+        // Migration: Profiles a terminal tab was explicitly started with
+        if !self.migration_applied("add_terminal_session_profile_columns") {
+            for column in ["env_profile_id", "terminal_profile_id"] {
+                let has_column = self
+                    .conn
+                    .prepare(&format!("SELECT {} FROM terminal_sessions LIMIT 1", column))
+                    .is_ok();
+                if !has_column {
+                    tracing::info!("Running migration: Adding '{}' column to terminal_sessions table", column);
+                    self.conn.execute(
+                        &format!("ALTER TABLE terminal_sessions ADD COLUMN {} TEXT", column),
+                        [],
+                    )?;
+                }
+            }
+            self.record_migration("add_terminal_session_profile_columns")?;
+        }
+
         tracing::info!("Database migrations complete");
         Ok(())
     }
@@ -774,10 +1039,33 @@ CREATE TABLE IF NOT EXISTS costs (
     model TEXT NOT NULL,
     input_tokens INTEGER DEFAULT 0,
     output_tokens INTEGER DEFAULT 0,
+    cache_creation_tokens INTEGER DEFAULT 0,
+    cache_read_tokens INTEGER DEFAULT 0,
     total_cost REAL DEFAULT 0,
+    plan TEXT,
+    session_id TEXT,
+    message_id TEXT,
     created_at TEXT DEFAULT (datetime('now'))
 );
 
+-- Per-model token prices (USD per million tokens); `model` matches as a substring
+CREATE TABLE IF NOT EXISTS model_prices (
+    model TEXT PRIMARY KEY,
+    input_per_mtok REAL NOT NULL,
+    output_per_mtok REAL NOT NULL,
+    cache_write_per_mtok REAL NOT NULL,
+    cache_read_per_mtok REAL NOT NULL,
+    updated_at TEXT DEFAULT (datetime('now'))
+);
+
+-- Read offsets for Claude session transcripts already ingested into costs
+CREATE TABLE IF NOT EXISTS cost_ingest_state (
+    file_path TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    byte_offset INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT DEFAULT (datetime('now'))
+);
+
 -- Roadmaps table (formerly flight_plans)
 CREATE TABLE IF NOT EXISTS roadmaps (
     id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
@@ -864,6 +1152,8 @@ CREATE TABLE IF NOT EXISTS decisions (
     question TEXT NOT NULL,
     answer TEXT NOT NULL,
     reasoning TEXT,
+    source TEXT,
+    content_hash TEXT,
     created_at TEXT DEFAULT (datetime('now'))
 );
 
@@ -887,23 +1177,106 @@ CREATE TABLE IF NOT EXISTS cost_thresholds (
     stop_cost REAL DEFAULT 50.0,
     enabled INTEGER DEFAULT 1,
     alert_acknowledged INTEGER DEFAULT 0,
+    interrupt_on_stop INTEGER DEFAULT 0,
+    use_global INTEGER DEFAULT 0,
+    last_level TEXT,
+    stop_triggered_at TEXT,
+    acknowledged_at TEXT,
     created_at TEXT DEFAULT (datetime('now')),
     updated_at TEXT DEFAULT (datetime('now'))
 );
 
+-- Notification center entries (bell icon)
+CREATE TABLE IF NOT EXISTS notifications (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    project_id TEXT REFERENCES projects(id) ON DELETE CASCADE,
+    notification_type TEXT NOT NULL,
+    title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    link TEXT,
+    read INTEGER DEFAULT 0,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
 -- Knowledge table for persistent memory
 CREATE TABLE IF NOT EXISTS knowledge (
     id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
     project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
     title TEXT NOT NULL,
     content TEXT NOT NULL,
-    category TEXT DEFAULT 'learning' CHECK (category IN ('learning', 'decision', 'reference', 'fact')),
+    category TEXT NOT NULL DEFAULT 'learning',
     source TEXT,
     metadata TEXT,
+    deleted_at TEXT,
+    content_hash TEXT,
     created_at TEXT DEFAULT (datetime('now')),
     updated_at TEXT DEFAULT (datetime('now'))
 );
 
+-- Saved global searches (query + scope filters) for the command palette
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    filters TEXT NOT NULL DEFAULT '{}',
+    last_run_at TEXT,
+    last_result_keys TEXT,
+    created_at TEXT DEFAULT (datetime('now')),
+    updated_at TEXT DEFAULT (datetime('now'))
+);
+
+-- Recent global searches, one row per distinct query + filters
+CREATE TABLE IF NOT EXISTS search_history (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    query TEXT NOT NULL,
+    filters TEXT NOT NULL DEFAULT '{}',
+    result_count INTEGER DEFAULT 0,
+    search_count INTEGER DEFAULT 1,
+    last_searched_at TEXT DEFAULT (datetime('now')),
+    UNIQUE(query, filters)
+);
+
+-- Knowledge categories defined per project (in addition to the built-in four)
+CREATE TABLE IF NOT EXISTS knowledge_custom_categories (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at TEXT DEFAULT (datetime('now')),
+    UNIQUE(project_id, name)
+);
+
+-- Knowledge tags (per project) and the many-to-many link to entries
+CREATE TABLE IF NOT EXISTS knowledge_tags (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    created_at TEXT DEFAULT (datetime('now')),
+    UNIQUE(project_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS knowledge_tag_links (
+    knowledge_id TEXT NOT NULL REFERENCES knowledge(id) ON DELETE CASCADE,
+    tag_id TEXT NOT NULL REFERENCES knowledge_tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (knowledge_id, tag_id)
+);
+
+-- Knowledge revisions (snapshot of an entry taken before each update, delete or restore)
+CREATE TABLE IF NOT EXISTS knowledge_revisions (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    knowledge_id TEXT NOT NULL REFERENCES knowledge(id) ON DELETE CASCADE,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    revision_number INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    category TEXT,
+    source TEXT,
+    metadata TEXT,
+    change_type TEXT NOT NULL CHECK (change_type IN ('update', 'delete', 'restore')),
+    created_at TEXT DEFAULT (datetime('now')),
+    UNIQUE(knowledge_id, revision_number)
+);
+
 -- Test runs table
 CREATE TABLE IF NOT EXISTS test_runs (
     id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
@@ -918,6 +1291,7 @@ CREATE TABLE IF NOT EXISTS test_runs (
     coverage_lines REAL,
     coverage_branches REAL,
     coverage_functions REAL,
+    commit_sha TEXT,
     started_at TEXT DEFAULT (datetime('now')),
     completed_at TEXT,
     created_at TEXT DEFAULT (datetime('now'))
@@ -936,6 +1310,22 @@ CREATE TABLE IF NOT EXISTS test_results (
     created_at TEXT DEFAULT (datetime('now'))
 );
 
+-- Per-file coverage imported from lcov / cobertura reports
+CREATE TABLE IF NOT EXISTS test_coverage_files (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    test_run_id TEXT NOT NULL REFERENCES test_runs(id) ON DELETE CASCADE,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    file_path TEXT NOT NULL,
+    lines_total INTEGER DEFAULT 0,
+    lines_covered INTEGER DEFAULT 0,
+    branches_total INTEGER DEFAULT 0,
+    branches_covered INTEGER DEFAULT 0,
+    functions_total INTEGER DEFAULT 0,
+    functions_covered INTEGER DEFAULT 0,
+    created_at TEXT DEFAULT (datetime('now')),
+    UNIQUE(test_run_id, file_path)
+);
+
 -- Flaky tests table
 CREATE TABLE IF NOT EXISTS flaky_tests (
     id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
@@ -945,7 +1335,14 @@ CREATE TABLE IF NOT EXISTS flaky_tests (
     total_runs INTEGER DEFAULT 0,
     failure_count INTEGER DEFAULT 0,
     flake_rate REAL DEFAULT 0,
+    flip_count INTEGER DEFAULT 0,
+    same_commit_flips INTEGER DEFAULT 0,
+    last_flip_commit TEXT,
+    last_status TEXT,
     last_failure TEXT,
+    quarantined INTEGER DEFAULT 0,
+    quarantined_at TEXT,
+    quarantine_reason TEXT,
     first_seen TEXT DEFAULT (datetime('now')),
     UNIQUE(project_id, test_name, test_file)
 );
@@ -968,6 +1365,11 @@ CREATE TABLE IF NOT EXISTS command_history (
     project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
     command TEXT NOT NULL,
     source TEXT DEFAULT 'manual',
+    exit_code INTEGER,
+    duration_ms INTEGER,
+    cwd TEXT,
+    session_id TEXT,
+    started_at TEXT,
     created_at TEXT DEFAULT (datetime('now'))
 );
 
@@ -993,6 +1395,21 @@ CREATE TABLE IF NOT EXISTS script_favorites (
     UNIQUE(project_id, script_id)
 );
 
+-- Open terminal tabs, restored on launch
+CREATE TABLE IF NOT EXISTS terminal_sessions (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    tab_name TEXT NOT NULL,
+    tab_type TEXT NOT NULL,
+    working_directory TEXT NOT NULL,
+    sort_order INTEGER DEFAULT 0,
+    tmux_session TEXT,
+    command TEXT,
+    env_profile_id TEXT,
+    terminal_profile_id TEXT,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
 -- Auto commands table
 CREATE TABLE IF NOT EXISTS auto_commands (
     id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
@@ -1003,10 +1420,90 @@ CREATE TABLE IF NOT EXISTS auto_commands (
     enabled INTEGER DEFAULT 1,
     order_index INTEGER DEFAULT 0,
     preset TEXT,
+    timeout_secs INTEGER DEFAULT 300,
+    stop_on_failure INTEGER DEFAULT 0,
+    created_at TEXT DEFAULT (datetime('now')),
+    updated_at TEXT DEFAULT (datetime('now'))
+);
+
+-- Per-project environment profiles applied to new PTY sessions
+CREATE TABLE IF NOT EXISTS env_profiles (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    is_default INTEGER DEFAULT 0,
+    vars_json TEXT NOT NULL DEFAULT '[]',
     created_at TEXT DEFAULT (datetime('now')),
+    updated_at TEXT DEFAULT (datetime('now')),
+    UNIQUE(project_id, name)
+);
+
+-- Per-project terminal profiles: which shell to start and how
+CREATE TABLE IF NOT EXISTS terminal_profiles (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    shell_path TEXT,
+    args_json TEXT NOT NULL DEFAULT '[]',
+    load_rc_files INTEGER DEFAULT 0,
+    env_json TEXT NOT NULL DEFAULT '{}',
+    initial_command TEXT,
+    cwd TEXT,
+    is_default INTEGER DEFAULT 0,
+    created_at TEXT DEFAULT (datetime('now')),
+    updated_at TEXT DEFAULT (datetime('now')),
+    UNIQUE(project_id, name)
+);
+
+-- Background Claude/shell jobs run through the PTY backend
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK(kind IN ('claude', 'shell')),
+    command TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued'
+        CHECK(status IN ('queued', 'running', 'succeeded', 'failed', 'cancelled')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_retries INTEGER NOT NULL DEFAULT 0,
+    exit_code INTEGER,
+    error TEXT,
+    log TEXT,
+    session_id TEXT,
+    tmux_name TEXT,
+    created_at TEXT DEFAULT (datetime('now')),
+    started_at TEXT,
+    finished_at TEXT,
     updated_at TEXT DEFAULT (datetime('now'))
 );
 
+-- Vulnerability advisories imported from OSV dumps or a RustSec advisory-db clone
+CREATE TABLE IF NOT EXISTS advisories (
+    id TEXT NOT NULL,
+    ecosystem TEXT NOT NULL,
+    package TEXT NOT NULL,
+    title TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    url TEXT,
+    aliases TEXT,
+    affected TEXT NOT NULL,
+    source TEXT NOT NULL CHECK(source IN ('osv', 'rustsec')),
+    modified TEXT,
+    imported_at TEXT DEFAULT (datetime('now')),
+    PRIMARY KEY (id, ecosystem, package)
+);
+
+-- Every dependency audit run: per-ecosystem results plus the locked packages at the time
+CREATE TABLE IF NOT EXISTS dependency_audit_snapshots (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    results TEXT NOT NULL,
+    packages TEXT NOT NULL,
+    total INTEGER DEFAULT 0,
+    critical INTEGER DEFAULT 0,
+    high INTEGER DEFAULT 0,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
 -- Settings table
 CREATE TABLE IF NOT EXISTS settings (
     key TEXT PRIMARY KEY,
@@ -1197,6 +1694,14 @@ CREATE INDEX IF NOT EXISTS idx_roadmaps_project ON roadmaps(project_id);
 CREATE INDEX IF NOT EXISTS idx_cost_thresholds_project ON cost_thresholds(project_id);
 CREATE INDEX IF NOT EXISTS idx_knowledge_project ON knowledge(project_id);
 CREATE INDEX IF NOT EXISTS idx_knowledge_category ON knowledge(category);
+CREATE INDEX IF NOT EXISTS idx_knowledge_deleted ON knowledge(project_id, deleted_at);
+CREATE INDEX IF NOT EXISTS idx_knowledge_tag_links_tag ON knowledge_tag_links(tag_id);
+CREATE INDEX IF NOT EXISTS idx_knowledge_revisions_knowledge ON knowledge_revisions(knowledge_id, revision_number DESC);
+CREATE INDEX IF NOT EXISTS idx_knowledge_content_hash ON knowledge(project_id, content_hash);
+CREATE INDEX IF NOT EXISTS idx_decisions_content_hash ON decisions(project_id, content_hash);
+CREATE INDEX IF NOT EXISTS idx_search_history_last ON search_history(last_searched_at DESC);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_costs_message ON costs(project_id, message_id);
+CREATE INDEX IF NOT EXISTS idx_notifications_created ON notifications(created_at DESC);
 CREATE INDEX IF NOT EXISTS idx_test_runs_project ON test_runs(project_id);
 CREATE INDEX IF NOT EXISTS idx_test_runs_execution ON test_runs(execution_id);
 CREATE INDEX IF NOT EXISTS idx_test_runs_created ON test_runs(created_at);
@@ -1204,6 +1709,8 @@ CREATE INDEX IF NOT EXISTS idx_test_results_run ON test_results(test_run_id);
 CREATE INDEX IF NOT EXISTS idx_test_results_status ON test_results(status);
 CREATE INDEX IF NOT EXISTS idx_flaky_tests_project ON flaky_tests(project_id);
 CREATE INDEX IF NOT EXISTS idx_flaky_tests_rate ON flaky_tests(flake_rate);
+CREATE INDEX IF NOT EXISTS idx_test_runs_commit ON test_runs(project_id, commit_sha);
+CREATE INDEX IF NOT EXISTS idx_test_coverage_files_project ON test_coverage_files(project_id, file_path);
 CREATE INDEX IF NOT EXISTS idx_app_logs_level ON app_logs(level);
 CREATE INDEX IF NOT EXISTS idx_app_logs_source ON app_logs(source);
 CREATE INDEX IF NOT EXISTS idx_app_logs_project ON app_logs(project_id);
@@ -1216,6 +1723,12 @@ CREATE INDEX IF NOT EXISTS idx_snippets_category ON snippets(category);
 CREATE INDEX IF NOT EXISTS idx_script_favorites_project ON script_favorites(project_id);
 CREATE INDEX IF NOT EXISTS idx_auto_commands_project ON auto_commands(project_id);
 CREATE INDEX IF NOT EXISTS idx_auto_commands_hook ON auto_commands(hook_type);
+CREATE INDEX IF NOT EXISTS idx_env_profiles_project ON env_profiles(project_id);
+CREATE INDEX IF NOT EXISTS idx_terminal_profiles_project ON terminal_profiles(project_id);
+CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status, created_at);
+CREATE INDEX IF NOT EXISTS idx_jobs_project ON jobs(project_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_advisories_package ON advisories(ecosystem, package);
+CREATE INDEX IF NOT EXISTS idx_audit_snapshots_project ON dependency_audit_snapshots(project_id, created_at DESC);
 -- Composite indexes for common multi-column query patterns
 CREATE INDEX IF NOT EXISTS idx_activity_project_created ON activity_log(project_id, created_at DESC);
 CREATE INDEX IF NOT EXISTS idx_decisions_project_category ON decisions(project_id, category);
@@ -43,7 +43,7 @@ pub fn run() {
     builder
         .setup(move |app| {
             // Initialize database connection pool (1 writer + N readers)
-            let pool = DbPool::new(app.handle())?;
+            let pool = Arc::new(DbPool::new(app.handle())?);
 
             // Set up the tracing layer's DB connection (separate connection to avoid deadlocks)
             let app_data_dir = app.handle().path().app_data_dir().unwrap();
@@ -68,8 +68,20 @@ pub fn run() {
                     .map(|v| v == "true")
                     .unwrap_or(true);
 
+                let shell_integration: bool = db
+                    .conn()
+                    .query_row(
+                        "SELECT value FROM settings WHERE key = 'shell_integration'",
+                        [],
+                        |row| row.get::<_, String>(0),
+                    )
+                    .map(|v| v == "true")
+                    .unwrap_or(true);
+
                 // Initialize Terminal Manager (auto-detects tmux availability)
-                let terminal_manager = pty::TerminalManager::new(use_tmux);
+                let mut terminal_manager = pty::TerminalManager::new(use_tmux);
+                terminal_manager.set_shell_integration(shell_integration);
+                terminal_manager.set_command_sink(commands::terminal::command_recorder(pool.clone()));
 
                 tmux_status = serde_json::json!({
                     "available": terminal_manager.tmux_available,
@@ -81,7 +93,10 @@ pub fn run() {
                 if terminal_manager.tmux_available {
                     let known_names: Vec<String> = db
                         .conn()
-                        .prepare("SELECT tmux_session FROM terminal_sessions WHERE tmux_session IS NOT NULL")
+                        .prepare(
+                            "SELECT tmux_session FROM terminal_sessions WHERE tmux_session IS NOT NULL
+                             UNION SELECT tmux_name FROM jobs WHERE status = 'running' AND tmux_name IS NOT NULL",
+                        )
                         .and_then(|mut stmt| {
                             stmt.query_map([], |row| row.get::<_, String>(0))
                                 .map(|rows| rows.filter_map(|r| r.ok()).collect())
@@ -95,7 +110,6 @@ pub fn run() {
             }
 
             // Register the pool as managed state
-            let pool = Arc::new(pool);
             app.manage(pool.clone());
 
             // Emit tmux status event after app is set up
@@ -106,6 +120,19 @@ pub fn run() {
                 let _ = app_handle_tmux.emit("app:tmux-status", tmux_status_clone);
             });
 
+            // Tail Claude session transcripts into the costs table
+            commands::costs::spawn_cost_ingester(app.handle().clone(), pool.clone());
+
+            // Sample process trees under terminal sessions
+            let process_sampler: commands::pty::ProcessSamplerState = Default::default();
+            app.manage(process_sampler.clone());
+            commands::pty::spawn_process_monitor(app.handle().clone(), process_sampler);
+
+            // Run queued jobs and pick up ones left running by the last app run
+            let job_queue = Arc::new(commands::jobs::JobQueue::new(app_data_dir.join("jobs")));
+            app.manage(job_queue.clone());
+            commands::jobs::spawn_job_runner(app.handle().clone(), pool.clone(), job_queue);
+
             // Initialize file watcher manager
             let watcher_manager = commands::watcher::WatcherManager::new();
             app.manage(Arc::new(Mutex::new(watcher_manager)));
@@ -167,11 +194,22 @@ pub fn run() {
             commands::pty::pty_list_sessions,
             commands::pty::pty_is_active,
             commands::pty::pty_get_session_info,
+            commands::pty::pty_get_state,
+            commands::pty::pty_process_trees,
+            commands::pty::pty_kill_process,
+            commands::pty::pty_search,
             commands::pty::pty_active_count,
             commands::pty::pty_close_all,
             commands::pty::pty_attach,
             commands::pty::pty_check_tmux,
             commands::pty::pty_list_tmux,
+            commands::pty::pty_tmux_list_windows,
+            commands::pty::pty_tmux_split_pane,
+            commands::pty::pty_tmux_new_window,
+            commands::pty::pty_tmux_select_window,
+            commands::pty::pty_tmux_select_pane,
+            commands::pty::pty_tmux_capture_pane,
+            commands::pty::pty_tmux_kill,
             // Knowledge commands
             commands::knowledge::knowledge_store,
             commands::knowledge::knowledge_search,
@@ -181,11 +219,51 @@ pub fn run() {
             commands::knowledge::knowledge_delete,
             commands::knowledge::knowledge_categories,
             commands::knowledge::knowledge_import,
+            commands::knowledge::knowledge_list_categories,
+            commands::knowledge::knowledge_create_category,
+            commands::knowledge::knowledge_delete_category,
+            commands::knowledge::knowledge_list_tags,
+            commands::knowledge::knowledge_set_tags,
+            commands::knowledge::knowledge_delete_tag,
+            commands::knowledge::knowledge_list_revisions,
+            commands::knowledge::knowledge_diff_revisions,
+            commands::knowledge::knowledge_restore_revision,
+            commands::knowledge::knowledge_list_trash,
+            commands::knowledge::knowledge_restore_from_trash,
+            commands::knowledge::knowledge_empty_trash,
+            commands::knowledge::knowledge_export_vault,
+            commands::knowledge::knowledge_import_vault,
             commands::knowledge::create_knowledge_bookmark,
             commands::knowledge::list_knowledge_bookmarks,
             commands::knowledge::delete_knowledge_bookmark,
+            // Cost tracking commands
+            commands::costs::ingest_claude_costs,
+            commands::costs::list_model_prices,
+            commands::costs::set_model_price,
+            commands::costs::delete_model_price,
+            commands::costs::get_cost_thresholds,
+            commands::costs::set_cost_thresholds,
+            commands::costs::acknowledge_cost_alert,
+            commands::costs::get_cost_report,
+            // Test run commands
+            commands::test_runs::record_test_run,
+            commands::test_runs::list_test_runs,
+            commands::test_runs::get_test_results,
+            commands::test_runs::list_flaky_tests,
+            commands::test_runs::list_quarantined_tests,
+            commands::test_runs::set_test_quarantine,
+            commands::coverage::import_coverage_report,
+            commands::coverage::get_coverage_trend,
+            commands::coverage::get_coverage_delta,
             // Global search
             commands::search::global_search,
+            commands::search::list_search_history,
+            commands::search::clear_search_history,
+            commands::search::list_saved_searches,
+            commands::search::create_saved_search,
+            commands::search::update_saved_search,
+            commands::search::delete_saved_search,
+            commands::search::run_saved_search,
             // App log commands
             commands::logs::get_app_logs,
             commands::logs::get_app_log_stats,
@@ -228,6 +306,7 @@ pub fn run() {
             commands::terminal::reorder_script_favorites,
             commands::terminal::save_terminal_sessions,
             commands::terminal::restore_terminal_sessions,
+            commands::terminal::restore_workspace,
             // Snippet & auto-command commands
             commands::snippets::list_snippets,
             commands::snippets::create_snippet,
@@ -239,11 +318,20 @@ pub fn run() {
             commands::snippets::delete_auto_command,
             commands::snippets::toggle_auto_command,
             commands::snippets::get_auto_command_presets,
+            commands::auto_commands::run_auto_commands,
             // Dependency scanner commands
             commands::dependencies::get_dependency_status,
             commands::dependencies::invalidate_dependency_cache,
             commands::dependencies::run_dependency_audit,
             commands::dependencies::get_outdated_packages,
+            commands::lockfiles::get_dependency_inventory,
+            commands::lockfiles::export_sbom,
+            commands::advisories::import_advisories,
+            commands::advisories::get_advisory_db_status,
+            commands::advisories::clear_advisories,
+            commands::advisories::run_offline_audit,
+            commands::audit_history::list_audit_snapshots,
+            commands::audit_history::diff_audit_snapshots,
             // File watcher commands
             commands::watcher::watch_project_files,
             commands::watcher::unwatch_project_files,
@@ -276,6 +364,7 @@ pub fn run() {
             commands::gsd::gsd_list_all_todos,
             commands::gsd::gsd_list_validations,
             commands::gsd::gsd_get_validation_by_phase,
+            commands::gsd::gsd_run_validation,
             commands::gsd::gsd_list_uat_results,
             commands::gsd::gsd_get_uat_by_phase,
             // Secrets / OS keychain commands
@@ -285,6 +374,26 @@ pub fn run() {
             commands::secrets::list_secret_keys,
             commands::secrets::get_predefined_secret_keys,
             commands::secrets::has_secret,
+            // Env profile commands
+            commands::env_profiles::list_env_profiles,
+            commands::env_profiles::create_env_profile,
+            commands::env_profiles::update_env_profile,
+            commands::env_profiles::delete_env_profile,
+            // Terminal profile commands
+            commands::terminal_profiles::list_terminal_profiles,
+            commands::terminal_profiles::create_terminal_profile,
+            commands::terminal_profiles::update_terminal_profile,
+            commands::terminal_profiles::delete_terminal_profile,
+            // Job queue commands
+            commands::jobs::enqueue_job,
+            commands::jobs::list_jobs,
+            commands::jobs::get_job_log,
+            commands::jobs::cancel_job,
+            commands::jobs::retry_job,
+            commands::jobs::delete_job,
+            // Session state detection commands
+            commands::session_state::get_session_state_config,
+            commands::session_state::update_session_state_config,
         ])
         .run(tauri::generate_context!())
         .expect("error while running Track Your Shit");
@@ -108,6 +108,29 @@ pub struct Settings {
     pub debug_logging: bool,
     // Terminal persistence
     pub use_tmux: bool,
+    // Terminal shell integration (OSC 133/7 command tracking; native sessions only)
+    #[serde(default = "default_shell_integration")]
+    pub shell_integration: bool,
+    // GSD sync
+    #[serde(default)]
+    pub gsd_extract_knowledge: bool,
+    // Job queue concurrency
+    #[serde(default = "default_job_max_concurrent")]
+    pub job_max_concurrent: u32,
+    #[serde(default = "default_job_max_per_project")]
+    pub job_max_per_project: u32,
+}
+
+fn default_shell_integration() -> bool {
+    true
+}
+
+fn default_job_max_concurrent() -> u32 {
+    2
+}
+
+fn default_job_max_per_project() -> u32 {
+    1
 }
 
 impl Default for Settings {
@@ -140,6 +163,12 @@ impl Default for Settings {
             debug_logging: false,
             // Terminal persistence defaults
             use_tmux: true,
+            shell_integration: true,
+            // GSD sync defaults
+            gsd_extract_knowledge: false,
+            // Job queue defaults
+            job_max_concurrent: default_job_max_concurrent(),
+            job_max_per_project: default_job_max_per_project(),
         }
     }
 }
@@ -239,6 +268,11 @@ pub struct Knowledge {
     pub category: String,
     pub source: Option<String>,
     pub metadata: Option<serde_json::Value>,
+    /// Set when the entry has been moved to the trash (soft delete)
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -250,6 +284,8 @@ pub struct KnowledgeInput {
     pub category: Option<String>,
     pub source: Option<String>,
     pub metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -259,10 +295,95 @@ pub struct KnowledgeSearchResult {
     pub title: String,
     pub content: String,
     pub category: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub relevance_score: Option<f64>,
     pub created_at: String,
 }
 
+/// A knowledge category available to a project (built-in or project-defined)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeCategory {
+    pub name: String,
+    pub description: Option<String>,
+    pub builtin: bool,
+    pub entry_count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeTag {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub entry_count: i32,
+}
+
+/// Snapshot of a knowledge entry as it was before an update, delete or restore
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeRevision {
+    pub id: String,
+    pub knowledge_id: String,
+    pub project_id: String,
+    pub revision_number: i32,
+    pub title: String,
+    pub content: String,
+    pub category: Option<String>,
+    pub source: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    pub change_type: String, // "update" | "delete" | "restore"
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeDiffLine {
+    pub op: String, // "equal" | "insert" | "delete"
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeRevisionDiff {
+    pub knowledge_id: String,
+    /// Revision number on the left side of the diff
+    pub from_revision: i32,
+    /// Revision number on the right side, or None for the current entry
+    pub to_revision: Option<i32>,
+    pub from_title: String,
+    pub to_title: String,
+    pub lines: Vec<KnowledgeDiffLine>,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeImportResult {
+    pub imported: i32,
+    /// Entries rejected for an unknown category or a failed insert
+    pub skipped: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeVaultExportResult {
+    pub directory: String,
+    pub files_written: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeVaultConflict {
+    pub knowledge_id: String,
+    pub file_path: String,
+    pub title: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeVaultImportResult {
+    pub created: i32,
+    pub updated: i32,
+    pub unchanged: i32,
+    pub conflicts: Vec<KnowledgeVaultConflict>,
+    pub errors: Vec<String>,
+}
+
 // ============================================================
 // Application Logging Models
 // ============================================================
@@ -369,6 +490,50 @@ pub struct KnowledgeSearchResultItem {
     pub project_name: String,
 }
 
+/// Scope filters for global search. `result_types` accepts
+/// "project", "phase", "decision" and "knowledge"; dates are YYYY-MM-DD.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchFilters {
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub result_types: Option<Vec<String>>,
+    #[serde(default)]
+    pub date_from: Option<String>,
+    #[serde(default)]
+    pub date_to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: String,
+    pub name: String,
+    pub query: String,
+    pub filters: SearchFilters,
+    pub last_run_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearchRunResult {
+    pub saved_search: SavedSearch,
+    pub results: GlobalSearchResults,
+    /// Result keys ("<type>:<id>") not returned by the previous run
+    pub new_result_keys: Vec<String>,
+    pub new_count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHistoryEntry {
+    pub id: String,
+    pub query: String,
+    pub filters: SearchFilters,
+    pub result_count: i32,
+    pub search_count: i32,
+    pub last_searched_at: String,
+}
+
 // ============================================================
 // Enriched Project Card Models
 // ============================================================
@@ -463,6 +628,16 @@ pub struct CommandHistoryEntry {
     pub project_id: String,
     pub command: String,
     pub source: String,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub duration_ms: Option<i64>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<String>,
     pub created_at: String,
 }
 
@@ -505,6 +680,12 @@ pub struct AutoCommand {
     pub enabled: bool,
     pub order_index: i32,
     pub preset: Option<String>,
+    #[serde(default)]
+    pub timeout_secs: i32,
+    /// Skip the remaining hooks in the chain when this one fails (for `pre`
+    /// hooks this also keeps the Claude session from starting)
+    #[serde(default)]
+    pub stop_on_failure: bool,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -515,6 +696,51 @@ pub struct AutoCommandInput {
     pub command: String,
     pub hook_type: Option<String>,
     pub preset: Option<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<i32>,
+    #[serde(default)]
+    pub stop_on_failure: Option<bool>,
+}
+
+/// Outcome of a single auto-command hook run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoCommandResult {
+    pub auto_command_id: String,
+    pub project_id: String,
+    pub label: String,
+    pub command: String,
+    pub hook_type: String,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub success: bool,
+    pub duration_ms: i64,
+    pub output_tail: Vec<String>,
+    /// Set when the hook ran a recognised test runner and results were recorded
+    pub test_run_id: Option<String>,
+    /// Failing tests ignored because they are quarantined
+    pub quarantined_failures: Vec<String>,
+}
+
+/// Outcome of running a project's pre or post hook chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoCommandChainResult {
+    pub project_id: String,
+    pub hook_type: String,
+    /// PTY session that triggered the chain, if any
+    pub session_id: Option<String>,
+    pub success: bool,
+    /// A failing hook with stop_on_failure skipped the rest of the chain
+    pub stopped: bool,
+    pub results: Vec<AutoCommandResult>,
+}
+
+/// A line of hook output streamed while an auto-command runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoCommandOutputLine {
+    pub project_id: String,
+    pub auto_command_id: String,
+    pub stream: String,
+    pub line: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -525,6 +751,148 @@ pub struct AutoCommandPreset {
     pub hook_type: String,
 }
 
+/// One variable of an env profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvProfileVar {
+    /// Environment variable name set in the session
+    pub name: String,
+    /// "secret" (value is a keychain key) or "literal"
+    pub source: String,
+    pub value: String,
+}
+
+/// Named set of environment variables applied to a project's PTY sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvProfile {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    /// Applied to sessions that don't pick a profile
+    pub is_default: bool,
+    pub vars: Vec<EnvProfileVar>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvProfileInput {
+    pub project_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub is_default: Option<bool>,
+    pub vars: Vec<EnvProfileVar>,
+}
+
+/// How a project's terminals are started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalProfile {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    /// Shell binary; None uses `$SHELL`
+    pub shell_path: Option<String>,
+    pub args: Vec<String>,
+    /// Load the user's rc/profile files (nvm, pyenv, direnv...) instead of a clean shell
+    pub load_rc_files: bool,
+    pub env: std::collections::BTreeMap<String, String>,
+    /// Typed into interactive sessions once the shell starts
+    pub initial_command: Option<String>,
+    /// Working directory relative to the project root
+    pub cwd: Option<String>,
+    /// Used by sessions that don't pick a profile
+    pub is_default: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalProfileInput {
+    pub project_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub shell_path: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub load_rc_files: bool,
+    #[serde(default)]
+    pub env: std::collections::BTreeMap<String, String>,
+    #[serde(default)]
+    pub initial_command: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub is_default: Option<bool>,
+}
+
+/// A queued Claude or shell command for a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub project_id: String,
+    /// "claude" (command is the prompt) or "shell"
+    pub kind: String,
+    pub command: String,
+    /// queued, running, succeeded, failed or cancelled
+    pub status: String,
+    pub attempts: i32,
+    pub max_retries: i32,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    pub session_id: Option<String>,
+    pub tmux_name: Option<String>,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInput {
+    pub project_id: String,
+    pub kind: String,
+    pub command: String,
+    #[serde(default)]
+    pub max_retries: Option<i32>,
+}
+
+/// Output patterns and timeouts for classifying Claude session state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionStateConfig {
+    /// Prompt patterns checked once output settles (e.g. permission questions)
+    pub awaiting_input_patterns: Vec<String>,
+    pub error_patterns: Vec<String>,
+    pub finished_patterns: Vec<String>,
+    /// Seconds without output before a session counts as idle
+    pub idle_timeout_secs: u64,
+    /// Milliseconds output must settle before a prompt match counts
+    pub input_settle_ms: u64,
+}
+
+impl Default for SessionStateConfig {
+    fn default() -> Self {
+        Self {
+            awaiting_input_patterns: vec![
+                r"(?i)do you want to (proceed|continue|make this edit|create|allow)".to_string(),
+                r"❯\s*1\.\s*Yes".to_string(),
+                r"(?i)\(y/n\)".to_string(),
+                r"(?i)press enter to continue".to_string(),
+            ],
+            error_patterns: vec![
+                r"API Error: \d+".to_string(),
+                r"(?i)rate limit(ed| exceeded)".to_string(),
+                r"overloaded_error".to_string(),
+                r"(?i)credit balance is too low".to_string(),
+                r"authentication_error".to_string(),
+            ],
+            finished_patterns: Vec::new(),
+            idle_timeout_secs: 60,
+            input_settle_ms: 1500,
+        }
+    }
+}
+
 // ============================================================
 // Markdown Scanning & Indexing Models
 // ============================================================
@@ -628,6 +996,15 @@ pub struct TerminalSession {
     pub working_directory: String,
     pub sort_order: i32,
     pub tmux_session: Option<String>,
+    /// Command the tab was launched with (None for interactive shells)
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Env profile the tab was explicitly started with
+    #[serde(default)]
+    pub env_profile_id: Option<String>,
+    /// Terminal profile the tab was explicitly started with
+    #[serde(default)]
+    pub terminal_profile_id: Option<String>,
     pub created_at: String,
 }
 
@@ -639,6 +1016,26 @@ pub struct SaveTerminalSessionInput {
     pub working_directory: String,
     pub sort_order: i32,
     pub tmux_session: Option<String>,
+    /// Launch command; derived from `tab_type` when not sent
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub env_profile_id: Option<String>,
+    #[serde(default)]
+    pub terminal_profile_id: Option<String>,
+}
+
+/// What `restore_workspace` did with a saved tab
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoredTab {
+    pub tab: TerminalSession,
+    /// "reattached" (tmux session still alive), "respawned" or "dropped"
+    pub outcome: String,
+    /// The PTY session now backing the tab (None when dropped)
+    pub session_id: Option<String>,
+    pub tmux_name: Option<String>,
+    /// Why a tab was respawned or dropped
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -839,6 +1236,13 @@ pub struct GsdSyncResult {
     pub summaries_synced: i32,
     pub phase_research_synced: i32,
     pub uat_synced: i32,
+    #[serde(default)]
+    pub decisions_extracted: i32,
+    #[serde(default)]
+    pub knowledge_extracted: i32,
+    /// Phases that completed during this sync with a drop in line coverage
+    #[serde(default)]
+    pub coverage_drops_flagged: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1000,3 +1404,287 @@ pub struct GsdUatResult {
     pub issue_count: i32,
     pub pending_count: i32,
 }
+
+// ============================================================
+// Cost Tracking Models (Claude session transcripts)
+// ============================================================
+
+/// Token prices for a model pattern, in USD per million tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub model: String,
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    pub cache_write_per_mtok: f64,
+    pub cache_read_per_mtok: f64,
+    pub is_default: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CostIngestResult {
+    pub projects_scanned: i32,
+    pub files_scanned: i32,
+    pub rows_inserted: i32,
+    pub total_cost: f64,
+    #[serde(default)]
+    pub threshold_events: Vec<CostThresholdEvent>,
+    /// Models seen without a configured price (their usage is recorded at $0)
+    #[serde(default)]
+    pub unpriced_models: Vec<String>,
+}
+
+/// Effective cost thresholds for a project. When `use_global` is set the
+/// warn/alert/stop amounts come from Settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostThreshold {
+    pub project_id: String,
+    pub warn_cost: f64,
+    pub alert_cost: f64,
+    pub stop_cost: f64,
+    pub enabled: bool,
+    pub use_global: bool,
+    pub interrupt_on_stop: bool,
+    pub alert_acknowledged: bool,
+    pub last_level: Option<String>, // "warn" | "alert" | "stop"
+    pub stop_triggered_at: Option<String>,
+    pub acknowledged_at: Option<String>,
+    pub current_cost: f64,
+}
+
+/// Aggregated spend for one group in a cost report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostBreakdownRow {
+    pub key: String,
+    pub label: String,
+    pub total_cost: f64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub message_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostReport {
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub granularity: String, // "day" | "week"
+    pub totals: CostBreakdownRow,
+    pub by_project: Vec<CostBreakdownRow>,
+    pub by_model: Vec<CostBreakdownRow>,
+    pub by_agent: Vec<CostBreakdownRow>,
+    pub by_phase: Vec<CostBreakdownRow>,
+    pub by_plan: Vec<CostBreakdownRow>,
+    pub by_period: Vec<CostBreakdownRow>,
+    /// Share of prompt tokens served from cache: cache_read / (input + cache_creation + cache_read)
+    pub cache_hit_ratio: f64,
+    /// Share of prompt tokens processed fresh (1 - cache_hit_ratio when there is any usage)
+    pub fresh_token_ratio: f64,
+    /// Plans with a SUMMARY.md (synced into gsd_summaries) that have attributed spend
+    pub completed_plans: i32,
+    pub cost_per_completed_plan: Option<f64>,
+}
+
+/// A threshold level reached by a project during cost evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostThresholdEvent {
+    pub project_id: String,
+    pub project_path: String,
+    pub level: String, // "warn" | "alert" | "stop"
+    pub threshold: f64,
+    pub current_cost: f64,
+    pub notification: Option<Notification>,
+    pub interrupt_requested: bool,
+    #[serde(default)]
+    pub interrupted_sessions: Vec<String>,
+    /// The stop notification is written after the interrupt, once the number
+    /// of interrupted sessions is known
+    #[serde(skip)]
+    pub notify_after_interrupt: bool,
+}
+
+// ============================================================
+// Test Run Models (JUnit / TAP / cargo / vitest+jest ingestion)
+// ============================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestRun {
+    pub id: String,
+    pub project_id: String,
+    pub execution_id: Option<String>,
+    pub phase: Option<String>,
+    pub total_tests: i32,
+    pub passed: i32,
+    pub failed: i32,
+    pub skipped: i32,
+    pub duration_ms: i64,
+    pub coverage_lines: Option<f64>,
+    pub coverage_branches: Option<f64>,
+    pub coverage_functions: Option<f64>,
+    /// HEAD sha, suffixed with a diff hash when the worktree was dirty
+    #[serde(default)]
+    pub commit_sha: Option<String>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestResult {
+    pub id: String,
+    pub test_run_id: String,
+    pub test_name: String,
+    pub test_file: Option<String>,
+    pub status: String, // "passed" | "failed" | "skipped"
+    pub duration_ms: i64,
+    pub error_message: Option<String>,
+    pub retry_count: i32,
+    pub created_at: String,
+}
+
+/// Input for `record_test_run`. Provide the report either inline (`content`)
+/// or as a path (`report_path`, relative paths resolve against the project).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordTestRunInput {
+    pub project_id: String,
+    /// "junit" | "tap" | "cargo" | "jest" (vitest's JSON reporter uses the jest shape);
+    /// detected from the content when omitted
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub report_path: Option<String>,
+    #[serde(default)]
+    pub phase: Option<String>,
+    /// Attribute the run to the current GSD phase from STATE.md when `phase` is not given
+    #[serde(default)]
+    pub use_current_phase: bool,
+    #[serde(default)]
+    pub execution_id: Option<String>,
+    #[serde(default)]
+    pub coverage_lines: Option<f64>,
+    #[serde(default)]
+    pub coverage_branches: Option<f64>,
+    #[serde(default)]
+    pub coverage_functions: Option<f64>,
+}
+
+/// A line of output streamed while a validation command runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCommandOutputLine {
+    pub project_id: String,
+    pub phase_number: String,
+    pub stream: String, // "stdout" | "stderr"
+    pub line: String,
+}
+
+/// Result of running a phase's VALIDATION.md quick/full command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationRunResult {
+    pub project_id: String,
+    pub phase_number: String,
+    pub mode: String, // "quick" | "full"
+    pub command: String,
+    pub framework: Option<String>,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub passed: bool,
+    pub duration_ms: i64,
+    /// None when no test results could be parsed from the run
+    pub test_run: Option<TestRun>,
+    /// Failing tests that were ignored because they are quarantined
+    #[serde(default)]
+    pub quarantined_failures: Vec<String>,
+    pub task_map: Vec<TaskVerification>,
+    pub output_tail: Vec<String>,
+}
+
+/// A test with pass/fail history worth watching. `flake_rate` is the share of
+/// consecutive runs where the outcome flipped between pass and fail.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlakyTest {
+    pub id: String,
+    pub project_id: String,
+    pub test_name: String,
+    pub test_file: Option<String>,
+    pub total_runs: i32,
+    pub failure_count: i32,
+    pub flake_rate: f64,
+    pub flip_count: i32,
+    /// Flips observed between runs of the same commit / worktree state
+    pub same_commit_flips: i32,
+    pub last_flip_commit: Option<String>,
+    pub last_status: Option<String>,
+    pub last_failure: Option<String>,
+    pub quarantined: bool,
+    pub quarantined_at: Option<String>,
+    pub quarantine_reason: Option<String>,
+    pub first_seen: Option<String>,
+}
+
+// ============================================================
+// Coverage Models (lcov / cobertura per-file import)
+// ============================================================
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileCoverage {
+    /// Relative to the project root when the report path is inside it
+    pub file_path: String,
+    pub lines_total: i64,
+    pub lines_covered: i64,
+    pub branches_total: i64,
+    pub branches_covered: i64,
+    pub functions_total: i64,
+    pub functions_covered: i64,
+}
+
+/// Input for `import_coverage_report`. Attaches to `test_run_id` when given,
+/// otherwise a coverage-only test run is created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportCoverageInput {
+    pub project_id: String,
+    #[serde(default)]
+    pub test_run_id: Option<String>,
+    /// "lcov" | "cobertura"; detected from the content when omitted
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub report_path: Option<String>,
+    #[serde(default)]
+    pub phase: Option<String>,
+    #[serde(default)]
+    pub use_current_phase: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageImportResult {
+    pub test_run: TestRun,
+    pub files_imported: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageTrendPoint {
+    pub test_run_id: String,
+    pub phase: Option<String>,
+    pub commit_sha: Option<String>,
+    pub created_at: String,
+    pub lines_pct: Option<f64>,
+    pub branches_pct: Option<f64>,
+    pub functions_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageDirectoryDelta {
+    pub directory: String,
+    pub base_lines_covered: i64,
+    pub base_lines_total: i64,
+    pub head_lines_covered: i64,
+    pub head_lines_total: i64,
+    pub base_pct: Option<f64>,
+    pub head_pct: Option<f64>,
+    /// Percentage points, head minus base; None when either side has no lines
+    pub delta: Option<f64>,
+}
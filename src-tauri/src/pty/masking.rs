@@ -0,0 +1,189 @@
+// Track Your Shit - Secret Masking
+// Replaces injected secret values in PTY output before it leaves the backend
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// Shown in place of a secret value
+const MASK: &[u8] = b"********";
+
+/// Values shorter than this aren't masked (they'd match ordinary output)
+const MIN_SECRET_LEN: usize = 4;
+
+/// How long a possible partial secret at the end of a read is held back
+const HOLD_TIMEOUT: Duration = Duration::from_millis(30);
+
+/// Masks secret values in a byte stream. Output that ends with the start of
+/// a secret is held back until the next chunk shows whether it completes.
+pub struct SecretMasker {
+    secrets: Vec<Vec<u8>>,
+    pending: Vec<u8>,
+}
+
+impl SecretMasker {
+    pub fn new(secrets: &[String]) -> Self {
+        let mut secrets: Vec<Vec<u8>> = secrets
+            .iter()
+            .filter(|s| s.len() >= MIN_SECRET_LEN)
+            .map(|s| s.as_bytes().to_vec())
+            .collect();
+        // Longest first so a secret containing another is masked whole
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        secrets.dedup();
+        Self {
+            secrets,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty()
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Mask `data`, returning what can be emitted now
+    pub fn mask(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut input = std::mem::take(&mut self.pending);
+        input.extend_from_slice(data);
+
+        let mut out = Vec::with_capacity(input.len());
+        let mut i = 0;
+        'scan: while i < input.len() {
+            for secret in &self.secrets {
+                if input[i..].starts_with(secret) {
+                    out.extend_from_slice(MASK);
+                    i += secret.len();
+                    continue 'scan;
+                }
+            }
+            // The rest of the input is the start of a secret: wait for more
+            let rest = &input[i..];
+            if self
+                .secrets
+                .iter()
+                .any(|s| s.len() > rest.len() && s.starts_with(rest))
+            {
+                self.pending = rest.to_vec();
+                break;
+            }
+            out.push(input[i]);
+            i += 1;
+        }
+        out
+    }
+
+    /// Release held-back bytes (the secret never completed)
+    pub fn flush(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Mask `secrets` in complete text, e.g. scrollback captured from tmux
+pub fn mask_text(secrets: &[String], text: String) -> String {
+    let mut masker = SecretMasker::new(secrets);
+    if masker.is_empty() {
+        return text;
+    }
+    let mut out = masker.mask(text.as_bytes());
+    out.extend(masker.flush());
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// A reader yielding the masked output of `inner`. Reads happen on a helper
+/// thread so held-back bytes can be released after HOLD_TIMEOUT.
+pub struct MaskingReader {
+    rx: Receiver<std::io::Result<Vec<u8>>>,
+    masker: SecretMasker,
+    ready: Vec<u8>,
+}
+
+impl MaskingReader {
+    pub fn new(mut inner: Box<dyn Read + Send>, masker: SecretMasker) -> Self {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                match inner.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        if tx.send(Ok(buf[..n].to_vec())).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        break;
+                    }
+                }
+            }
+        });
+        Self {
+            rx,
+            masker,
+            ready: Vec::new(),
+        }
+    }
+}
+
+impl Read for MaskingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.ready.is_empty() {
+            let chunk = if self.masker.has_pending() {
+                match self.rx.recv_timeout(HOLD_TIMEOUT) {
+                    Ok(chunk) => Some(chunk),
+                    Err(RecvTimeoutError::Timeout) => {
+                        self.ready = self.masker.flush();
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => None,
+                }
+            } else {
+                self.rx.recv().ok()
+            };
+            match chunk {
+                Some(Ok(data)) => self.ready = self.masker.mask(&data),
+                Some(Err(e)) => return Err(e),
+                None => {
+                    self.ready = self.masker.flush();
+                    if self.ready.is_empty() {
+                        return Ok(0);
+                    }
+                }
+            }
+        }
+        let n = buf.len().min(self.ready.len());
+        buf[..n].copy_from_slice(&self.ready[..n]);
+        self.ready.drain(..n);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_secrets_split_across_chunks() {
+        let mut masker = SecretMasker::new(&["sk-ant-12345".to_string(), "ab".to_string()]);
+        let mut out = masker.mask(b"key=sk-an");
+        assert_eq!(out, b"key=");
+        out.extend(masker.mask(b"t-12345 ab sk-a"));
+        assert_eq!(out, b"key=******** ab ");
+        out.extend(masker.flush());
+        assert_eq!(String::from_utf8(out).unwrap(), "key=******** ab sk-a");
+    }
+
+    #[test]
+    fn masking_reader_releases_held_bytes() {
+        let masker = SecretMasker::new(&["secret-value".to_string()]);
+        let mut reader = MaskingReader::new(Box::new(&b"echo secret-value; echo sec"[..]), masker);
+        let mut out = String::new();
+        reader.read_to_string(&mut out).unwrap();
+        assert_eq!(out, "echo ********; echo sec");
+    }
+}
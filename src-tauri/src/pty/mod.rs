@@ -2,13 +2,26 @@
 // Manages pseudo-terminal sessions with optional tmux persistence
 // Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
 
+pub mod masking;
+pub mod output_search;
+pub mod process_tree;
+pub mod session_state;
+pub mod shell_integration;
+pub mod tmux_panes;
+
 use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
 use std::collections::HashMap;
 use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex as StdMutex};
 use tauri::{AppHandle, Emitter};
 use tokio::sync::Mutex;
 
+use masking::{MaskingReader, SecretMasker};
+use output_search::Scrollback;
+use session_state::SessionStateTracker;
+use shell_integration::{CommandTracker, FinishedCommand, ShellKind};
+
 /// Event payload for PTY output
 #[derive(Clone, serde::Serialize)]
 pub struct PtyOutputEvent {
@@ -17,12 +30,27 @@ pub struct PtyOutputEvent {
 }
 
 /// Event payload for PTY exit
-#[derive(Clone, serde::Serialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct PtyExitEvent {
     pub session_id: String,
     pub exit_code: Option<i32>,
+    /// The user closed the session, rather than its process exiting
+    pub closed: bool,
+}
+
+/// Event payload for a command delimited by shell integration markers
+#[derive(Clone, serde::Serialize)]
+pub struct PtyCommandEvent {
+    pub session_id: String,
+    pub command: FinishedCommand,
 }
 
+/// Receives commands recorded by shell integration, keyed by session ID
+pub type CommandSink = Arc<dyn Fn(&str, FinishedCommand) + Send + Sync>;
+
+/// Receives a session's raw (masked) output, e.g. to keep a job log
+pub type OutputSink = Arc<dyn Fn(&[u8]) + Send + Sync>;
+
 /// Event payload for PTY errors
 #[allow(dead_code)]
 #[derive(Clone, serde::Serialize)]
@@ -31,6 +59,179 @@ pub struct PtyErrorEvent {
     pub error: String,
 }
 
+/// Whether a launch command runs the Claude CLI (`claude`, `npx claude`,
+/// `/usr/local/bin/claude ...`). Only the program itself counts, so
+/// `grep claude` or `vim ~/notes/claude` don't.
+pub fn is_claude_command(command: &str) -> bool {
+    let basename = |word: &str| word.rsplit(['/', '\\']).next().unwrap_or(word).to_string();
+    let is_claude = |word: &str| {
+        let name = basename(word);
+        name == "claude" || name == "claude.exe" || name == "claude.cmd"
+    };
+    // Skip leading `VAR=value` assignments
+    let mut words = command
+        .split_whitespace()
+        .skip_while(|w| w.contains('=') && !w.starts_with('-'));
+    let Some(program) = words.next() else {
+        return false;
+    };
+    if matches!(basename(program).as_str(), "npx" | "bunx") {
+        return words.find(|w| !w.starts_with('-')).is_some_and(|package| {
+            // Drop a version suffix (`claude@latest`), keeping a scope's leading `@`
+            let name = match package.rsplit_once('@') {
+                Some((name, _)) if !name.is_empty() => name,
+                _ => package,
+            };
+            is_claude(name) || name == "@anthropic-ai/claude-code"
+        });
+    }
+    is_claude(program)
+}
+
+/// Shell to start and how, for interactive and command sessions
+#[derive(Default)]
+pub struct ShellProfile {
+    /// Shell binary (default: `$SHELL`)
+    pub shell: Option<String>,
+    /// Extra arguments, e.g. `-l` for a login shell
+    pub args: Vec<String>,
+    /// Load the user's rc files instead of starting a clean shell with our prompt
+    pub load_rc_files: bool,
+}
+
+/// Per-session launch options
+#[derive(Default)]
+pub struct SessionOptions {
+    pub shell: ShellProfile,
+    /// Extra environment variables
+    pub env: Vec<(String, String)>,
+    /// Secret values among `env` that are masked in the output stream
+    pub masked: Vec<String>,
+    /// Classifies the session's output into running/idle/awaiting input/etc.
+    pub state: Option<Arc<SessionStateTracker>>,
+    /// Also receives the session's output
+    pub output: Option<OutputSink>,
+    /// tmux only: keep the pane (and its scrollback) after the command exits
+    pub remain_on_exit: bool,
+}
+
+/// Consumers of a session's output besides the frontend
+#[derive(Default)]
+struct OutputHooks {
+    state: Option<Arc<SessionStateTracker>>,
+    output: Option<OutputSink>,
+    scrollback: Option<Arc<Scrollback>>,
+}
+
+impl OutputHooks {
+    fn from_options(options: &SessionOptions) -> Self {
+        Self {
+            state: options.state.clone(),
+            output: options.output.clone(),
+            scrollback: None,
+        }
+    }
+}
+
+/// Flags that start `shell` without rc files
+fn clean_shell_flags(shell: &str) -> &'static [&'static str] {
+    if shell.ends_with("zsh") {
+        &["--no-rcs"]
+    } else if shell.ends_with("bash") {
+        &["--norc", "--noprofile"]
+    } else {
+        &[]
+    }
+}
+
+/// Quote a word for the `sh -c` command line tmux runs
+fn shell_quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/-_.=:,+@%".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// Oldest tmux whose pane formats and `capture-pane -J` we rely on
+const MIN_TMUX_VERSION: (u32, u32) = (1, 8);
+
+/// Parse `tmux -V` output (`tmux 3.3a`, `tmux next-3.4`) into (major, minor)
+fn parse_tmux_version(version: &str) -> Option<(u32, u32)> {
+    let number = version.trim().rsplit([' ', '-']).next()?;
+    let (major, rest) = number.split_once('.')?;
+    let minor: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Per-user directory under the app data dir for files our shells read at
+/// startup: created 0700 and refused if another user owns it
+pub(crate) fn private_dir(name: &str) -> Result<PathBuf, String> {
+    let dir = dirs::data_dir()
+        .ok_or("Could not determine data directory")?
+        .join("net.fluxlabs.track-your-shit")
+        .join(name);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(&dir)
+            .map_err(|e| e.to_string())?;
+        let meta = std::fs::symlink_metadata(&dir).map_err(|e| e.to_string())?;
+        if !meta.is_dir() || meta.uid() != unsafe { libc::geteuid() } {
+            return Err(format!("{} is not a directory owned by the current user", dir.display()));
+        }
+        if meta.mode() & 0o077 != 0 {
+            std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700)).map_err(|e| e.to_string())?;
+        }
+    }
+    #[cfg(not(unix))]
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// Write `env` as `export` lines to an owner-only file the session's shell
+/// sources and deletes, keeping the values out of argv and tmux's environment
+fn write_env_file(session_id: &str, env: &[(String, String)]) -> Result<PathBuf, String> {
+    let path = private_dir("tmux-env")?.join(format!("{}.env", session_id));
+    let contents: String = env
+        .iter()
+        .map(|(key, value)| format!("export {}={}\n", key, shell_quote(value)))
+        .collect();
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path).map_err(|e| e.to_string())?;
+    file.write_all(contents.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Prefix `shell_cmd` so it loads and removes the env file first
+fn sourcing_env_file(env_file: &Path, shell_cmd: &str) -> String {
+    let quoted = shell_quote(&env_file.to_string_lossy());
+    format!(". {q}; rm -f {q}; exec {cmd}", q = quoted, cmd = shell_cmd)
+}
+
+/// Wrap a PTY reader so the `secrets` values never reach the frontend
+fn masked_reader(reader: Box<dyn Read + Send>, secrets: &[String]) -> Box<dyn Read + Send> {
+    let masker = SecretMasker::new(secrets);
+    if masker.is_empty() {
+        reader
+    } else {
+        Box::new(MaskingReader::new(reader, masker))
+    }
+}
+
 /// Info about a tmux session managed by Track Your Shit
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct TmuxSessionInfo {
@@ -39,6 +240,14 @@ pub struct TmuxSessionInfo {
     pub created_at: String,
 }
 
+/// A session's searchable output
+pub enum OutputSource {
+    /// Captured from each pane of the tmux session, with the session's
+    /// secret values still to be masked
+    Tmux { tmux_name: String, masked: Vec<String> },
+    Scrollback(Arc<Scrollback>),
+}
+
 /// Backend type for a terminal session
 pub enum SessionBackend {
     /// Raw PTY — current behavior, no persistence
@@ -59,6 +268,15 @@ pub struct TerminalSession {
     child: Arc<StdMutex<Box<dyn Child + Send + Sync>>>,
     /// Working directory for this session
     pub working_directory: String,
+    /// Command the session was launched with (None for interactive shells and reattached tmux)
+    pub command: Option<String>,
+    /// State detection for Claude sessions
+    state: Option<Arc<SessionStateTracker>>,
+    /// Searchable output (native sessions; tmux keeps its own scrollback)
+    scrollback: Option<Arc<Scrollback>>,
+    /// Secret values masked in this session's output, also applied to text
+    /// read back from tmux panes
+    masked: Vec<String>,
     /// Session creation timestamp
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
@@ -137,6 +355,10 @@ pub struct TerminalManager {
     pub tmux_version: Option<String>,
     /// Whether to use tmux for new sessions (user preference)
     pub use_tmux: bool,
+    /// Whether native interactive shells get OSC 133/7 integration (user preference)
+    pub shell_integration: bool,
+    /// Where integrated sessions report finished commands
+    command_sink: Option<CommandSink>,
 }
 
 impl TerminalManager {
@@ -174,6 +396,8 @@ impl TerminalManager {
             tmux_available,
             tmux_version,
             use_tmux,
+            shell_integration: true,
+            command_sink: None,
         }
     }
 
@@ -193,6 +417,13 @@ impl TerminalManager {
 
     /// Run a tmux command and return stdout
     fn run_tmux(args: &[&str]) -> Result<String, String> {
+        Self::run_tmux_capture(args).map(|out| out.trim().to_string())
+    }
+
+    /// Run a tmux command and return stdout minus its final newline. Used for
+    /// capture-pane, where leading blank lines are part of the pane and line
+    /// numbers depend on them.
+    fn run_tmux_capture(args: &[&str]) -> Result<String, String> {
         let output = std::process::Command::new("tmux")
             .args(args)
             .output()
@@ -203,7 +434,8 @@ impl TerminalManager {
             return Err(format!("tmux error: {}", stderr.trim()));
         }
 
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.strip_suffix('\n').unwrap_or(&stdout).to_string())
     }
 
     /// List all Track Your Shit tmux sessions (prefixed with `ct-`)
@@ -250,12 +482,31 @@ impl TerminalManager {
         }
     }
 
+    /// Whether a tmux session's command has exited (`remain-on-exit` panes).
+    /// None when the session doesn't exist.
+    pub fn tmux_pane_dead(tmux_name: &str) -> Option<bool> {
+        Self::run_tmux(&["display-message", "-p", "-t", tmux_name, "#{pane_dead}"])
+            .ok()
+            .map(|out| out.trim() == "1")
+    }
+
+    /// Full scrollback of a tmux session's pane as plain text
+    pub fn capture_tmux_output(tmux_name: &str) -> Option<String> {
+        Self::run_tmux_capture(&["capture-pane", "-p", "-J", "-S", "-", "-t", tmux_name]).ok()
+    }
+
+    /// Kill a tmux session that has no attached TerminalSession
+    pub fn kill_tmux_session(tmux_name: &str) {
+        let _ = Self::run_tmux(&["kill-session", "-t", tmux_name]);
+    }
+
     /// Whether tmux should be used for new sessions
     fn should_use_tmux(&self) -> bool {
         self.tmux_available && self.use_tmux
     }
 
     /// Create a new terminal session
+    #[allow(clippy::too_many_arguments)]
     pub fn create_session(
         &mut self,
         app: &AppHandle,
@@ -264,6 +515,7 @@ impl TerminalManager {
         command: Option<&str>,
         cols: u16,
         rows: u16,
+        options: &SessionOptions,
     ) -> Result<Option<String>, String> {
         // Check if session already exists
         if self.sessions.contains_key(&session_id) {
@@ -271,14 +523,15 @@ impl TerminalManager {
         }
 
         if self.should_use_tmux() {
-            self.create_tmux_session(app, session_id, working_dir, command, cols, rows)
+            self.create_tmux_session(app, session_id, working_dir, command, cols, rows, options)
         } else {
-            self.create_native_session(app, session_id, working_dir, command, cols, rows)?;
+            self.create_native_session(app, session_id, working_dir, command, cols, rows, options)?;
             Ok(None)
         }
     }
 
     /// Create a tmux-backed session
+    #[allow(clippy::too_many_arguments)]
     fn create_tmux_session(
         &mut self,
         app: &AppHandle,
@@ -287,7 +540,16 @@ impl TerminalManager {
         command: Option<&str>,
         cols: u16,
         rows: u16,
+        options: &SessionOptions,
     ) -> Result<Option<String>, String> {
+        let version = self.tmux_version.as_deref().unwrap_or_default();
+        if parse_tmux_version(version).is_some_and(|v| v < MIN_TMUX_VERSION) {
+            return Err(format!(
+                "tmux {}.{} or newer is required for persistent sessions (found {})",
+                MIN_TMUX_VERSION.0, MIN_TMUX_VERSION.1, version
+            ));
+        }
+
         // Generate tmux session name: ct-<first-8-of-uuid>
         let tmux_name = format!("ct-{}", &session_id[..8.min(session_id.len())]);
 
@@ -308,68 +570,86 @@ impl TerminalManager {
             &rows_str,
         ];
 
-        // Always pass explicit shell command for clean terminals (no rc files)
-        let is_zsh = self.default_shell.ends_with("zsh");
-        let is_bash = self.default_shell.ends_with("bash");
+        // No shell integration here: tmux doesn't pass OSC 133 through to the
+        // attach client we read, so only native sessions record commands.
+        // Always pass an explicit shell command: a clean shell (no rc files)
+        // unless the profile asks for them
+        let shell = options.shell.shell.as_deref().unwrap_or(&self.default_shell);
+        let is_zsh = shell.ends_with("zsh");
+        let is_bash = shell.ends_with("bash");
+        let load_rc = options.shell.load_rc_files;
+        let mut shell_words = vec![shell_quote(shell)];
+        if !load_rc {
+            shell_words.extend(clean_shell_flags(shell).iter().map(|f| f.to_string()));
+        }
+        shell_words.extend(options.shell.args.iter().map(|a| shell_quote(a)));
+        let shell_line = shell_words.join(" ");
+
         let shell_cmd;
         if let Some(cmd) = command {
-            // Command sessions: run via clean shell without rc files
             let escaped = cmd.replace('\'', "'\\''");
-            if is_zsh {
-                shell_cmd = format!("{} --no-rcs -c '{}'", self.default_shell, escaped);
-            } else if is_bash {
-                shell_cmd = format!("{} --norc --noprofile -c '{}'", self.default_shell, escaped);
-            } else {
-                shell_cmd = format!("{} -c '{}'", self.default_shell, escaped);
-            }
+            shell_cmd = format!("{} -c '{}'", shell_line, escaped);
         } else {
-            // Interactive sessions: start clean shell without rc files, with custom prompt
+            // Interactive sessions: clean shells get our custom prompt
             let dir_name = std::path::Path::new(working_dir)
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or(working_dir)
                 .replace('\'', "'\\''");
-            if is_zsh {
+            if is_zsh && !load_rc {
                 shell_cmd = format!(
-                    "/usr/bin/env TERM=xterm-256color COLORTERM=truecolor CONTROL_TOWER=1 PS1='%F{{cyan}}{}%f %F{{blue}}$%f ' {} --no-rcs",
-                    dir_name, self.default_shell
+                    "/usr/bin/env TERM=xterm-256color COLORTERM=truecolor CONTROL_TOWER=1 PS1='%F{{cyan}}{}%f %F{{blue}}$%f ' {}",
+                    dir_name, shell_line
                 );
-            } else if is_bash {
+            } else if is_bash && !load_rc {
                 shell_cmd = format!(
-                    "/usr/bin/env TERM=xterm-256color COLORTERM=truecolor CONTROL_TOWER=1 PS1='\\[\\033[36m\\]{}\\[\\033[0m\\] \\[\\033[34m\\]$\\[\\033[0m\\] ' {} --norc --noprofile",
-                    dir_name, self.default_shell
+                    "/usr/bin/env TERM=xterm-256color COLORTERM=truecolor CONTROL_TOWER=1 PS1='\\[\\033[36m\\]{}\\[\\033[0m\\] \\[\\033[34m\\]$\\[\\033[0m\\] ' {}",
+                    dir_name, shell_line
                 );
             } else {
                 shell_cmd = format!(
                     "/usr/bin/env TERM=xterm-256color COLORTERM=truecolor CONTROL_TOWER=1 {}",
-                    self.default_shell
+                    shell_line
                 );
             }
         }
+        // Profile variables reach only this shell, through an env file it
+        // sources and deletes (panes opened later don't inherit them)
+        let env_file = if options.env.is_empty() {
+            None
+        } else {
+            Some(write_env_file(&session_id, &options.env)?)
+        };
+        let shell_cmd = match &env_file {
+            Some(path) => sourcing_env_file(path, &shell_cmd),
+            None => shell_cmd,
+        };
         tmux_args.push(&shell_cmd);
+        if options.remain_on_exit {
+            // Chained so a command that exits at once still leaves its pane
+            tmux_args.extend([";", "set-option", "-t", &tmux_name, "remain-on-exit", "on"]);
+        }
 
-        Self::run_tmux(&tmux_args)?;
+        if let Err(e) = Self::run_tmux(&tmux_args) {
+            if let Some(path) = &env_file {
+                let _ = std::fs::remove_file(path);
+            }
+            return Err(e);
+        }
 
         // Configure the tmux session
         let _ = Self::run_tmux(&["set-option", "-t", &tmux_name, "status", "off"]);
         let _ = Self::run_tmux(&["set-option", "-t", &tmux_name, "-g", "mouse", "on"]);
         let _ = Self::run_tmux(&["set-option", "-t", &tmux_name, "history-limit", "50000"]);
 
-        // Set clean shell as default-command for new panes/windows in this session
+        // Use the same shell as default-command for new panes/windows in this session
         if command.is_none() {
-            let default_cmd = if is_zsh {
-                format!("{} --no-rcs", self.default_shell)
-            } else if is_bash {
-                format!("{} --norc --noprofile", self.default_shell)
-            } else {
-                self.default_shell.clone()
-            };
             let _ = Self::run_tmux(&[
                 "set-option",
                 "-t",
                 &tmux_name,
                 "default-command",
-                &default_cmd,
+                &shell_line,
             ]);
         }
 
@@ -385,6 +665,10 @@ impl TerminalManager {
             writer,
             child: child.clone(),
             working_directory: working_dir.to_string(),
+            command: command.map(|c| c.to_string()),
+            state: options.state.clone(),
+            scrollback: None,
+            masked: options.masked.clone(),
             created_at: chrono::Utc::now(),
         };
 
@@ -392,11 +676,17 @@ impl TerminalManager {
 
         // For command-based sessions, spawn a monitor thread
         if command.is_some() {
-            self.spawn_monitor_thread(app, &session_id, child.clone());
+            self.spawn_monitor_thread(app, &session_id, child.clone(), options.state.clone());
         }
 
         // Spawn reader thread
-        self.spawn_reader_thread(app, &session_id, reader);
+        self.spawn_reader_thread(
+            app,
+            &session_id,
+            masked_reader(reader, &options.masked),
+            None,
+            OutputHooks::from_options(options),
+        );
 
         tracing::info!(
             "Created tmux session {} (tmux: {}) in {}",
@@ -409,6 +699,7 @@ impl TerminalManager {
     }
 
     /// Attach to an existing tmux session (for reconnection after app restart)
+    #[allow(clippy::too_many_arguments)]
     pub fn attach_session(
         &mut self,
         app: &AppHandle,
@@ -417,6 +708,7 @@ impl TerminalManager {
         working_dir: &str,
         cols: u16,
         rows: u16,
+        masked: &[String],
     ) -> Result<bool, String> {
         // Verify the tmux session still exists
         Self::run_tmux(&["has-session", "-t", tmux_name])
@@ -447,13 +739,23 @@ impl TerminalManager {
             writer,
             child,
             working_directory: working_dir.to_string(),
+            command: None,
+            state: None,
+            scrollback: None,
+            masked: masked.to_vec(),
             created_at: chrono::Utc::now(),
         };
 
         self.sessions.insert(session_id.clone(), session);
 
         // Spawn reader thread
-        self.spawn_reader_thread(app, &session_id, reader);
+        self.spawn_reader_thread(
+            app,
+            &session_id,
+            masked_reader(reader, masked),
+            None,
+            OutputHooks::default(),
+        );
 
         tracing::info!(
             "Reattached to tmux session {} (tmux: {})",
@@ -522,6 +824,7 @@ impl TerminalManager {
     }
 
     /// Create a native (non-tmux) PTY session — original behavior
+    #[allow(clippy::too_many_arguments)]
     fn create_native_session(
         &mut self,
         app: &AppHandle,
@@ -530,6 +833,7 @@ impl TerminalManager {
         command: Option<&str>,
         cols: u16,
         rows: u16,
+        options: &SessionOptions,
     ) -> Result<(), String> {
         let pty_system = native_pty_system();
         let size = PtySize {
@@ -544,37 +848,47 @@ impl TerminalManager {
             .map_err(|e| format!("Failed to open PTY: {}", e))?;
 
         // Build the command
-        let is_zsh = self.default_shell.ends_with("zsh");
-        let is_bash = self.default_shell.ends_with("bash");
+        let shell = options.shell.shell.as_deref().unwrap_or(&self.default_shell);
+        let is_zsh = shell.ends_with("zsh");
+        let load_rc = options.shell.load_rc_files;
+        let mut args = options.shell.args.clone();
+        let mut integrated = false;
 
-        let mut cmd = if let Some(command_str) = command {
-            let mut c = CommandBuilder::new(&self.default_shell);
-            if is_zsh {
-                c.arg("--no-rcs");
-            } else if is_bash {
-                c.arg("--norc");
-                c.arg("--noprofile");
-            }
-            c.arg("-c");
-            c.arg(command_str);
-            c
-        } else {
-            let mut c = CommandBuilder::new(&self.default_shell);
-            if is_zsh {
-                c.arg("--no-rcs");
-            } else if is_bash {
-                c.arg("--norc");
-                c.arg("--noprofile");
+        let mut cmd = CommandBuilder::new(shell);
+        if command.is_none() {
+            let integration = ShellKind::detect(shell)
+                .filter(|_| self.shell_integration && self.command_sink.is_some());
+            if let Some(kind) = integration {
+                let login = args.iter().any(|a| a == "-l" || a == "--login");
+                match shell_integration::apply(&mut cmd, kind, load_rc, login) {
+                    Ok(()) => {
+                        integrated = true;
+                        if kind == ShellKind::Bash {
+                            args.retain(|a| a != "-l" && a != "--login");
+                        }
+                    }
+                    Err(e) => tracing::warn!("Shell integration unavailable, starting plain shell: {}", e),
+                }
             }
-            c
-        };
+        }
+        if !integrated && !load_rc {
+            cmd.args(clean_shell_flags(shell));
+        }
+        cmd.args(&args);
+        if let Some(command_str) = command {
+            cmd.arg("-c");
+            cmd.arg(command_str);
+        }
         cmd.cwd(working_dir);
 
         cmd.env("TERM", "xterm-256color");
         cmd.env("COLORTERM", "truecolor");
         cmd.env("CONTROL_TOWER", "1");
+        for (key, value) in &options.env {
+            cmd.env(key, value);
+        }
 
-        if command.is_none() {
+        if command.is_none() && !load_rc {
             let dir_name = std::path::Path::new(working_dir)
                 .file_name()
                 .and_then(|n| n.to_str())
@@ -610,12 +924,17 @@ impl TerminalManager {
             .try_clone_reader()
             .map_err(|e| format!("Failed to get PTY reader: {}", e))?;
 
+        let scrollback = Arc::new(Scrollback::default());
         let session = TerminalSession {
             backend: SessionBackend::Native,
             master: pair.master,
             writer,
             child: child.clone(),
             working_directory: working_dir.to_string(),
+            command: command.map(|c| c.to_string()),
+            state: options.state.clone(),
+            scrollback: Some(scrollback.clone()),
+            masked: options.masked.clone(),
             created_at: chrono::Utc::now(),
         };
 
@@ -623,16 +942,31 @@ impl TerminalManager {
 
         // For command-based sessions, spawn monitor thread
         if command.is_some() {
-            self.spawn_monitor_thread(app, &session_id, child.clone());
+            self.spawn_monitor_thread(app, &session_id, child.clone(), options.state.clone());
         }
 
         // Spawn reader thread
-        self.spawn_reader_thread(app, &session_id, reader);
+        let integration = self
+            .command_sink
+            .clone()
+            .filter(|_| integrated)
+            .map(|sink| (sink, working_dir.to_string()));
+        self.spawn_reader_thread(
+            app,
+            &session_id,
+            masked_reader(reader, &options.masked),
+            integration,
+            OutputHooks {
+                scrollback: Some(scrollback),
+                ..OutputHooks::from_options(options)
+            },
+        );
 
         tracing::info!(
-            "Created native PTY session {} in {}",
+            "Created native PTY session {} in {} (shell integration: {})",
             session_id,
-            working_dir
+            working_dir,
+            integrated
         );
         Ok(())
     }
@@ -643,6 +977,7 @@ impl TerminalManager {
         app: &AppHandle,
         session_id: &str,
         child: Arc<StdMutex<Box<dyn Child + Send + Sync>>>,
+        state: Option<Arc<SessionStateTracker>>,
     ) {
         let monitor_app = app.clone();
         let monitor_sid = session_id.to_string();
@@ -668,11 +1003,15 @@ impl TerminalManager {
                         monitor_sid,
                         exit_code
                     );
+                    if let Some(state) = &state {
+                        state.exit(Some(exit_code));
+                    }
                     let _ = monitor_app.emit(
                         &format!("pty:exit:{}", monitor_sid),
                         PtyExitEvent {
                             session_id: monitor_sid.clone(),
                             exit_code: Some(exit_code),
+                            closed: false,
                         },
                     );
                     break;
@@ -682,12 +1021,17 @@ impl TerminalManager {
         });
     }
 
-    /// Spawn reader thread for PTY output
+    /// Spawn reader thread for PTY output. With `integration` (sink and the
+    /// session's working directory) commands delimited by OSC 133 markers are
+    /// emitted as `pty:command:<id>` and passed to the sink. Output is also
+    /// fed to the session's state tracker and output sink, if any.
     fn spawn_reader_thread(
         &self,
         app: &AppHandle,
         session_id: &str,
         mut reader: Box<dyn Read + Send>,
+        integration: Option<(CommandSink, String)>,
+        hooks: OutputHooks,
     ) {
         let app_handle = app.clone();
         let sid = session_id.to_string();
@@ -695,6 +1039,7 @@ impl TerminalManager {
             tracing::info!("PTY reader thread started for session {}", sid);
             let mut buf = [0u8; 4096];
             let mut total_bytes = 0usize;
+            let mut tracker = integration.as_ref().map(|_| CommandTracker::default());
             loop {
                 match reader.read(&mut buf) {
                     Ok(0) => {
@@ -704,6 +1049,7 @@ impl TerminalManager {
                             PtyExitEvent {
                                 session_id: sid.clone(),
                                 exit_code: None,
+                                closed: false,
                             },
                         );
                         break;
@@ -722,6 +1068,32 @@ impl TerminalManager {
                         if let Err(e) = result {
                             tracing::error!("Failed to emit PTY output event: {}", e);
                         }
+
+                        if let Some(state) = &hooks.state {
+                            state.feed(&buf[..n]);
+                        }
+                        if let Some(output) = &hooks.output {
+                            output(&buf[..n]);
+                        }
+                        if let Some(scrollback) = &hooks.scrollback {
+                            scrollback.feed(&buf[..n]);
+                        }
+
+                        if let (Some(tracker), Some((sink, working_dir))) =
+                            (tracker.as_mut(), integration.as_ref())
+                        {
+                            for mut command in tracker.feed(&buf[..n]) {
+                                command.cwd.get_or_insert_with(|| working_dir.clone());
+                                let _ = app_handle.emit(
+                                    &format!("pty:command:{}", sid),
+                                    PtyCommandEvent {
+                                        session_id: sid.clone(),
+                                        command: command.clone(),
+                                    },
+                                );
+                                sink(&sid, command);
+                            }
+                        }
                     }
                     Err(e) => {
                         tracing::info!("PTY {} read error after {} bytes: {}", sid, total_bytes, e);
@@ -730,6 +1102,7 @@ impl TerminalManager {
                             PtyExitEvent {
                                 session_id: sid.clone(),
                                 exit_code: None,
+                                closed: false,
                             },
                         );
                         break;
@@ -794,6 +1167,11 @@ impl TerminalManager {
             .remove(session_id)
             .ok_or_else(|| format!("Session {} not found", session_id))?;
 
+        // The session lives on in tmux; stop tracking so this isn't reported as an exit
+        if let Some(state) = &session.state {
+            state.close();
+        }
+
         // Kill the PTY attach process but leave the tmux session alive
         if session.is_running() {
             let _ = session.kill();
@@ -810,6 +1188,11 @@ impl TerminalManager {
             .remove(session_id)
             .ok_or_else(|| format!("Session {} not found", session_id))?;
 
+        // A user close isn't a finish or failure worth notifying about
+        if let Some(state) = &session.state {
+            state.close();
+        }
+
         // For tmux sessions, kill the tmux session (permanent close)
         if let SessionBackend::Tmux { ref tmux_name } = session.backend {
             tracing::info!("Killing tmux session {} for tab close", tmux_name);
@@ -828,6 +1211,7 @@ impl TerminalManager {
             PtyExitEvent {
                 session_id: session_id.to_string(),
                 exit_code,
+                closed: true,
             },
         );
 
@@ -864,20 +1248,91 @@ impl TerminalManager {
         })
     }
 
+    /// Root processes of each session (or just `session_id`): the shell for
+    /// native sessions, the pane processes for tmux sessions
+    pub fn session_root_pids(&self, session_id: Option<&str>) -> Vec<(String, Vec<u32>)> {
+        self.sessions
+            .iter()
+            .filter(|(id, _)| session_id.is_none_or(|wanted| wanted == id.as_str()))
+            .map(|(id, session)| {
+                let roots = match &session.backend {
+                    SessionBackend::Tmux { tmux_name } => {
+                        Self::run_tmux(&["list-panes", "-s", "-t", tmux_name, "-F", "#{pane_pid}"])
+                            .map(|out| out.lines().filter_map(|l| l.trim().parse().ok()).collect())
+                            .unwrap_or_default()
+                    }
+                    SessionBackend::Native => session
+                        .child
+                        .lock()
+                        .ok()
+                        .and_then(|child| child.process_id())
+                        .into_iter()
+                        .collect(),
+                };
+                (id.clone(), roots)
+            })
+            .collect()
+    }
+
+    /// Current detected state of a tracked (Claude) session
+    pub fn session_state(&self, session_id: &str) -> Option<session_state::SessionState> {
+        self.sessions
+            .get(session_id)
+            .and_then(|s| s.state.as_ref())
+            .and_then(|state| state.state())
+    }
+
     /// Get the tmux session name for a session
-    #[allow(dead_code)]
     pub fn get_tmux_name(&self, session_id: &str) -> Option<String> {
         self.sessions
             .get(session_id)
             .and_then(|s| s.tmux_name().map(|n| n.to_string()))
     }
 
+    /// Secret values masked in a session's output
+    pub fn masked_values(&self, session_id: &str) -> Vec<String> {
+        self.sessions
+            .get(session_id)
+            .map(|s| s.masked.clone())
+            .unwrap_or_default()
+    }
+
+    /// Where each session's (or just `session_id`'s) output can be searched
+    pub fn output_sources(&self, session_id: Option<&str>) -> Vec<(String, OutputSource)> {
+        self.sessions
+            .iter()
+            .filter(|(id, _)| session_id.is_none_or(|wanted| wanted == id.as_str()))
+            .filter_map(|(id, session)| {
+                let source = match (&session.backend, &session.scrollback) {
+                    (SessionBackend::Tmux { tmux_name }, _) => OutputSource::Tmux {
+                        tmux_name: tmux_name.clone(),
+                        masked: session.masked.clone(),
+                    },
+                    (SessionBackend::Native, Some(scrollback)) => OutputSource::Scrollback(scrollback.clone()),
+                    (SessionBackend::Native, None) => return None,
+                };
+                Some((id.clone(), source))
+            })
+            .collect()
+    }
+
+    /// The session currently attached to a tmux session, if any
+    pub fn session_for_tmux(&self, tmux_name: &str) -> Option<String> {
+        self.sessions
+            .iter()
+            .find(|(_, s)| s.tmux_name() == Some(tmux_name))
+            .map(|(id, _)| id.clone())
+    }
+
     /// Close all terminal sessions
     /// Native sessions are killed. tmux sessions are detached (survive for reconnect).
     pub fn close_all(&mut self) {
         let session_ids: Vec<String> = self.sessions.keys().cloned().collect();
         for session_id in session_ids {
             if let Some(mut session) = self.sessions.remove(&session_id) {
+                if let Some(state) = &session.state {
+                    state.close();
+                }
                 match session.backend {
                     SessionBackend::Tmux { .. } => {
                         // Just kill the PTY attach process, leave tmux session alive
@@ -926,11 +1381,61 @@ impl TerminalManager {
             .collect()
     }
 
+    /// Send an interrupt (Ctrl-C) to every Claude session whose working directory
+    /// is `project_path` or below it. A session counts as Claude when it was
+    /// launched with a `claude` command or, for tmux sessions, when the pane's
+    /// foreground process is `claude`. Returns the interrupted session IDs.
+    pub fn interrupt_claude_sessions(&mut self, project_path: &str) -> Vec<String> {
+        let root = std::path::Path::new(project_path);
+        let mut interrupted = Vec::new();
+
+        for (session_id, session) in self.sessions.iter_mut() {
+            if !std::path::Path::new(&session.working_directory).starts_with(root) {
+                continue;
+            }
+            let launched_claude = session.command.as_deref().is_some_and(is_claude_command);
+            let running_claude = match &session.backend {
+                SessionBackend::Tmux { tmux_name } => Self::run_tmux(&[
+                    "display-message",
+                    "-p",
+                    "-t",
+                    tmux_name,
+                    "#{pane_current_command}",
+                ])
+                .map(|cmd| cmd.trim() == "claude")
+                .unwrap_or(false),
+                SessionBackend::Native => false,
+            };
+            if !(launched_claude || running_claude) || !session.is_running() {
+                continue;
+            }
+
+            match session.write(b"\x03") {
+                Ok(()) => interrupted.push(session_id.clone()),
+                Err(e) => tracing::warn!("Failed to interrupt session {}: {}", session_id, e),
+            }
+        }
+
+        interrupted
+    }
+
     /// Update the use_tmux preference (affects future sessions only)
     pub fn set_use_tmux(&mut self, enabled: bool) {
         self.use_tmux = enabled;
         tracing::info!("tmux preference updated: use_tmux={}", enabled);
     }
+
+    /// Update shell integration preference for new native sessions. tmux
+    /// sessions never get it: tmux consumes OSC 133/7 instead of passing them on.
+    pub fn set_shell_integration(&mut self, enabled: bool) {
+        self.shell_integration = enabled;
+        tracing::info!("Shell integration preference updated: shell_integration={}", enabled);
+    }
+
+    /// Set where shell-integrated sessions report finished commands
+    pub fn set_command_sink(&mut self, sink: CommandSink) {
+        self.command_sink = Some(sink);
+    }
 }
 
 impl Default for TerminalManager {
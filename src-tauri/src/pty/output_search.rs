@@ -0,0 +1,143 @@
+// Track Your Shit - Terminal Output Search
+// Plain-text scrollback for native sessions and line search over session output
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use super::session_state::strip_ansi;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::sync::Mutex as StdMutex;
+
+/// Lines of output kept per native session
+const MAX_LINES: usize = 10_000;
+
+/// Longest partial line buffered before it's cut (progress bars without newlines)
+const MAX_PARTIAL_BYTES: usize = 64 * 1024;
+
+/// A line of session output matching a search
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OutputMatch {
+    pub session_id: String,
+    /// tmux pane the line is in (None for native sessions)
+    pub pane_id: Option<String>,
+    /// 1-based line number in the session's (or pane's) output
+    pub line_number: usize,
+    pub line: String,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+struct ScrollbackLines {
+    lines: VecDeque<String>,
+    /// Lines dropped off the front, so line numbers stay stable
+    dropped: usize,
+    partial: Vec<u8>,
+}
+
+/// ANSI-stripped output of a native session, fed by its reader thread
+pub struct Scrollback {
+    inner: StdMutex<ScrollbackLines>,
+}
+
+impl Default for Scrollback {
+    fn default() -> Self {
+        Self {
+            inner: StdMutex::new(ScrollbackLines {
+                lines: VecDeque::new(),
+                dropped: 0,
+                partial: Vec::new(),
+            }),
+        }
+    }
+}
+
+/// What a terminal shows for a line: text after the last carriage return
+fn clean_line(raw: &[u8]) -> String {
+    let text = strip_ansi(&String::from_utf8_lossy(raw));
+    let text = text.trim_end_matches('\r');
+    text.rsplit('\r').next().unwrap_or_default().to_string()
+}
+
+impl Scrollback {
+    pub fn feed(&self, data: &[u8]) {
+        let Ok(mut inner) = self.inner.lock() else { return };
+        inner.partial.extend_from_slice(data);
+        let mut start = 0;
+        let mut complete = Vec::new();
+        while let Some(pos) = inner.partial[start..].iter().position(|b| *b == b'\n') {
+            complete.push(clean_line(&inner.partial[start..start + pos]));
+            start += pos + 1;
+        }
+        if inner.partial.len() - start > MAX_PARTIAL_BYTES {
+            complete.push(clean_line(&inner.partial[start..]));
+            start = inner.partial.len();
+        }
+        inner.partial.drain(..start);
+        for line in complete {
+            inner.lines.push_back(line);
+        }
+        while inner.lines.len() > MAX_LINES {
+            inner.lines.pop_front();
+            inner.dropped += 1;
+        }
+    }
+
+    /// Number of the first kept line (1-based) and the lines, including the
+    /// current unfinished one
+    pub fn snapshot(&self) -> (usize, Vec<String>) {
+        let Ok(inner) = self.inner.lock() else { return (1, Vec::new()) };
+        let mut lines: Vec<String> = inner.lines.iter().cloned().collect();
+        if !inner.partial.is_empty() {
+            lines.push(clean_line(&inner.partial));
+        }
+        (inner.dropped + 1, lines)
+    }
+}
+
+/// Matches in `lines` as (index, before, after) with `context` lines either side
+pub fn search_lines(lines: &[String], re: &Regex, context: usize) -> Vec<(usize, Vec<String>, Vec<String>)> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| re.is_match(line))
+        .map(|(i, _)| {
+            let before = lines[i.saturating_sub(context)..i].to_vec();
+            let after = lines[i + 1..(i + 1 + context).min(lines.len())].to_vec();
+            (i, before, after)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrollback_strips_escapes_and_finds_context() {
+        let scrollback = Scrollback::default();
+        scrollback.feed(b"\x1b]133;C\x07   Compiling app v0.1.0\r\n");
+        scrollback.feed(b"\x1b[1m\x1b[31merror[E0");
+        scrollback.feed(b"308]\x1b[0m: mismatched types\r\n  --> src/main.rs:4:5\r\n");
+        scrollback.feed(b"Building [==>   ] 10%\rBuilding [=====>] 100%\r\nwarn");
+
+        let (first, lines) = scrollback.snapshot();
+        assert_eq!(first, 1);
+        assert_eq!(
+            lines,
+            [
+                "   Compiling app v0.1.0",
+                "error[E0308]: mismatched types",
+                "  --> src/main.rs:4:5",
+                "Building [=====>] 100%",
+                "warn",
+            ]
+        );
+
+        let re = Regex::new(&regex::escape("error[E0")).unwrap();
+        let found = search_lines(&lines, &re, 2);
+        assert_eq!(found.len(), 1);
+        let (index, before, after) = &found[0];
+        assert_eq!(*index, 1);
+        assert_eq!(before, &["   Compiling app v0.1.0"]);
+        assert_eq!(after, &["  --> src/main.rs:4:5", "Building [=====>] 100%"]);
+    }
+}
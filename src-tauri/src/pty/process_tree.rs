@@ -0,0 +1,382 @@
+// Track Your Shit - Process Tree Sampling
+// Processes running under each terminal session, with CPU, memory and listening ports
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// A process under a terminal session
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    /// Executable name
+    pub name: String,
+    /// Full command line (the name in brackets when unavailable, e.g. zombies)
+    pub command: String,
+    /// Scheduler state: R running, S sleeping, Z zombie, ...
+    pub state: String,
+    /// CPU usage since the previous sample (None on the first sample)
+    pub cpu_percent: Option<f64>,
+    pub rss_bytes: u64,
+    /// TCP ports the process is listening on
+    pub ports: Vec<u16>,
+    /// Distance from the session's root process
+    pub depth: u32,
+}
+
+/// The process tree of one terminal session, parents before children
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionProcesses {
+    pub session_id: String,
+    pub root_pids: Vec<u32>,
+    pub processes: Vec<ProcessInfo>,
+    pub total_cpu_percent: f64,
+    pub total_rss_bytes: u64,
+}
+
+/// One process as read from the OS
+struct RawProcess {
+    pid: u32,
+    ppid: u32,
+    name: String,
+    state: String,
+    command: String,
+    rss_bytes: u64,
+    /// Total CPU time (Linux), turned into a percentage between samples
+    cpu_secs: Option<f64>,
+    /// CPU percentage reported directly (`ps`)
+    cpu_percent: Option<f64>,
+}
+
+/// Reads the process table, remembering CPU times so usage can be computed
+/// between calls
+#[derive(Default)]
+pub struct ProcessSampler {
+    prev_cpu: HashMap<u32, f64>,
+    prev_at: Option<Instant>,
+}
+
+impl ProcessSampler {
+    /// Process trees under each `(session_id, root_pids)`
+    pub fn sample(&mut self, sessions: &[(String, Vec<u32>)]) -> Vec<SessionProcesses> {
+        let now = Instant::now();
+        let elapsed = self.prev_at.map(|at| now.duration_since(at).as_secs_f64());
+        let procs: HashMap<u32, RawProcess> = read_processes().into_iter().map(|p| (p.pid, p)).collect();
+
+        let cpu = |p: &RawProcess| match (p.cpu_secs, elapsed) {
+            (Some(secs), Some(elapsed)) if elapsed > 0.0 => self
+                .prev_cpu
+                .get(&p.pid)
+                .map(|prev| ((secs - prev).max(0.0) / elapsed * 100.0 * 10.0).round() / 10.0),
+            (Some(_), _) => None,
+            (None, _) => p.cpu_percent,
+        };
+
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for p in procs.values() {
+            children.entry(p.ppid).or_default().push(p.pid);
+        }
+        for list in children.values_mut() {
+            list.sort_unstable();
+        }
+
+        let tree_pids: Vec<u32> = sessions
+            .iter()
+            .flat_map(|(_, roots)| walk(roots, &children).into_iter().map(|(pid, _)| pid))
+            .collect();
+        let ports = listening_ports(&tree_pids);
+
+        let result = sessions
+            .iter()
+            .map(|(session_id, roots)| {
+                let processes: Vec<ProcessInfo> = walk(roots, &children)
+                    .into_iter()
+                    .filter_map(|(pid, depth)| {
+                        let p = procs.get(&pid)?;
+                        Some(ProcessInfo {
+                            pid,
+                            ppid: p.ppid,
+                            name: p.name.clone(),
+                            command: p.command.clone(),
+                            state: p.state.clone(),
+                            cpu_percent: cpu(p),
+                            rss_bytes: p.rss_bytes,
+                            ports: ports.get(&pid).cloned().unwrap_or_default(),
+                            depth,
+                        })
+                    })
+                    .collect();
+                SessionProcesses {
+                    session_id: session_id.clone(),
+                    root_pids: roots.clone(),
+                    total_cpu_percent: processes.iter().filter_map(|p| p.cpu_percent).sum(),
+                    total_rss_bytes: processes.iter().map(|p| p.rss_bytes).sum(),
+                    processes,
+                }
+            })
+            .collect();
+
+        self.prev_cpu = procs
+            .values()
+            .filter_map(|p| p.cpu_secs.map(|secs| (p.pid, secs)))
+            .collect();
+        self.prev_at = Some(now);
+        result
+    }
+}
+
+/// Depth-first walk from `roots`: (pid, depth), parents first
+fn walk(roots: &[u32], children: &HashMap<u32, Vec<u32>>) -> Vec<(u32, u32)> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    let mut stack: Vec<(u32, u32)> = roots.iter().rev().map(|pid| (*pid, 0)).collect();
+    while let Some((pid, depth)) = stack.pop() {
+        if !seen.insert(pid) {
+            continue;
+        }
+        out.push((pid, depth));
+        if let Some(kids) = children.get(&pid) {
+            stack.extend(kids.iter().rev().map(|kid| (*kid, depth + 1)));
+        }
+    }
+    out
+}
+
+/// All processes descending from `roots`, excluding the roots themselves
+pub fn descendants(roots: &[u32]) -> HashSet<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for p in read_processes() {
+        children.entry(p.ppid).or_default().push(p.pid);
+    }
+    walk(roots, &children)
+        .into_iter()
+        .filter(|(_, depth)| *depth > 0)
+        .map(|(pid, _)| pid)
+        .collect()
+}
+
+/// Send SIGTERM (or SIGKILL with `force`) to a process
+#[cfg(unix)]
+pub fn signal_process(pid: u32, force: bool) -> Result<(), String> {
+    let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+    if unsafe { libc::kill(pid as i32, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to signal process {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
+#[cfg(not(unix))]
+pub fn signal_process(_pid: u32, _force: bool) -> Result<(), String> {
+    Err("Killing individual processes is not supported on this platform".to_string())
+}
+
+/// Parse `/proc/<pid>/stat`: (pid, name, state, ppid, utime + stime ticks, rss pages).
+/// The name is in parentheses and may itself contain spaces or parentheses.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_stat(stat: &str) -> Option<(u32, String, String, u32, u64, u64)> {
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let pid = stat[..open].trim().parse().ok()?;
+    let name = stat[open + 1..close].to_string();
+    let fields: Vec<&str> = stat.get(close + 1..)?.split_whitespace().collect();
+    // fields[0] is field 3 of proc(5)
+    let state = fields.first()?.to_string();
+    let ppid = fields.get(1)?.parse().ok()?;
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    let rss: i64 = fields.get(21)?.parse().ok()?;
+    Some((pid, name, state, ppid, utime + stime, rss.max(0) as u64))
+}
+
+/// Listening sockets in `/proc/net/tcp{,6}`: (inode, port)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_net_tcp(content: &str) -> Vec<(u64, u16)> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // st 0A = TCP_LISTEN
+            if fields.get(3) != Some(&"0A") {
+                return None;
+            }
+            let port = u16::from_str_radix(fields.get(1)?.rsplit(':').next()?, 16).ok()?;
+            let inode = fields.get(9)?.parse().ok()?;
+            Some((inode, port))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn read_processes() -> Vec<RawProcess> {
+    let ticks_per_sec = match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+        n if n > 0 => n as f64,
+        _ => 100.0,
+    };
+    let page_size = match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+        n if n > 0 => n as u64,
+        _ => 4096,
+    };
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            entry.file_name().to_str()?.parse::<u32>().ok()?;
+            let stat = std::fs::read_to_string(entry.path().join("stat")).ok()?;
+            let (pid, name, state, ppid, ticks, rss_pages) = parse_proc_stat(&stat)?;
+            let cmdline = std::fs::read(entry.path().join("cmdline")).unwrap_or_default();
+            let command = cmdline
+                .split(|b| *b == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            Some(RawProcess {
+                pid,
+                ppid,
+                command: if command.is_empty() { format!("[{}]", name) } else { command },
+                name,
+                state,
+                rss_bytes: rss_pages * page_size,
+                cpu_secs: Some(ticks as f64 / ticks_per_sec),
+                cpu_percent: None,
+            })
+        })
+        .collect()
+}
+
+/// Listening TCP ports of each of `pids`, matched through their socket fds
+#[cfg(target_os = "linux")]
+fn listening_ports(pids: &[u32]) -> HashMap<u32, Vec<u16>> {
+    let sockets: HashMap<u64, u16> = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|content| parse_proc_net_tcp(&content))
+        .collect();
+    let mut ports = HashMap::new();
+    if sockets.is_empty() {
+        return ports;
+    }
+    for pid in pids {
+        let Ok(fds) = std::fs::read_dir(format!("/proc/{}/fd", pid)) else {
+            continue;
+        };
+        let mut found: Vec<u16> = fds
+            .filter_map(|fd| {
+                let target = std::fs::read_link(fd.ok()?.path()).ok()?;
+                let inode = target.to_str()?.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok()?;
+                sockets.get(&inode).copied()
+            })
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        if !found.is_empty() {
+            ports.insert(*pid, found);
+        }
+    }
+    ports
+}
+
+/// Pop the next whitespace-separated field off `line`
+#[cfg(not(target_os = "linux"))]
+fn next_field(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start();
+    let end = line.find(char::is_whitespace).unwrap_or(line.len());
+    (end > 0).then(|| (&line[..end], &line[end..]))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_processes() -> Vec<RawProcess> {
+    let Ok(output) = std::process::Command::new("ps")
+        .args(["-axo", "pid=,ppid=,rss=,%cpu=,state=,args="])
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (pid, rest) = next_field(line)?;
+            let (ppid, rest) = next_field(rest)?;
+            let (rss_kb, rest) = next_field(rest)?;
+            let (cpu, rest) = next_field(rest)?;
+            let (state, rest) = next_field(rest)?;
+            let command = rest.trim().to_string();
+            let name = command
+                .split_whitespace()
+                .next()
+                .and_then(|exe| exe.rsplit('/').next())
+                .unwrap_or_default()
+                .to_string();
+            Some(RawProcess {
+                pid: pid.parse().ok()?,
+                ppid: ppid.parse().ok()?,
+                name,
+                state: state.chars().next()?.to_string(),
+                command,
+                rss_bytes: rss_kb.parse::<u64>().ok()? * 1024,
+                cpu_secs: None,
+                cpu_percent: cpu.parse().ok(),
+            })
+        })
+        .collect()
+}
+
+/// Listening TCP ports of each of `pids`, from `lsof`
+#[cfg(not(target_os = "linux"))]
+fn listening_ports(pids: &[u32]) -> HashMap<u32, Vec<u16>> {
+    let mut ports: HashMap<u32, Vec<u16>> = HashMap::new();
+    if pids.is_empty() {
+        return ports;
+    }
+    let Ok(output) = std::process::Command::new("lsof")
+        .args(["-nP", "-iTCP", "-sTCP:LISTEN", "-Fpn"])
+        .output()
+    else {
+        return ports;
+    };
+    // Field output: "p<pid>" then one "n<addr>:<port>" per socket
+    let mut current = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(pid) = line.strip_prefix('p') {
+            current = pid.parse::<u32>().ok().filter(|pid| pids.contains(pid));
+        } else if let (Some(pid), Some(addr)) = (current, line.strip_prefix('n')) {
+            if let Some(port) = addr.rsplit(':').next().and_then(|p| p.parse().ok()) {
+                let list = ports.entry(pid).or_default();
+                if !list.contains(&port) {
+                    list.push(port);
+                }
+            }
+        }
+    }
+    ports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_stat_and_listening_sockets() {
+        let stat = "4242 (node (dev) x) S 4200 4242 4200 34816 4242 4194304 1234 0 0 0 150 25 0 0 20 0 11 0 987654 1234567 8192 18446744073709551615";
+        let (pid, name, state, ppid, ticks, rss) = parse_proc_stat(stat).unwrap();
+        assert_eq!((pid, name.as_str(), state.as_str(), ppid), (4242, "node (dev) x", "S", 4200));
+        assert_eq!((ticks, rss), (175, 8192));
+
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 55501 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:A1B2 0100007F:0BB8 01 00000000:00000000 00:00000000 00000000  1000        0 55502 1 0000000000000000 20 4 30 10 -1";
+        assert_eq!(parse_proc_net_tcp(tcp), vec![(55501, 3000)]);
+
+        let children = HashMap::from([(1, vec![2, 3]), (2, vec![4]), (4, vec![1])]);
+        assert_eq!(walk(&[1], &children), vec![(1, 0), (2, 1), (4, 2), (3, 1)]);
+    }
+}
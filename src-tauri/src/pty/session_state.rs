@@ -0,0 +1,337 @@
+// Track Your Shit - Session State Detection
+// Classifies Claude PTY sessions as running, idle, awaiting input, errored or finished
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use regex::Regex;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+
+/// Recent output kept for prompt matching (characters)
+const TAIL_CHARS: usize = 4096;
+
+/// How often a tracker re-checks quiet sessions
+const TICK_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionState {
+    Running,
+    Idle,
+    AwaitingInput,
+    Errored,
+    Finished,
+}
+
+/// Compiled detection rules
+#[derive(Clone)]
+pub struct StatePatterns {
+    /// Matched against the output tail once the session goes quiet
+    pub awaiting_input: Vec<Regex>,
+    /// Matched against new output
+    pub error: Vec<Regex>,
+    /// Matched against new output
+    pub finished: Vec<Regex>,
+    /// Quiet time before a prompt match counts as awaiting input
+    pub input_settle: Duration,
+    /// Quiet time before a running session counts as idle
+    pub idle_timeout: Duration,
+}
+
+/// A state transition
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionStateChange {
+    pub session_id: String,
+    pub state: SessionState,
+    pub previous: SessionState,
+    /// The output that triggered the change, or the exit code
+    pub detail: Option<String>,
+}
+
+/// Strip ANSI escape sequences (CSI, OSC and two-byte escapes) from output
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            if c == '\r' || c == '\n' || c == '\t' || !c.is_control() {
+                out.push(c);
+            }
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                // CSI: parameters until a final byte in @..~
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                // OSC: until BEL or ESC \
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Charset designation: ESC ( B and friends
+            Some('(') | Some(')') => {
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Pure state machine over output and time
+pub struct SessionStateDetector {
+    patterns: StatePatterns,
+    state: SessionState,
+    tail: String,
+    last_output: Instant,
+}
+
+impl SessionStateDetector {
+    pub fn new(patterns: StatePatterns, now: Instant) -> Self {
+        Self {
+            patterns,
+            state: SessionState::Running,
+            tail: String::new(),
+            last_output: now,
+        }
+    }
+
+    pub fn state(&self) -> SessionState {
+        self.state
+    }
+
+    fn transition(&mut self, state: SessionState, detail: Option<String>) -> Option<(SessionState, Option<String>)> {
+        if state == self.state {
+            return None;
+        }
+        let previous = self.state;
+        self.state = state;
+        Some((previous, detail))
+    }
+
+    /// Feed output; returns (previous state, detail) on a change
+    pub fn feed(&mut self, data: &[u8], now: Instant) -> Option<(SessionState, Option<String>)> {
+        if self.state == SessionState::Finished {
+            return None;
+        }
+        let text = strip_ansi(&String::from_utf8_lossy(data));
+        if text.trim().is_empty() {
+            return None;
+        }
+        self.last_output = now;
+        // Output after a prompt means it was answered; don't match it again
+        if self.state == SessionState::AwaitingInput {
+            self.tail.clear();
+        }
+
+        // Match new text with a little of the old tail, for patterns split across reads
+        let overlap_start = self.tail.char_indices().rev().nth(255).map_or(0, |(i, _)| i);
+        let window = format!("{}{}", &self.tail[overlap_start..], text);
+        self.tail.push_str(&text);
+        if self.tail.len() > TAIL_CHARS * 2 {
+            let cut = self.tail.char_indices().rev().nth(TAIL_CHARS - 1).map_or(0, |(i, _)| i);
+            self.tail.drain(..cut);
+        }
+
+        let matched = |patterns: &[Regex]| {
+            patterns
+                .iter()
+                .find_map(|re| re.find(&window).map(|m| m.as_str().trim().to_string()))
+        };
+        if let Some(detail) = matched(&self.patterns.error) {
+            self.tail.clear();
+            return self.transition(SessionState::Errored, Some(detail));
+        }
+        if let Some(detail) = matched(&self.patterns.finished) {
+            self.tail.clear();
+            return self.transition(SessionState::Finished, Some(detail));
+        }
+        match self.state {
+            // Spinner redraws while errored don't mean the session recovered;
+            // a new line of output does
+            SessionState::Errored if !text.contains('\n') => None,
+            _ => self.transition(SessionState::Running, None),
+        }
+    }
+
+    /// Re-check a quiet session
+    pub fn tick(&mut self, now: Instant) -> Option<(SessionState, Option<String>)> {
+        if !matches!(self.state, SessionState::Running | SessionState::Idle | SessionState::Errored) {
+            return None;
+        }
+        let quiet = now.saturating_duration_since(self.last_output);
+        if quiet >= self.patterns.input_settle {
+            let start = self.tail.char_indices().rev().nth(1023).map_or(0, |(i, _)| i);
+            let recent = &self.tail[start..];
+            if let Some(detail) = self
+                .patterns
+                .awaiting_input
+                .iter()
+                .find_map(|re| re.find(recent).map(|m| m.as_str().trim().to_string()))
+            {
+                return self.transition(SessionState::AwaitingInput, Some(detail));
+            }
+        }
+        // An error stays visible until output or a prompt follows it
+        if quiet >= self.patterns.idle_timeout && self.state != SessionState::Errored {
+            return self.transition(SessionState::Idle, None);
+        }
+        None
+    }
+
+    /// The process exited
+    pub fn exit(&mut self, exit_code: Option<i32>) -> Option<(SessionState, Option<String>)> {
+        match exit_code {
+            Some(code) if code != 0 => {
+                self.transition(SessionState::Errored, Some(format!("Exited with code {}", code)))
+            }
+            Some(_) => self.transition(SessionState::Finished, Some("Exited with code 0".to_string())),
+            None => self.transition(SessionState::Finished, None),
+        }
+    }
+}
+
+/// Receives state changes (emits events, creates notifications)
+pub type StateSink = Arc<dyn Fn(&SessionStateChange) + Send + Sync>;
+
+/// Shared detector for one session, fed by the reader thread and ticked by
+/// its own timer thread until the session exits or is closed
+pub struct SessionStateTracker {
+    session_id: String,
+    detector: StdMutex<SessionStateDetector>,
+    sink: StateSink,
+    closed: std::sync::atomic::AtomicBool,
+}
+
+impl SessionStateTracker {
+    pub fn new(session_id: &str, patterns: StatePatterns, sink: StateSink) -> Arc<Self> {
+        let tracker = Arc::new(Self {
+            session_id: session_id.to_string(),
+            detector: StdMutex::new(SessionStateDetector::new(patterns, Instant::now())),
+            sink,
+            closed: std::sync::atomic::AtomicBool::new(false),
+        });
+        let ticker = Arc::downgrade(&tracker);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(TICK_INTERVAL);
+            let Some(tracker) = ticker.upgrade() else { break };
+            if tracker.is_closed() {
+                break;
+            }
+            tracker.apply(|d| d.tick(Instant::now()));
+        });
+        tracker
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    fn apply(&self, step: impl FnOnce(&mut SessionStateDetector) -> Option<(SessionState, Option<String>)>) {
+        if self.is_closed() {
+            return;
+        }
+        let change = match self.detector.lock() {
+            Ok(mut detector) => step(&mut detector).map(|(previous, detail)| SessionStateChange {
+                session_id: self.session_id.clone(),
+                state: detector.state(),
+                previous,
+                detail,
+            }),
+            Err(_) => None,
+        };
+        if let Some(change) = change {
+            (self.sink)(&change);
+        }
+    }
+
+    pub fn feed(&self, data: &[u8]) {
+        self.apply(|d| d.feed(data, Instant::now()));
+    }
+
+    /// Report the process exit; tracking stops afterwards
+    pub fn exit(&self, exit_code: Option<i32>) {
+        self.apply(|d| d.exit(exit_code));
+        self.close();
+    }
+
+    /// Stop tracking without a state change (session closed or detached)
+    pub fn close(&self) {
+        self.closed.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn state(&self) -> Option<SessionState> {
+        self.detector.lock().ok().map(|d| d.state())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns() -> StatePatterns {
+        StatePatterns {
+            awaiting_input: vec![Regex::new(r"(?i)do you want to proceed\?").unwrap()],
+            error: vec![Regex::new(r"API Error: \d+").unwrap()],
+            finished: vec![],
+            input_settle: Duration::from_secs(2),
+            idle_timeout: Duration::from_secs(30),
+        }
+    }
+
+    #[test]
+    fn classifies_prompt_idle_error_and_exit() {
+        let start = Instant::now();
+        let mut d = SessionStateDetector::new(patterns(), start);
+
+        assert!(d.feed(b"\x1b[1mEditing src/lib.rs\x1b[0m", start).is_none());
+        assert_eq!(d.feed(b"Do you want to pro", start), None);
+        d.feed(b"ceed?\r\n \x1b[36m\xe2\x9d\xaf 1. Yes\x1b[0m", start);
+        assert!(d.tick(start + Duration::from_secs(1)).is_none());
+        let (previous, detail) = d.tick(start + Duration::from_secs(3)).unwrap();
+        assert_eq!((previous, d.state()), (SessionState::Running, SessionState::AwaitingInput));
+        assert_eq!(detail.as_deref(), Some("Do you want to proceed?"));
+
+        let later = start + Duration::from_secs(10);
+        assert_eq!(d.feed(b"Writing file...", later).unwrap().0, SessionState::AwaitingInput);
+        assert!(d.tick(later + Duration::from_secs(29)).is_none());
+        d.tick(later + Duration::from_secs(31));
+        assert_eq!(d.state(), SessionState::Idle);
+
+        d.feed(b"  \x1b[31mAPI Error: 529\x1b[0m overloaded", later);
+        assert_eq!(d.state(), SessionState::Errored);
+
+        let (previous, detail) = d.exit(Some(0)).unwrap();
+        assert_eq!((previous, d.state()), (SessionState::Errored, SessionState::Finished));
+        assert_eq!(detail.as_deref(), Some("Exited with code 0"));
+    }
+
+    #[test]
+    fn recovers_from_error_on_later_output() {
+        let start = Instant::now();
+        let mut d = SessionStateDetector::new(patterns(), start);
+
+        d.feed(b"API Error: 529 overloaded\r\n", start);
+        assert_eq!(d.state(), SessionState::Errored);
+        assert!(d.feed(b"\r\xe2\x9c\xbb Retrying", start).is_none());
+        assert!(d.tick(start + Duration::from_secs(60)).is_none());
+
+        let later = start + Duration::from_secs(61);
+        let (previous, _) = d.feed(b"Retried request\r\nDo you want to proceed?", later).unwrap();
+        assert_eq!((previous, d.state()), (SessionState::Errored, SessionState::Running));
+        let (previous, detail) = d.tick(later + Duration::from_secs(3)).unwrap();
+        assert_eq!((previous, d.state()), (SessionState::Running, SessionState::AwaitingInput));
+        assert_eq!(detail.as_deref(), Some("Do you want to proceed?"));
+    }
+}
@@ -0,0 +1,364 @@
+// Track Your Shit - Shell Integration
+// OSC 133 prompt markers and OSC 7 cwd reporting for bash, zsh and fish
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use portable_pty::CommandBuilder;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// Longest OSC payload we buffer before giving up on the sequence
+const MAX_OSC_LEN: usize = 8192;
+
+const BASH_SCRIPT: &str = r#"# Track Your Shit shell integration (bash)
+# --rcfile replaces the usual startup files, so load them here when asked
+if [ -n "$TYS_LOGIN" ]; then
+    [ -r /etc/profile ] && . /etc/profile
+    for __tys_rc in ~/.bash_profile ~/.bash_login ~/.profile; do
+        if [ -r "$__tys_rc" ]; then . "$__tys_rc"; break; fi
+    done
+    unset __tys_rc
+elif [ -n "$TYS_LOAD_RC" ]; then
+    [ -r ~/.bashrc ] && . ~/.bashrc
+fi
+unset TYS_LOGIN TYS_LOAD_RC
+__tys_prompt() {
+    local ret=$?
+    printf '\033]133;D;%s\007\033]7;file://%s%s\007' "$ret" "$HOSTNAME" "$PWD"
+}
+__tys_preexec() {
+    local cmd
+    cmd=$(HISTTIMEFORMAT= builtin history 1)
+    cmd="${cmd#"${cmd%%[![:space:]]*}"}"
+    cmd="${cmd#*[0-9]  }"
+    printf '\033]133;C;cmdline=%s\007' "${cmd//[$'\a\e']/}"
+}
+PROMPT_COMMAND="__tys_prompt${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+PS0='$(__tys_preexec)'
+PS1="\[\033]133;A\007\]${PS1}\[\033]133;B\007\]"
+"#;
+
+/// Sources the user's own copy of a zsh startup file when rc files are
+/// wanted (ZDOTDIR points at our directory, so zsh won't find them itself)
+const ZSH_LOADER: &str = r#"if [[ -n $TYS_LOAD_RC && -r ${TYS_USER_ZDOTDIR:-$HOME}/FILE ]]; then
+    __tys_zdotdir=$ZDOTDIR
+    ZDOTDIR=${TYS_USER_ZDOTDIR:-$HOME}
+    source "${TYS_USER_ZDOTDIR:-$HOME}/FILE"
+    ZDOTDIR=$__tys_zdotdir
+fi
+"#;
+
+const ZSH_SCRIPT: &str = r#"# Track Your Shit shell integration (zsh)
+__tys_precmd() {
+    local ret=$?
+    printf '\033]133;D;%s\007\033]7;file://%s%s\007' "$ret" "$HOST" "$PWD"
+}
+__tys_preexec() {
+    printf '\033]133;C;cmdline=%s\007' "${1//[$'\a\e']/}"
+}
+autoload -Uz add-zsh-hook
+add-zsh-hook precmd __tys_precmd
+add-zsh-hook preexec __tys_preexec
+PS1=$'%{\e]133;A\a%}'"${PS1}"$'%{\e]133;B\a%}'
+# Hand ZDOTDIR back so .zlogin and nested shells use the user's files
+if [[ -n $TYS_USER_ZDOTDIR ]]; then ZDOTDIR=$TYS_USER_ZDOTDIR; else unset ZDOTDIR; fi
+unset TYS_LOAD_RC TYS_USER_ZDOTDIR __tys_zdotdir
+"#;
+
+const FISH_SCRIPT: &str = r#"# Track Your Shit shell integration (fish)
+function __tys_preexec --on-event fish_preexec
+    printf '\e]133;C;cmdline=%s\a' (string replace -ra '[\a\e]' '' -- $argv[1] | string join ' ')
+end
+function __tys_postexec --on-event fish_postexec
+    printf '\e]133;D;%s\a' $status
+end
+function __tys_prompt --on-event fish_prompt
+    printf '\e]7;file://%s%s\a\e]133;A\a' $hostname $PWD
+end
+"#;
+
+/// Shells we know how to inject integration into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl ShellKind {
+    pub fn detect(shell: &str) -> Option<Self> {
+        if shell.ends_with("bash") {
+            Some(Self::Bash)
+        } else if shell.ends_with("zsh") {
+            Some(Self::Zsh)
+        } else if shell.ends_with("fish") {
+            Some(Self::Fish)
+        } else {
+            None
+        }
+    }
+}
+
+/// Write the integration scripts to our private dir, once per app launch
+fn integration_dir() -> Result<PathBuf, String> {
+    static WRITTEN: OnceLock<PathBuf> = OnceLock::new();
+    if let Some(dir) = WRITTEN.get() {
+        return Ok(dir.clone());
+    }
+    let dir = super::private_dir("shell-integration")?;
+    std::fs::create_dir_all(dir.join("zsh")).map_err(|e| e.to_string())?;
+    let zsh_loader = |file: &str| ZSH_LOADER.replace("FILE", file);
+    for (path, script) in [
+        (dir.join("bash-integration.sh"), BASH_SCRIPT.to_string()),
+        (dir.join("zsh").join(".zshenv"), zsh_loader(".zshenv")),
+        (dir.join("zsh").join(".zprofile"), zsh_loader(".zprofile")),
+        (dir.join("zsh").join(".zshrc"), zsh_loader(".zshrc") + ZSH_SCRIPT),
+        (dir.join("fish-integration.fish"), FISH_SCRIPT.to_string()),
+    ] {
+        std::fs::write(&path, script).map_err(|e| e.to_string())?;
+    }
+    Ok(WRITTEN.get_or_init(|| dir).clone())
+}
+
+/// Add the args/env that start an interactive `shell` with integration
+/// loaded. The user's rc files are loaded only with `load_rc_files`; `login`
+/// is for bash, whose login flag the caller drops (bash ignores `--rcfile`
+/// in login shells, so the script sources the profile files instead).
+pub fn apply(
+    cmd: &mut CommandBuilder,
+    shell: ShellKind,
+    load_rc_files: bool,
+    login: bool,
+) -> Result<(), String> {
+    let dir = integration_dir()?;
+    if load_rc_files {
+        cmd.env("TYS_LOAD_RC", "1");
+    }
+    match shell {
+        ShellKind::Bash => {
+            if load_rc_files && login {
+                cmd.env("TYS_LOGIN", "1");
+            }
+            cmd.arg("--noprofile");
+            cmd.arg("--rcfile");
+            cmd.arg(dir.join("bash-integration.sh"));
+        }
+        ShellKind::Zsh => {
+            if !load_rc_files {
+                cmd.arg("--no-globalrcs");
+            } else if let Ok(zdotdir) = std::env::var("ZDOTDIR") {
+                cmd.env("TYS_USER_ZDOTDIR", zdotdir);
+            }
+            cmd.env("ZDOTDIR", dir.join("zsh"));
+        }
+        ShellKind::Fish => {
+            cmd.arg("--init-command");
+            cmd.arg(format!(
+                "source '{}'",
+                dir.join("fish-integration.fish").to_string_lossy().replace('\'', "\\'")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A shell integration marker decoded from the output stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShellEvent {
+    /// OSC 133;A
+    PromptStart,
+    /// OSC 133;B
+    CommandStart,
+    /// OSC 133;C, with the command line when the shell reports it
+    CommandExecuted(Option<String>),
+    /// OSC 133;D, with the exit code when reported
+    CommandFinished(Option<i32>),
+    /// OSC 7 working directory
+    Cwd(String),
+}
+
+#[derive(Default)]
+enum OscState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+}
+
+/// Incremental OSC scanner. Sequences may be split across reads; the bytes
+/// themselves are left in the stream for xterm.js, which ignores them.
+#[derive(Default)]
+pub struct OscParser {
+    state: OscState,
+    payload: Vec<u8>,
+}
+
+impl OscParser {
+    pub fn feed(&mut self, data: &[u8]) -> Vec<ShellEvent> {
+        let mut events = Vec::new();
+        for &byte in data {
+            self.state = match (&self.state, byte) {
+                (OscState::Ground, 0x1b) => OscState::Escape,
+                (OscState::Ground, _) => OscState::Ground,
+                (OscState::Escape, b']') => {
+                    self.payload.clear();
+                    OscState::Osc
+                }
+                (OscState::Escape, 0x1b) => OscState::Escape,
+                (OscState::Escape, _) => OscState::Ground,
+                (OscState::Osc, 0x07) => {
+                    events.extend(parse_osc(&self.payload));
+                    OscState::Ground
+                }
+                (OscState::Osc, 0x1b) => OscState::OscEscape,
+                (OscState::Osc, _) if self.payload.len() >= MAX_OSC_LEN => OscState::Ground,
+                (OscState::Osc, _) => {
+                    self.payload.push(byte);
+                    OscState::Osc
+                }
+                (OscState::OscEscape, b'\\') => {
+                    events.extend(parse_osc(&self.payload));
+                    OscState::Ground
+                }
+                // Any other escape aborts the OSC and may start a new sequence
+                (OscState::OscEscape, b']') => {
+                    self.payload.clear();
+                    OscState::Osc
+                }
+                (OscState::OscEscape, _) => OscState::Ground,
+            };
+        }
+        events
+    }
+}
+
+fn parse_osc(payload: &[u8]) -> Option<ShellEvent> {
+    let payload = String::from_utf8_lossy(payload);
+    if let Some(rest) = payload.strip_prefix("133;") {
+        let mut parts = rest.splitn(2, ';');
+        let kind = parts.next()?;
+        let params = parts.next();
+        return match kind {
+            "A" => Some(ShellEvent::PromptStart),
+            "B" => Some(ShellEvent::CommandStart),
+            "C" => Some(ShellEvent::CommandExecuted(params.and_then(|p| {
+                if let Some(url) = p.strip_prefix("cmdline_url=") {
+                    Some(percent_decode(url))
+                } else {
+                    p.strip_prefix("cmdline=").map(String::from)
+                }
+            }))),
+            "D" => Some(ShellEvent::CommandFinished(
+                params.and_then(|p| p.split(';').next()?.trim().parse().ok()),
+            )),
+            _ => None,
+        };
+    }
+    let url = payload.strip_prefix("7;")?;
+    let rest = url.strip_prefix("file://")?;
+    // Drop the host part: file://host/path
+    let path = &rest[rest.find('/')?..];
+    Some(ShellEvent::Cwd(percent_decode(path)))
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                out.push(hi << 4 | lo);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// A command delimited by OSC 133 C/D markers
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FinishedCommand {
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub cwd: Option<String>,
+    pub started_at: String,
+    pub duration_ms: i64,
+}
+
+/// Turns the marker stream of one session into finished commands
+#[derive(Default)]
+pub struct CommandTracker {
+    parser: OscParser,
+    cwd: Option<String>,
+    running: Option<(String, Option<String>, chrono::DateTime<chrono::Utc>, Instant)>,
+}
+
+impl CommandTracker {
+    pub fn feed(&mut self, data: &[u8]) -> Vec<FinishedCommand> {
+        let mut finished = Vec::new();
+        for event in self.parser.feed(data) {
+            match event {
+                ShellEvent::Cwd(path) => self.cwd = Some(path),
+                ShellEvent::CommandExecuted(Some(command)) if !command.trim().is_empty() => {
+                    self.running = Some((
+                        command.trim().to_string(),
+                        self.cwd.clone(),
+                        chrono::Utc::now(),
+                        Instant::now(),
+                    ));
+                }
+                ShellEvent::CommandFinished(exit_code) => {
+                    if let Some((command, cwd, started_at, started)) = self.running.take() {
+                        finished.push(FinishedCommand {
+                            command,
+                            exit_code,
+                            cwd,
+                            started_at: started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                            duration_ms: started.elapsed().as_millis() as i64,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_markers_split_across_reads() {
+        let stream = b"\x1b]7;file://host/home/me/my%20proj\x07\x1b]133;A\x07proj $ \x1b]133;B\x07\x1b]133;C;cmdline=cargo test\x07running...\r\n\x1b]133;D;101\x1b\\";
+        let mut tracker = CommandTracker::default();
+        let mut finished = Vec::new();
+        for chunk in stream.chunks(5) {
+            finished.extend(tracker.feed(chunk));
+        }
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].command, "cargo test");
+        assert_eq!(finished[0].exit_code, Some(101));
+        assert_eq!(finished[0].cwd.as_deref(), Some("/home/me/my proj"));
+    }
+
+    #[test]
+    fn ignores_finish_without_command() {
+        let mut parser = OscParser::default();
+        assert_eq!(
+            parser.feed(b"\x1b]133;C;cmdline_url=ls%20-la\x07\x1b[31m\x1b]0;title\x07"),
+            vec![ShellEvent::CommandExecuted(Some("ls -la".to_string()))]
+        );
+
+        // Empty prompt line: D arrives with no C before it
+        let mut tracker = CommandTracker::default();
+        assert!(tracker.feed(b"\x1b]133;D;0\x07\x1b]133;A\x07").is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+// Track Your Shit - tmux Windows and Panes
+// Splits, windows and pane capture inside a ct-* tmux session
+// Copyright (c) 2026 Jeremy McSpadden <jeremy@fluxlabs.net>
+
+use super::TerminalManager;
+
+/// `list-panes` fields, tab separated; the path goes last since it may contain anything
+const PANE_FORMAT: &str = "#{window_id}\t#{window_index}\t#{window_name}\t#{window_active}\t#{pane_id}\t#{pane_index}\t#{pane_active}\t#{pane_dead}\t#{pane_pid}\t#{pane_width}\t#{pane_height}\t#{pane_current_command}\t#{pane_current_path}";
+
+/// A pane inside a tmux window
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct TmuxPane {
+    /// tmux pane ID (`%3`)
+    pub id: String,
+    pub index: u32,
+    pub active: bool,
+    /// The pane's process exited (only with `remain-on-exit`)
+    pub dead: bool,
+    pub pid: Option<u32>,
+    pub width: u16,
+    pub height: u16,
+    /// Foreground command, e.g. `zsh`, `node`, `claude`
+    pub current_command: String,
+    pub current_path: String,
+}
+
+/// A window of a tmux session with its panes
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct TmuxWindow {
+    /// tmux window ID (`@1`)
+    pub id: String,
+    pub index: u32,
+    pub name: String,
+    pub active: bool,
+    pub panes: Vec<TmuxPane>,
+}
+
+/// How to split a pane
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    /// Side by side (`split-window -h`)
+    Horizontal,
+    /// Stacked (`split-window -v`)
+    Vertical,
+}
+
+/// Group `list-panes -s` output into windows, in tmux's order
+fn parse_panes(output: &str) -> Vec<TmuxWindow> {
+    let mut windows: Vec<TmuxWindow> = Vec::new();
+    for line in output.lines() {
+        let fields: Vec<&str> = line.splitn(13, '\t').collect();
+        if fields.len() < 13 {
+            continue;
+        }
+        let pane = TmuxPane {
+            id: fields[4].to_string(),
+            index: fields[5].parse().unwrap_or(0),
+            active: fields[6] == "1",
+            dead: fields[7] == "1",
+            pid: fields[8].parse().ok(),
+            width: fields[9].parse().unwrap_or(0),
+            height: fields[10].parse().unwrap_or(0),
+            current_command: fields[11].to_string(),
+            current_path: fields[12].to_string(),
+        };
+        match windows.iter_mut().find(|w| w.id == fields[0]) {
+            Some(window) => window.panes.push(pane),
+            None => windows.push(TmuxWindow {
+                id: fields[0].to_string(),
+                index: fields[1].parse().unwrap_or(0),
+                name: fields[2].to_string(),
+                active: fields[3] == "1",
+                panes: vec![pane],
+            }),
+        }
+    }
+    windows
+}
+
+/// Windows and panes of a tmux session
+pub fn list_windows(tmux_name: &str) -> Result<Vec<TmuxWindow>, String> {
+    TerminalManager::run_tmux(&["list-panes", "-s", "-t", tmux_name, "-F", PANE_FORMAT]).map(|out| parse_panes(&out))
+}
+
+/// tmux IDs are global, so make sure a window or pane belongs to this session
+fn ensure_in_session(tmux_name: &str, target: &str) -> Result<Vec<TmuxWindow>, String> {
+    let windows = list_windows(tmux_name)?;
+    let found = windows
+        .iter()
+        .any(|w| w.id == target || w.panes.iter().any(|p| p.id == target));
+    if !found {
+        return Err(format!("{} is not a window or pane of {}", target, tmux_name));
+    }
+    Ok(windows)
+}
+
+/// Type a command into a fresh shell pane, so the pane outlives it
+fn send_command(pane_id: &str, command: Option<&str>) -> Result<(), String> {
+    if let Some(command) = command.filter(|c| !c.trim().is_empty()) {
+        TerminalManager::run_tmux(&["send-keys", "-t", pane_id, "-l", command])?;
+        TerminalManager::run_tmux(&["send-keys", "-t", pane_id, "Enter"])?;
+    }
+    Ok(())
+}
+
+/// Split a pane (default: the session's active pane), optionally running a
+/// command in the new pane. Returns the new pane's ID.
+pub fn split_pane(
+    tmux_name: &str,
+    pane_id: Option<&str>,
+    direction: SplitDirection,
+    working_dir: Option<&str>,
+    command: Option<&str>,
+) -> Result<String, String> {
+    if let Some(pane_id) = pane_id {
+        ensure_in_session(tmux_name, pane_id)?;
+    }
+    let target = pane_id.unwrap_or(tmux_name);
+    let flag = match direction {
+        SplitDirection::Horizontal => "-h",
+        SplitDirection::Vertical => "-v",
+    };
+    let mut args = vec!["split-window", flag, "-t", target, "-P", "-F", "#{pane_id}"];
+    if let Some(dir) = working_dir {
+        args.extend(["-c", dir]);
+    }
+    let new_pane = TerminalManager::run_tmux(&args)?;
+    send_command(&new_pane, command)?;
+    Ok(new_pane)
+}
+
+/// Create a window and switch to it, optionally running a command in it.
+/// Returns the new window's ID.
+pub fn new_window(
+    tmux_name: &str,
+    name: Option<&str>,
+    working_dir: Option<&str>,
+    command: Option<&str>,
+) -> Result<String, String> {
+    // A trailing colon targets the next free index in the session
+    let target = format!("{}:", tmux_name);
+    let mut args = vec!["new-window", "-t", &target, "-P", "-F", "#{window_id}\t#{pane_id}"];
+    if let Some(name) = name {
+        args.extend(["-n", name]);
+    }
+    if let Some(dir) = working_dir {
+        args.extend(["-c", dir]);
+    }
+    let out = TerminalManager::run_tmux(&args)?;
+    let (window_id, pane_id) = out
+        .split_once('\t')
+        .ok_or_else(|| format!("Unexpected tmux output: {}", out))?;
+    send_command(pane_id, command)?;
+    Ok(window_id.to_string())
+}
+
+/// Switch the session to a window
+pub fn select_window(tmux_name: &str, window_id: &str) -> Result<(), String> {
+    ensure_in_session(tmux_name, window_id)?;
+    TerminalManager::run_tmux(&["select-window", "-t", window_id]).map(|_| ())
+}
+
+/// Focus a pane, switching to its window
+pub fn select_pane(tmux_name: &str, pane_id: &str) -> Result<(), String> {
+    ensure_in_session(tmux_name, pane_id)?;
+    TerminalManager::run_tmux(&["select-window", "-t", pane_id])?;
+    TerminalManager::run_tmux(&["select-pane", "-t", pane_id]).map(|_| ())
+}
+
+/// A pane's contents as text: the last `lines` of scrollback (all of it by
+/// default), with colour escapes when `escapes` is set
+pub fn capture_pane(tmux_name: &str, pane_id: &str, lines: Option<u32>, escapes: bool) -> Result<String, String> {
+    ensure_in_session(tmux_name, pane_id)?;
+    let start = lines.map_or("-".to_string(), |n| format!("-{}", n));
+    let mut args = vec!["capture-pane", "-p", "-J", "-t", pane_id, "-S", &start];
+    if escapes {
+        args.push("-e");
+    }
+    TerminalManager::run_tmux_capture(&args)
+}
+
+/// Close a pane (or a whole window). The session's last pane can't be
+/// closed this way since that ends the terminal.
+pub fn kill_target(tmux_name: &str, target: &str) -> Result<(), String> {
+    let windows = ensure_in_session(tmux_name, target)?;
+    let is_window = windows.iter().any(|w| w.id == target);
+    let remaining: usize = windows
+        .iter()
+        .filter(|w| !(is_window && w.id == target))
+        .map(|w| w.panes.iter().filter(|p| p.id != target).count())
+        .sum();
+    if remaining == 0 {
+        return Err("That is the session's last pane; close the terminal instead".to_string());
+    }
+    let command = if is_window { "kill-window" } else { "kill-pane" };
+    TerminalManager::run_tmux(&[command, "-t", target]).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_panes_into_windows() {
+        let output = "@1\t0\tzsh\t0\t%1\t0\t0\t0\t4242\t80\t24\tzsh\t/home/dev/app\n\
+                      @1\t0\tzsh\t0\t%4\t1\t1\t0\t4300\t79\t24\tnode\t/home/dev/app/web\n\
+                      @2\t1\tclaude\t1\t%2\t0\t1\t1\t\t160\t48\tclaude\t/tmp/a\tb";
+        let windows = parse_panes(output);
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].panes.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), ["%1", "%4"]);
+        assert_eq!(windows[0].panes[1].current_command, "node");
+        assert!(windows[0].panes[1].active && !windows[0].active);
+
+        let claude = &windows[1];
+        assert_eq!((claude.name.as_str(), claude.index, claude.active), ("claude", 1, true));
+        assert_eq!(claude.panes[0].pid, None);
+        assert!(claude.panes[0].dead);
+        assert_eq!(claude.panes[0].current_path, "/tmp/a\tb");
+    }
+}
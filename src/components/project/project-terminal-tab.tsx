@@ -47,7 +47,7 @@ import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card';
 import { type TerminalViewRef } from '@/components/terminal';
 import { cn } from '@/lib/utils';
 
-import { useScriptFavorites, useToggleScriptFavorite, useAddCommandHistory } from '@/lib/queries';
+import { useScriptFavorites, useToggleScriptFavorite, useAddCommandHistory, useSettings } from '@/lib/queries';
 import { CommandHistoryDropdown } from './command-history-dropdown';
 import { SnippetsPanel } from './snippets-panel';
 import { AutoCommandsSettings } from './auto-commands-settings';
@@ -147,6 +147,9 @@ export function ProjectTerminalTab({
   const { data: favorites } = useScriptFavorites(projectId);
   const toggleFavorite = useToggleScriptFavorite();
   const addHistory = useAddCommandHistory();
+  const { data: settings } = useSettings();
+  // Native terminals with shell integration record each command themselves
+  const shellRecordsCommands = !!settings?.shell_integration && !settings.use_tmux;
 
   const favoriteIds = new Set((favorites ?? []).map((f) => f.script_id));
   const favoriteScripts = scripts.filter((s) => favoriteIds.has(s.id) && s.id !== 'custom');
@@ -155,11 +158,11 @@ export function ProjectTerminalTab({
     (command: string) => {
       // Delegate execution to parent handler (project.tsx)
       onRunScript(command);
-      if (command) {
+      if (command && !shellRecordsCommands) {
         addHistory.mutate({ projectId, command, source: 'script' });
       }
     },
-    [onRunScript, addHistory, projectId],
+    [onRunScript, addHistory, projectId, shellRecordsCommands],
   );
 
   const handleRunCustomCommand = () => {
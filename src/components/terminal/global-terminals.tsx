@@ -69,6 +69,8 @@ export function GlobalTerminals({
       tabId: tab.id,
       label: tab.label,
       command: tab.command,
+      envProfileId: tab.envProfileId,
+      terminalProfileId: tab.terminalProfileId,
       sessionId: tab.sessionId,
       tmuxSession: tab.tmuxSession,
       workingDirectory,
@@ -85,7 +87,7 @@ export function GlobalTerminals({
 
   return (
     <>
-      {terminals.map(({ projectId, tabId, command, sessionId, tmuxSession, workingDirectory, split, splitSessionId, splitCommand }) => {
+      {terminals.map(({ projectId, tabId, command, envProfileId, terminalProfileId, sessionId, tmuxSession, workingDirectory, split, splitSessionId, splitCommand }) => {
         const isVisible = projectId === visibleProjectId && tabId === activeTabId;
         const isBroadcasting = broadcastMode && broadcastTabIds.has(tabId);
 
@@ -105,6 +107,8 @@ export function GlobalTerminals({
                     persistKey={`${projectId}:${tabId}`}
                     workingDirectory={workingDirectory}
                     command={command}
+                    envProfileId={envProfileId}
+                    terminalProfileId={terminalProfileId}
                     fontSize={terminalFontSize}
                     existingSessionId={sessionId}
                     tmuxSession={tmuxSession}
@@ -141,6 +145,8 @@ export function GlobalTerminals({
                 persistKey={`${projectId}:${tabId}`}
                 workingDirectory={workingDirectory}
                 command={command}
+                envProfileId={envProfileId}
+                terminalProfileId={terminalProfileId}
                 fontSize={terminalFontSize}
                 existingSessionId={sessionId}
                 tmuxSession={tmuxSession}
@@ -81,6 +81,10 @@ export interface InteractiveTerminalProps {
   workingDirectory: string;
   /** Optional command to run (default: user's shell) */
   command?: string;
+  /** Env profile to start with instead of the project's default */
+  envProfileId?: string;
+  /** Terminal profile to start with instead of the project's default */
+  terminalProfileId?: string;
   /** Existing PTY session ID to reconnect to */
   existingSessionId?: string | null;
   /** Existing tmux session name to reattach to on restart */
@@ -127,6 +131,8 @@ export const InteractiveTerminal = forwardRef<InteractiveTerminalRef, Interactiv
       persistKey,
       workingDirectory,
       command,
+      envProfileId,
+      terminalProfileId,
       existingSessionId,
       tmuxSession,
       onSessionCreated,
@@ -280,6 +286,8 @@ export const InteractiveTerminal = forwardRef<InteractiveTerminalRef, Interactiv
       const { sessionId, tmuxName } = await connect({
         workingDirectory,
         command,
+        envProfileId,
+        terminalProfileId,
         cols,
         rows,
       });
@@ -292,7 +300,7 @@ export const InteractiveTerminal = forwardRef<InteractiveTerminalRef, Interactiv
         terminalSessionIds.set(key, sessionId);
         terminalTmuxNames.set(key, tmuxName);
       }
-    }, [connect, ptyReconnect, reattachListeners, workingDirectory, command, existingSessionId, tmuxSession, onSessionCreated, onTmuxSessionCreated]);
+    }, [connect, ptyReconnect, reattachListeners, workingDirectory, command, envProfileId, terminalProfileId, existingSessionId, tmuxSession, onSessionCreated, onTmuxSessionCreated]);
 
     // Reconnect handler (user-initiated restart, skip cache check to force fresh connection)
     const reconnect = useCallback(async () => {
@@ -15,6 +15,10 @@ interface TerminalTab {
   id: string;
   label: string;
   command?: string;
+  /** Env profile the tab was explicitly started with */
+  envProfileId?: string;
+  /** Terminal profile the tab was explicitly started with */
+  terminalProfileId?: string;
   isExited: boolean;
   exitCode: number | null;
   /** PTY session ID for reconnection */
@@ -186,7 +190,9 @@ export function TerminalProvider({ children }: { children: ReactNode }) {
             const tabs: TerminalTab[] = projectSessions.map((s) => ({
               id: crypto.randomUUID(),
               label: s.tab_name,
-              command: tabTypeToCommand(s.tab_type),
+              command: s.command ?? tabTypeToCommand(s.tab_type),
+              envProfileId: s.env_profile_id ?? undefined,
+              terminalProfileId: s.terminal_profile_id ?? undefined,
               isExited: false,
               exitCode: null,
               sessionId: null, // Fresh PTY on restore (tmux reattach happens in InteractiveTerminal)
@@ -236,6 +242,9 @@ export function TerminalProvider({ children }: { children: ReactNode }) {
             working_directory: workDir,
             sort_order: sortOrder++,
             tmux_session: tab.tmuxSession ?? undefined,
+            command: tab.command,
+            env_profile_id: tab.envProfileId,
+            terminal_profile_id: tab.terminalProfileId,
           });
         }
       });
@@ -267,6 +276,9 @@ export function TerminalProvider({ children }: { children: ReactNode }) {
             working_directory: workDir,
             sort_order: sortOrder++,
             tmux_session: tab.tmuxSession ?? undefined,
+            command: tab.command,
+            env_profile_id: tab.envProfileId,
+            terminal_profile_id: tab.terminalProfileId,
           });
         }
       });
@@ -509,6 +521,9 @@ export function TerminalProvider({ children }: { children: ReactNode }) {
           working_directory: workDir,
           sort_order: sortOrder++,
           tmux_session: tab.tmuxSession ?? undefined,
+          command: tab.command,
+          env_profile_id: tab.envProfileId,
+          terminal_profile_id: tab.terminalProfileId,
         });
       }
     });
@@ -185,6 +185,8 @@ export function usePtySession(options: UsePtySessionOptions = {}): UsePtySession
       const result = await ptyCreate({
         workingDirectory: connectOptions.workingDirectory,
         command: connectOptions.command,
+        envProfileId: connectOptions.envProfileId,
+        terminalProfileId: connectOptions.terminalProfileId,
         cols: connectOptions.cols ?? 80,
         rows: connectOptions.rows ?? 24,
       });
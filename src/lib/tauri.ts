@@ -169,6 +169,13 @@ export interface Settings {
   debug_logging: boolean;
   // Terminal persistence
   use_tmux: boolean;
+  // Shell integration (native, non-tmux terminals only)
+  shell_integration: boolean;
+  // GSD sync
+  gsd_extract_knowledge: boolean;
+  // Job queue concurrency
+  job_max_concurrent: number;
+  job_max_per_project: number;
 }
 
 export interface ProjectDocs {
@@ -223,6 +230,10 @@ export interface CreatePtyOptions {
   cols: number;
   rows: number;
   sessionName?: string;
+  /** Env profile to use instead of the project's default */
+  envProfileId?: string;
+  /** Terminal profile to use instead of the project's default */
+  terminalProfileId?: string;
 }
 
 export interface CreatePtyResult {
@@ -244,6 +255,7 @@ export interface PtyOutputEvent {
 export interface PtyExitEvent {
   session_id: string;
   exit_code: number | null;
+  closed: boolean;
 }
 
 // Global Search Types
@@ -748,6 +760,9 @@ export interface TerminalSession {
   working_directory: string;
   sort_order: number;
   tmux_session: string | null;
+  command: string | null;
+  env_profile_id: string | null;
+  terminal_profile_id: string | null;
   created_at: string;
 }
 
@@ -758,6 +773,9 @@ export interface SaveTerminalSessionInput {
   working_directory: string;
   sort_order: number;
   tmux_session?: string;
+  command?: string;
+  env_profile_id?: string;
+  terminal_profile_id?: string;
 }
 
 export const saveTerminalSessions = (sessions: SaveTerminalSessionInput[]) =>
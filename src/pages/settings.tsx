@@ -183,6 +183,19 @@ export function SettingsPage() {
                     onCheckedChange={(checked) => handleChange("use_tmux", checked)}
                   />
                 </div>
+                <div className="flex items-center justify-between mt-3">
+                  <div>
+                    <Label htmlFor="settings-shell-integration" className="text-sm font-medium">Shell integration</Label>
+                    <p className="text-xs text-muted-foreground">
+                      Record commands, exit codes and cwd in native (non-tmux) terminals
+                    </p>
+                  </div>
+                  <Switch
+                    id="settings-shell-integration"
+                    checked={formData.shell_integration}
+                    onCheckedChange={(checked) => handleChange("shell_integration", checked)}
+                  />
+                </div>
               </div>
 
               {/* ── Secrets ───────────────────────── */}